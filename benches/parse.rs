@@ -0,0 +1,46 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use onenote_parser::Parser;
+use std::hint::black_box;
+use std::path::Path;
+
+fn bench_parse_section(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_section");
+
+    for name in [
+        "New Section 1.one",
+        "Schnelle Notizen.one",
+        "non-legacy/New Section 1 2.one",
+    ] {
+        let path = Path::new("tests/samples").join(name);
+        let data = std::fs::read(&path).unwrap();
+        let parser = Parser::new();
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                parser
+                    .parse_section_buffer(black_box(&data), &path)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_parse_notebook(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_notebook");
+
+    for name in ["Open Notebook.onetoc2", "non-legacy/Open Notebook.onetoc2"] {
+        let path = Path::new("tests/samples").join(name);
+        let parser = Parser::new();
+
+        group.bench_function(name, |b| {
+            b.iter(|| parser.parse_notebook(black_box(&path)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_section, bench_parse_notebook);
+criterion_main!(benches);
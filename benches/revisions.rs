@@ -0,0 +1,24 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use onenote_parser::Parser;
+use onenote_parser::fuzzing::synthetic_section;
+use std::hint::black_box;
+use std::path::Path;
+
+fn bench_shared_revisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shared_revisions");
+    let path = Path::new("Synthetic.one");
+
+    for (pages, revisions) in [(10, 50), (50, 10)] {
+        let data = synthetic_section(pages, revisions);
+        let parser = Parser::new();
+
+        group.bench_function(format!("{pages} pages, {revisions} revisions"), |b| {
+            b.iter(|| parser.parse_section_buffer(black_box(&data), path).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_shared_revisions);
+criterion_main!(benches);
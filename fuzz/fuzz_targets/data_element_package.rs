@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use onenote_parser::fuzzing;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = fuzzing::parse_data_element_package(data);
+});
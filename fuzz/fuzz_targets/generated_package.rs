@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use onenote_parser::fuzzing::{self, ArbitraryPackage};
+
+fuzz_target!(|package: ArbitraryPackage| {
+    let data = package.to_bytes();
+
+    fuzzing::parse_packaging(&data).expect("generated package is valid");
+    let _ = fuzzing::parse_section(&data);
+});
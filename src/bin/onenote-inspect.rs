@@ -0,0 +1,477 @@
+use onenote_parser::raw::{
+    CellId, DataElementKind, ExGuid, Object, ObjectSpace, Property, PropertyValue, RawFile,
+};
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, Write as _};
+use std::process;
+
+const USAGE: &str = "usage: onenote-inspect [--json] <file>";
+
+/// The maximum number of bytes of a byte array property to print.
+const MAX_BYTES: usize = 64;
+
+fn main() {
+    let mut json = false;
+    let mut path = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => fail(USAGE),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| fail(USAGE));
+    let data = fs::read(&path).unwrap_or_else(|err| fail(&format!("{path}: {err}")));
+    let file = RawFile::parse(&data).unwrap_or_else(|err| fail(&format!("{path}: {err}")));
+
+    let node = inspect(&file);
+
+    let mut out = String::new();
+    if json {
+        node.write_json(&mut out);
+        out.push('\n');
+    } else {
+        node.write_text(&mut out, 0);
+    }
+
+    // Ignore errors when the output is piped into a closed pipe (e.g. `head`)
+    let _ = io::stdout().write_all(out.as_bytes());
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(1);
+}
+
+/// A node of the output tree.
+enum Node {
+    Null,
+    Number(u64),
+    Bool(bool),
+    Str(String),
+    List(Vec<Node>),
+    Map(Vec<(&'static str, Node)>),
+}
+
+impl Node {
+    fn map() -> Node {
+        Node::Map(vec![])
+    }
+
+    fn with(mut self, key: &'static str, value: impl Into<Node>) -> Node {
+        if let Node::Map(entries) = &mut self {
+            entries.push((key, value.into()));
+        }
+
+        self
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Node::List(_) | Node::Map(_))
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, Node::List(items) if items.is_empty())
+    }
+
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Node::Null => out.push_str("null"),
+            Node::Number(value) => write!(out, "{value}").unwrap(),
+            Node::Bool(value) => write!(out, "{value}").unwrap(),
+            Node::Str(value) => write_json_str(out, value),
+            Node::List(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    item.write_json(out);
+                }
+                out.push(']');
+            }
+            Node::Map(entries) => {
+                out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_str(out, key);
+                    out.push(':');
+                    value.write_json(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+
+        match self {
+            Node::List(items) => {
+                for item in items {
+                    if item.is_scalar() {
+                        writeln!(out, "{indent}- {}", item.scalar_text()).unwrap();
+                    } else if let Some(record) = item.record_text() {
+                        writeln!(out, "{indent}- {record}").unwrap();
+                    } else {
+                        writeln!(out, "{indent}-").unwrap();
+                        item.write_text(out, depth + 1);
+                    }
+                }
+            }
+            Node::Map(entries) => {
+                for (key, value) in entries {
+                    match value {
+                        Node::Null => {}
+                        _ if value.is_empty() => {}
+                        _ if value.is_scalar() => {
+                            writeln!(out, "{indent}{key}: {}", value.scalar_text()).unwrap()
+                        }
+                        _ => {
+                            writeln!(out, "{indent}{key}:").unwrap();
+                            value.write_text(out, depth + 1);
+                        }
+                    }
+                }
+            }
+            _ => writeln!(out, "{indent}{}", self.scalar_text()).unwrap(),
+        }
+    }
+
+    /// Format a map that only contains scalars on a single line.
+    fn record_text(&self) -> Option<String> {
+        let Node::Map(entries) = self else {
+            return None;
+        };
+        if !entries
+            .iter()
+            .all(|(_, value)| value.is_scalar() || value.is_empty())
+        {
+            return None;
+        }
+
+        let fields: Vec<_> = entries
+            .iter()
+            .filter(|(_, value)| value.is_scalar() && !matches!(value, Node::Null))
+            .map(|(key, value)| format!("{key}: {}", value.scalar_text()))
+            .collect();
+
+        Some(fields.join(", "))
+    }
+
+    fn scalar_text(&self) -> String {
+        match self {
+            Node::Null => "-".to_string(),
+            Node::Number(value) => value.to_string(),
+            Node::Bool(value) => value.to_string(),
+            Node::Str(value) => value.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+impl From<u64> for Node {
+    fn from(value: u64) -> Node {
+        Node::Number(value)
+    }
+}
+
+impl From<usize> for Node {
+    fn from(value: usize) -> Node {
+        Node::Number(value as u64)
+    }
+}
+
+impl From<bool> for Node {
+    fn from(value: bool) -> Node {
+        Node::Bool(value)
+    }
+}
+
+impl From<String> for Node {
+    fn from(value: String) -> Node {
+        Node::Str(value)
+    }
+}
+
+impl From<&str> for Node {
+    fn from(value: &str) -> Node {
+        Node::Str(value.to_string())
+    }
+}
+
+impl<T: Into<Node>> From<Option<T>> for Node {
+    fn from(value: Option<T>) -> Node {
+        value.map(Into::into).unwrap_or(Node::Null)
+    }
+}
+
+impl<T: Into<Node>> From<Vec<T>> for Node {
+    fn from(value: Vec<T>) -> Node {
+        Node::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+fn write_json_str(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn inspect(file: &RawFile) -> Node {
+    let versions = file.versions().map(|versions| {
+        Node::map()
+            .with("last_writer", hex(versions.last_writer()))
+            .with("oldest_writer", hex(versions.oldest_writer()))
+            .with("newest_writer", hex(versions.newest_writer()))
+            .with("oldest_reader", hex(versions.oldest_reader()))
+    });
+
+    let header = Node::map()
+        .with("file_type", file.file_type().to_string())
+        .with("file", file.file().to_string())
+        .with(
+            "legacy_file_version",
+            file.legacy_file_version().to_string(),
+        )
+        .with("file_format", file.file_format().to_string())
+        .with("storage_index", file.storage_index_id().to_string())
+        .with("cell_schema", file.cell_schema().to_string())
+        .with("versions", versions);
+
+    let data_elements: Vec<_> = file
+        .data_elements()
+        .into_iter()
+        .map(|element| {
+            Node::map()
+                .with("id", element.id().to_string())
+                .with("type", data_element_kind(element.kind()))
+                .with("offset", element.offset())
+                .with("size", element.size())
+        })
+        .collect();
+
+    let storage_manifest = file.storage_manifest().map(|manifest| {
+        let roots: Vec<_> = manifest
+            .roots()
+            .into_iter()
+            .map(|(id, cell)| {
+                Node::map()
+                    .with("id", id.to_string())
+                    .with("cell", cell_id(cell))
+            })
+            .collect();
+
+        Node::map()
+            .with("schema", manifest.schema().to_string())
+            .with("roots", roots)
+    });
+
+    let cells: Vec<_> = file
+        .storage_index()
+        .map(|index| index.cell_mappings())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|mapping| {
+            let chain: Vec<_> = file
+                .revision_chain(mapping.cell_id())
+                .into_iter()
+                .map(|id| id.to_string())
+                .collect();
+
+            Node::map()
+                .with("cell", cell_id(mapping.cell_id()))
+                .with("context", mapping.cell_id().0.to_string())
+                .with("object_space", mapping.cell_id().1.to_string())
+                .with("cell_manifest", mapping.manifest_id().to_string())
+                .with("revision_chain", chain)
+        })
+        .collect();
+
+    let revisions: Vec<_> = file
+        .revision_manifests()
+        .into_iter()
+        .map(|manifest| {
+            let roots: Vec<_> = manifest
+                .roots()
+                .iter()
+                .map(|(role, object)| {
+                    Node::map()
+                        .with("role", role.to_string())
+                        .with("object", object.to_string())
+                })
+                .collect();
+            let groups: Vec<_> = manifest
+                .group_references()
+                .iter()
+                .map(|id| id.to_string())
+                .collect();
+
+            Node::map()
+                .with("id", manifest.id().to_string())
+                .with("revision", manifest.revision_id().to_string())
+                .with(
+                    "base_revision",
+                    manifest.base_revision_id().map(|id| id.to_string()),
+                )
+                .with("roots", roots)
+                .with("object_groups", groups)
+        })
+        .collect();
+
+    let store = match file.store() {
+        Ok(store) => {
+            let spaces: Vec<_> = store
+                .object_spaces()
+                .into_iter()
+                .map(|(id, space)| object_space(Some(id), space))
+                .collect();
+
+            Node::map()
+                .with("schema", store.schema().to_string())
+                .with("data_root", object_space(None, store.data_root()))
+                .with("object_spaces", spaces)
+        }
+        Err(err) => Node::map().with("error", err.to_string()),
+    };
+
+    Node::map()
+        .with("header", header)
+        .with("data_elements", data_elements)
+        .with("storage_manifest", storage_manifest)
+        .with("cells", cells)
+        .with("revision_manifests", revisions)
+        .with("store", store)
+}
+
+fn object_space(id: Option<CellId>, space: ObjectSpace) -> Node {
+    let objects: Vec<_> = space
+        .objects()
+        .into_iter()
+        .map(|(id, object)| self::object(id, object))
+        .collect();
+
+    Node::map()
+        .with("cell", id.map(cell_id))
+        .with("id", space.id().to_string())
+        .with("context", space.context().to_string())
+        .with(
+            "content_root",
+            space.content_root().map(|id| id.to_string()),
+        )
+        .with(
+            "metadata_root",
+            space.metadata_root().map(|id| id.to_string()),
+        )
+        .with("objects", objects)
+}
+
+fn object(id: ExGuid, object: Object) -> Node {
+    let properties: Vec<_> = object.properties().iter().map(property).collect();
+
+    Node::map()
+        .with("id", id.to_string())
+        .with("jcid", hex(object.jcid()))
+        .with("name", object.jcid_name())
+        .with("file_data", object.file_data().map(|data| data.len()))
+        .with("properties", properties)
+}
+
+fn property(property: &Property) -> Node {
+    let object_refs: Vec<_> = property.object_refs().iter().map(ref_id).collect();
+    let object_space_refs: Vec<_> = property
+        .object_space_refs()
+        .iter()
+        .map(|id| Node::from(id.map(cell_id)))
+        .collect();
+    let context_refs: Vec<_> = property.context_refs().iter().map(ref_id).collect();
+
+    Node::map()
+        .with("id", hex(property.id()))
+        .with("name", property.name())
+        .with("value", value(property.value()))
+        .with("object_refs", object_refs)
+        .with("object_space_refs", object_space_refs)
+        .with("context_refs", context_refs)
+}
+
+fn value(value: &PropertyValue) -> Node {
+    match value {
+        PropertyValue::Empty => Node::Str("empty".to_string()),
+        PropertyValue::Bool(v) => Node::Bool(*v),
+        PropertyValue::U8(v) => Node::Number(*v as u64),
+        PropertyValue::U16(v) => Node::Number(*v as u64),
+        PropertyValue::U32(v) => Node::Number(*v as u64),
+        PropertyValue::U64(v) => Node::Number(*v),
+        PropertyValue::Bytes(data) => bytes(data),
+        PropertyValue::ObjectId => Node::Str("object id".to_string()),
+        PropertyValue::ObjectIds(count) => Node::Str(format!("{count} object ids")),
+        PropertyValue::ObjectSpaceId => Node::Str("object space id".to_string()),
+        PropertyValue::ObjectSpaceIds(count) => Node::Str(format!("{count} object space ids")),
+        PropertyValue::ContextId => Node::Str("context id".to_string()),
+        PropertyValue::ContextIds(count) => Node::Str(format!("{count} context ids")),
+        PropertyValue::PropertyValues(_, sets) => Node::List(
+            sets.iter()
+                .map(|set| Node::List(set.iter().map(property).collect()))
+                .collect(),
+        ),
+        PropertyValue::PropertySet(set) => Node::List(set.iter().map(property).collect()),
+    }
+}
+
+fn bytes(data: &[u8]) -> Node {
+    let mut text = String::new();
+    for byte in data.iter().take(MAX_BYTES) {
+        write!(text, "{byte:02X}").unwrap();
+    }
+
+    if data.len() > MAX_BYTES {
+        write!(text, "... ({} bytes)", data.len()).unwrap();
+    }
+
+    Node::Str(text)
+}
+
+fn ref_id(id: &Option<ExGuid>) -> Node {
+    id.map(|id| id.to_string()).into()
+}
+
+fn cell_id(id: CellId) -> String {
+    format!("{} / {}", id.0, id.1)
+}
+
+fn hex(value: u32) -> String {
+    format!("0x{value:08X}")
+}
+
+fn data_element_kind(kind: DataElementKind) -> &'static str {
+    match kind {
+        DataElementKind::StorageIndex => "storage index",
+        DataElementKind::StorageManifest => "storage manifest",
+        DataElementKind::CellManifest => "cell manifest",
+        DataElementKind::RevisionManifest => "revision manifest",
+        DataElementKind::ObjectGroup => "object group",
+        DataElementKind::DataElementFragment => "data element fragment",
+        DataElementKind::ObjectDataBlob => "object data blob",
+    }
+}
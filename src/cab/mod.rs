@@ -0,0 +1,389 @@
+//! A reader for Microsoft Cabinet (CAB) archives.
+//!
+//! OneNote packages (`.onepkg`) exported by the desktop client are cabinet
+//! archives that contain the notebook's table of contents, sections and
+//! section group folders. The reader supports uncompressed and MSZIP
+//! compressed folders of single-volume cabinets.
+//!
+//! See \[MS-CAB\].
+
+use crate::Reader;
+use crate::errors::{ErrorKind, Result};
+use crate::limits::Budget;
+use crate::reader::Reader as DataReader;
+use encoding_rs::WINDOWS_1252;
+
+mod mszip;
+
+const SIGNATURE: &[u8] = b"MSCF";
+
+const FLAG_PREV_CABINET: u16 = 0x0001;
+const FLAG_NEXT_CABINET: u16 = 0x0002;
+const FLAG_RESERVE_PRESENT: u16 = 0x0004;
+
+const COMPRESSION_MASK: u16 = 0x000F;
+const COMPRESSION_NONE: u16 = 0;
+const COMPRESSION_MSZIP: u16 = 1;
+
+const ATTRIBUTE_NAME_IS_UTF: u16 = 0x0080;
+
+/// A file stored in a cabinet archive.
+#[derive(Debug)]
+pub(crate) struct CabinetFile {
+    /// The file's path inside the archive, using `\` as the path separator.
+    pub(crate) name: String,
+    pub(crate) data: Vec<u8>,
+}
+
+/// A folder (compression unit) of a cabinet archive.
+///
+/// See \[MS-CAB\] 2.2 (CFFOLDER).
+#[derive(Debug)]
+struct Folder {
+    data_offset: usize,
+    block_count: u16,
+    compression: u16,
+}
+
+/// A file entry of a cabinet archive.
+///
+/// See \[MS-CAB\] 2.3 (CFFILE).
+#[derive(Debug)]
+struct FileEntry {
+    name: String,
+    size: usize,
+    folder_offset: usize,
+    folder: u16,
+}
+
+/// Extract all files of a cabinet archive into memory.
+///
+/// The decompressed data is accounted for in the budget's blob size.
+pub(crate) fn extract(data: &[u8], budget: &Budget) -> Result<Vec<CabinetFile>> {
+    let reader = &mut DataReader::new(data);
+
+    if reader.read(4)? != SIGNATURE {
+        return Err(malformed("invalid cabinet signature"));
+    }
+
+    reader.advance(4)?;
+    let _cabinet_size = reader.get_u32()?;
+    reader.advance(4)?;
+    let files_offset = reader.get_u32()? as usize;
+    reader.advance(4)?;
+    let _version_minor = reader.get_u8()?;
+    let _version_major = reader.get_u8()?;
+    let folder_count = reader.get_u16()?;
+    let file_count = reader.get_u16()?;
+    let flags = reader.get_u16()?;
+    let _set_id = reader.get_u16()?;
+    let _cabinet_index = reader.get_u16()?;
+
+    if flags & (FLAG_PREV_CABINET | FLAG_NEXT_CABINET) != 0 {
+        return Err(malformed("multi-volume cabinets are not supported"));
+    }
+
+    let (folder_reserve, data_reserve) = if flags & FLAG_RESERVE_PRESENT != 0 {
+        let header_reserve = reader.get_u16()? as usize;
+        let folder_reserve = reader.get_u8()? as usize;
+        let data_reserve = reader.get_u8()? as usize;
+        reader.advance(header_reserve)?;
+
+        (folder_reserve, data_reserve)
+    } else {
+        (0, 0)
+    };
+
+    let folders = (0..folder_count)
+        .map(|_| parse_folder(reader, folder_reserve))
+        .collect::<Result<Vec<_>>>()?;
+
+    let reader = &mut DataReader::new(
+        data.get(files_offset..)
+            .ok_or_else(|| malformed("file entries are out of bounds"))?,
+    );
+    let entries = (0..file_count)
+        .map(|_| parse_file_entry(reader))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut contents: Vec<Option<Vec<u8>>> = folders.iter().map(|_| None).collect();
+    let mut files = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let index = entry.folder as usize;
+        let folder = folders
+            .get(index)
+            .ok_or_else(|| malformed("file references an invalid folder"))?;
+
+        // Folders are only decompressed once they're referenced by a file
+        if contents[index].is_none() {
+            contents[index] = Some(decompress_folder(data, folder, data_reserve, budget)?);
+        }
+        let content = contents[index].as_deref().unwrap_or_default();
+
+        let file_data = entry
+            .folder_offset
+            .checked_add(entry.size)
+            .and_then(|end| content.get(entry.folder_offset..end))
+            .ok_or_else(|| malformed("file data is out of bounds"))?;
+
+        files.push(CabinetFile {
+            name: entry.name,
+            data: file_data.to_vec(),
+        });
+    }
+
+    Ok(files)
+}
+
+fn parse_folder(reader: Reader, reserve: usize) -> Result<Folder> {
+    let data_offset = reader.get_u32()? as usize;
+    let block_count = reader.get_u16()?;
+    let compression = reader.get_u16()?;
+    reader.advance(reserve)?;
+
+    Ok(Folder {
+        data_offset,
+        block_count,
+        compression,
+    })
+}
+
+fn parse_file_entry(reader: Reader) -> Result<FileEntry> {
+    let size = reader.get_u32()? as usize;
+    let folder_offset = reader.get_u32()? as usize;
+    let folder = reader.get_u16()?;
+    let _date = reader.get_u16()?;
+    let _time = reader.get_u16()?;
+    let attributes = reader.get_u16()?;
+
+    let name_len = reader
+        .bytes()
+        .iter()
+        .position(|b| *b == 0)
+        .ok_or_else(|| malformed("file name is not terminated"))?;
+    let name = reader.read(name_len)?;
+    let name = if attributes & ATTRIBUTE_NAME_IS_UTF != 0 {
+        String::from_utf8_lossy(name).into_owned()
+    } else {
+        WINDOWS_1252
+            .decode_without_bom_handling(name)
+            .0
+            .into_owned()
+    };
+    reader.advance(1)?;
+
+    // Folder indices above 0xFFFC mark files that span multiple cabinets
+    if folder > 0xFFFC {
+        return Err(malformed("multi-volume cabinets are not supported"));
+    }
+
+    Ok(FileEntry {
+        name,
+        size,
+        folder_offset,
+        folder,
+    })
+}
+
+/// Decompress all data blocks of a folder.
+///
+/// See \[MS-CAB\] 2.4 (CFDATA).
+fn decompress_folder(
+    data: &[u8],
+    folder: &Folder,
+    reserve: usize,
+    budget: &Budget,
+) -> Result<Vec<u8>> {
+    let reader = &mut DataReader::new(
+        data.get(folder.data_offset..)
+            .ok_or_else(|| malformed("folder data is out of bounds"))?,
+    );
+
+    let mut decoder = mszip::Decoder::new();
+    let mut output = vec![];
+
+    for _ in 0..folder.block_count {
+        let _checksum = reader.get_u32()?;
+        let compressed_size = reader.get_u16()? as usize;
+        let uncompressed_size = reader.get_u16()? as usize;
+        reader.advance(reserve)?;
+        let block = reader.read(compressed_size)?;
+
+        budget.add_blob_bytes(uncompressed_size as u64)?;
+        budget
+            .limits()
+            .check_allocation((output.len() + uncompressed_size) as u64)?;
+
+        match folder.compression & COMPRESSION_MASK {
+            COMPRESSION_NONE => output.extend_from_slice(block),
+            COMPRESSION_MSZIP => decoder.decompress(block, uncompressed_size, &mut output)?,
+            compression => {
+                return Err(ErrorKind::MalformedCabinetData(
+                    format!("unsupported compression type: {compression}").into(),
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn malformed(message: &'static str) -> crate::errors::Error {
+    ErrorKind::MalformedCabinetData(message.into()).into()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::extract;
+    use crate::errors::{ErrorKind, Limit};
+    use crate::limits::{Budget, ParseLimits};
+
+    fn budget() -> Budget {
+        Budget::new(ParseLimits::default())
+    }
+
+    /// Build a single-folder cabinet archive.
+    ///
+    /// The files are stored uncompressed if `compress` is false and MSZIP
+    /// compressed otherwise.
+    pub(crate) fn build_cabinet(files: &[(&str, &[u8])], compress: bool) -> Vec<u8> {
+        let content: Vec<u8> = files.iter().flat_map(|(_, data)| data.to_vec()).collect();
+
+        let blocks = content
+            .chunks(0x8000)
+            .map(|chunk| {
+                if compress {
+                    let mut block = b"CK".to_vec();
+                    block.extend(miniz_oxide::deflate::compress_to_vec(chunk, 6));
+                    (block, chunk.len())
+                } else {
+                    (chunk.to_vec(), chunk.len())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        build_cabinet_from_blocks(files, &blocks, compress as u16)
+    }
+
+    fn build_cabinet_from_blocks(
+        files: &[(&str, &[u8])],
+        blocks: &[(Vec<u8>, usize)],
+        compression: u16,
+    ) -> Vec<u8> {
+        let mut entries = vec![];
+        let mut offset = 0;
+        for (name, data) in files {
+            entries.extend((data.len() as u32).to_le_bytes());
+            entries.extend((offset as u32).to_le_bytes());
+            entries.extend(0u16.to_le_bytes());
+            entries.extend([0; 4]);
+            entries.extend(0x0080u16.to_le_bytes());
+            entries.extend(name.as_bytes());
+            entries.push(0);
+            offset += data.len();
+        }
+
+        let files_offset = 36 + 8;
+        let data_offset = files_offset + entries.len();
+
+        let mut cabinet = b"MSCF".to_vec();
+        cabinet.extend([0; 12]);
+        cabinet.extend((files_offset as u32).to_le_bytes());
+        cabinet.extend([0; 4]);
+        cabinet.extend([3, 1]);
+        cabinet.extend(1u16.to_le_bytes());
+        cabinet.extend((files.len() as u16).to_le_bytes());
+        cabinet.extend([0; 6]);
+
+        cabinet.extend((data_offset as u32).to_le_bytes());
+        cabinet.extend((blocks.len() as u16).to_le_bytes());
+        cabinet.extend(compression.to_le_bytes());
+
+        cabinet.extend(entries);
+
+        for (data, size) in blocks {
+            cabinet.extend([0; 4]);
+            cabinet.extend((data.len() as u16).to_le_bytes());
+            cabinet.extend((*size as u16).to_le_bytes());
+            cabinet.extend(data);
+        }
+
+        let size = cabinet.len() as u32;
+        cabinet[8..12].copy_from_slice(&size.to_le_bytes());
+
+        cabinet
+    }
+
+    #[test]
+    fn test_extract_uncompressed() {
+        let cabinet = build_cabinet(&[("a.txt", b"hello"), (r"dir\b.txt", b"world")], false);
+        let files = extract(&cabinet, &budget()).unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].name, "a.txt");
+        assert_eq!(files[0].data, b"hello");
+        assert_eq!(files[1].name, r"dir\b.txt");
+        assert_eq!(files[1].data, b"world");
+    }
+
+    #[test]
+    fn test_extract_mszip() {
+        let large: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let cabinet = build_cabinet(&[("large.bin", &large), ("small.txt", b"abc")], true);
+        let files = extract(&cabinet, &budget()).unwrap();
+
+        assert_eq!(files[0].data, large);
+        assert_eq!(files[1].data, b"abc");
+    }
+
+    #[test]
+    fn test_extract_mszip_history() {
+        let mut first = b"CK".to_vec();
+        first.extend(miniz_oxide::deflate::compress_to_vec(b"abcdefgh", 6));
+
+        // A fixed Huffman block that only contains a match (length 8, distance 8)
+        // which refers to the output of the previous block
+        let second = b"CK\x83\xD1\x00".to_vec();
+
+        let data = b"abcdefghabcdefgh";
+        let cabinet = build_cabinet_from_blocks(&[("a.txt", data)], &[(first, 8), (second, 8)], 1);
+        let files = extract(&cabinet, &budget()).unwrap();
+
+        assert_eq!(files[0].data, data);
+    }
+
+    #[test]
+    fn test_extract_invalid() {
+        assert!(extract(b"not a cabinet", &budget()).is_err());
+
+        let mut cabinet = build_cabinet(&[("a.txt", b"hello")], false);
+        cabinet.truncate(cabinet.len() - 2);
+        assert!(extract(&cabinet, &budget()).is_err());
+    }
+
+    #[test]
+    fn test_extract_limits() {
+        let large = vec![0; 100_000];
+        let cabinet = build_cabinet(&[("large.bin", &large)], true);
+
+        let budget = Budget::new(ParseLimits::default().with_max_blob_bytes(50_000));
+        assert!(matches!(
+            extract(&cabinet, &budget).unwrap_err().kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::BlobBytes,
+                ..
+            }
+        ));
+
+        let budget = Budget::new(ParseLimits::default().with_max_allocation(50_000));
+        assert!(matches!(
+            extract(&cabinet, &budget).unwrap_err().kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::Allocation,
+                ..
+            }
+        ));
+    }
+}
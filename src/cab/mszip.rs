@@ -0,0 +1,56 @@
+use crate::errors::{ErrorKind, Result};
+use miniz_oxide::inflate::TINFLStatus;
+use miniz_oxide::inflate::core::inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF;
+use miniz_oxide::inflate::core::{DecompressorOxide, decompress};
+
+/// A decoder for MSZIP compressed data blocks.
+///
+/// Each block contains a complete deflate stream prefixed with the `CK`
+/// signature. Matches may refer to data of previous blocks of the same folder,
+/// so blocks must be decompressed in order into the same output buffer.
+///
+/// See \[MS-MCI\].
+pub(crate) struct Decoder {
+    state: Box<DecompressorOxide>,
+}
+
+impl Decoder {
+    pub(crate) fn new() -> Decoder {
+        Decoder {
+            state: Box::default(),
+        }
+    }
+
+    /// Decompress a block and append its data to the output buffer.
+    pub(crate) fn decompress(
+        &mut self,
+        block: &[u8],
+        uncompressed_size: usize,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        let data = block
+            .strip_prefix(b"CK")
+            .ok_or_else(|| ErrorKind::MalformedCabinetData("invalid MSZIP signature".into()))?;
+
+        let start = output.len();
+        output.resize(start + uncompressed_size, 0);
+
+        self.state.init();
+        let (status, _, written) = decompress(
+            &mut self.state,
+            data,
+            output,
+            start,
+            TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+
+        if status != TINFLStatus::Done || written != uncompressed_size {
+            return Err(ErrorKind::MalformedCabinetData(
+                format!("failed to decompress MSZIP block: {status:?}").into(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use crate::errors::Result;
+use crate::fsshttpb::data::cell_id::CellId;
+use crate::onenote::context::{Context, Records};
+use crate::onenote::page::Page;
+use crate::onenote::section::Section;
+use crate::onestore::version::StoreVersion;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A cache of parsed sections for parsing files again after they changed.
+///
+/// When a parser with a cache parses a section file that it has parsed before,
+/// it compares the file identity and the serial numbers of the file's object
+/// spaces with the previous version. An unchanged section is returned from the
+/// cache and only the changed pages of a modified section are parsed again.
+/// The files still have to be read and their packaging decoded.
+///
+/// Sections are cached by their path. Clones of a cache share the cached
+/// sections. A cache should only be used with parsers that have the same
+/// configuration.
+///
+/// ```no_run
+/// use onenote_parser::{ParseCache, Parser};
+/// use std::path::Path;
+///
+/// let parser = Parser::new().with_cache(ParseCache::new());
+/// let path = Path::new("Notebook/Open Notebook.onetoc2");
+///
+/// let notebook = parser.parse_notebook(path).unwrap();
+///
+/// // After a page has been edited, only that page is parsed again.
+/// let notebook = parser.parse_notebook(path).unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct ParseCache {
+    sections: Arc<Mutex<HashMap<PathBuf, Arc<CachedSection>>>>,
+}
+
+impl ParseCache {
+    /// Create an empty cache.
+    pub fn new() -> ParseCache {
+        ParseCache::default()
+    }
+
+    /// The number of cached sections.
+    pub fn len(&self) -> usize {
+        self.sections.lock().unwrap().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached sections.
+    pub fn clear(&self) {
+        self.sections.lock().unwrap().clear();
+    }
+
+    pub(crate) fn section(&self, path: &Path) -> Option<Arc<CachedSection>> {
+        self.sections.lock().unwrap().get(path).cloned()
+    }
+
+    pub(crate) fn insert(&self, path: &Path, section: CachedSection) {
+        self.sections
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), Arc::new(section));
+    }
+}
+
+impl fmt::Debug for ParseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseCache")
+            .field("sections", &self.len())
+            .finish()
+    }
+}
+
+/// A parsed section with the file version it was parsed from.
+pub(crate) struct CachedSection {
+    pub(crate) version: StoreVersion,
+    pub(crate) section: Section,
+    pub(crate) pages: HashMap<CellId, Arc<CachedPage>>,
+}
+
+impl CachedSection {
+    /// The cached pages whose object spaces are unchanged in `version`.
+    pub(crate) fn unchanged_pages(
+        &self,
+        version: &StoreVersion,
+    ) -> HashMap<CellId, Arc<CachedPage>> {
+        self.pages
+            .iter()
+            .filter(|(cell_id, _)| version.is_cell_unchanged(&self.version, **cell_id))
+            .map(|(cell_id, page)| (*cell_id, page.clone()))
+            .collect()
+    }
+}
+
+/// A parsed page with the diagnostics and unsupported JCIDs recorded while parsing it.
+pub(crate) struct CachedPage {
+    page: Page,
+    records: Records,
+}
+
+/// The pages of a section that is being parsed.
+///
+/// Pages are reused from the previous version of the section if possible.
+/// Newly parsed pages are collected for the next version.
+pub(crate) struct PageCache {
+    reusable: HashMap<CellId, Arc<CachedPage>>,
+    pages: Option<Mutex<HashMap<CellId, Arc<CachedPage>>>>,
+}
+
+impl PageCache {
+    /// A page cache that doesn't reuse or collect pages.
+    pub(crate) fn disabled() -> PageCache {
+        PageCache {
+            reusable: HashMap::new(),
+            pages: None,
+        }
+    }
+
+    pub(crate) fn new(reusable: HashMap<CellId, Arc<CachedPage>>) -> PageCache {
+        PageCache {
+            reusable,
+            pages: Some(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The object spaces whose pages are reused and don't have to be parsed.
+    pub(crate) fn reusable_spaces(&self) -> HashSet<CellId> {
+        self.reusable.keys().copied().collect()
+    }
+
+    /// Reuse the page of the object space or parse it using `parse`.
+    pub(crate) fn page(
+        &self,
+        space_id: CellId,
+        ctx: &Context,
+        parse: impl FnOnce(&Context) -> Result<Page>,
+    ) -> Result<Page> {
+        let Some(pages) = &self.pages else {
+            return parse(ctx);
+        };
+
+        let cached = match self.reusable.get(&space_id) {
+            Some(cached) => {
+                ctx.replay(&cached.records);
+                cached.clone()
+            }
+            None => {
+                let (page, records) = ctx.record(parse)?;
+                Arc::new(CachedPage { page, records })
+            }
+        };
+
+        let page = cached.page.clone();
+        pages.lock().unwrap().insert(space_id, cached);
+
+        Ok(page)
+    }
+
+    /// The pages that have been reused or parsed.
+    pub(crate) fn into_pages(self) -> HashMap<CellId, Arc<CachedPage>> {
+        self.pages
+            .map(|pages| pages.into_inner().unwrap())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseCache;
+    use crate::Parser;
+    use crate::test_support::{SpaceBuilder, page_space, section_store};
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_reuse_unchanged_pages() {
+        let cache = ParseCache::new();
+        let parser = Parser::new().with_cache(cache.clone());
+        let path = Path::new("Cached.one");
+        let heights = |data: &[u8]| {
+            let section = parser.parse_section_buffer(data, path).unwrap();
+            assert_eq!(section.unknown_jcids(), &BTreeMap::from([(0x0006_0099, 2)]));
+
+            section.page_series()[0]
+                .pages()
+                .iter()
+                .map(|page| page.height())
+                .collect::<Vec<_>>()
+        };
+
+        let original = section_store(vec![page_space(20, 1, 1.0), page_space(21, 1, 2.0)]);
+        assert_eq!(heights(&original), [Some(1.0), Some(2.0)]);
+        let first = cache.section(path).unwrap();
+
+        // An unchanged file is returned from the cache.
+        assert_eq!(heights(&original), [Some(1.0), Some(2.0)]);
+        assert!(Arc::ptr_eq(&first, &cache.section(path).unwrap()));
+
+        // Only the edited page is parsed again.
+        let edited = section_store(vec![page_space(20, 1, 1.0), page_space(21, 2, 3.0)]);
+        assert_eq!(heights(&edited), [Some(1.0), Some(3.0)]);
+        let second = cache.section(path).unwrap();
+
+        let unchanged = SpaceBuilder::new(20).cell_id();
+        let changed = SpaceBuilder::new(21).cell_id();
+        assert!(Arc::ptr_eq(
+            &first.pages[&unchanged],
+            &second.pages[&unchanged]
+        ));
+        assert!(!Arc::ptr_eq(
+            &first.pages[&changed],
+            &second.pages[&changed]
+        ));
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -1,10 +1,15 @@
 //! OneNote parsing error handling.
 
+use crate::fsshttpb::data::exguid::ExGuid;
 #[cfg(feature = "backtrace")]
 use std::backtrace::Backtrace;
 use std::borrow::Cow;
+use std::fmt;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
+use uuid::Uuid;
 
 /// The result of parsing a OneNote file.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -15,27 +20,158 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// parsing error struct will contain a backtrace of the location where
 /// the error occurred. The backtrace can be accessed using
 /// `std::error::Error::backtrace`.
+///
+/// The error also records where it occurred, e.g. the path of the file,
+/// the byte offset in the file and the path of the object that failed to
+/// parse. This information is available through accessor methods and is
+/// appended to the error message.
 #[derive(Error, Debug)]
-#[error("{kind}")]
+#[error("{kind}{}", context.as_deref().map(ToString::to_string).unwrap_or_default())]
 pub struct Error {
     kind: ErrorKind,
+    context: Option<Box<ErrorContext>>,
 
     #[cfg(feature = "backtrace")]
     backtrace: Backtrace,
 }
 
+impl Error {
+    /// The kind of error that occurred.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// The path of the file that failed to parse.
+    pub fn file(&self) -> Option<&Path> {
+        self.context.as_ref()?.file.as_deref()
+    }
+
+    /// The byte offset in the FSSHTTPB stream at which parsing failed.
+    pub fn offset(&self) -> Option<usize> {
+        self.context.as_ref()?.offset
+    }
+
+    /// The ID of the FSSHTTPB data element that failed to parse.
+    ///
+    /// Data element IDs are extended GUIDs consisting of a GUID and a 32 bit
+    /// value (see [\[MS-FSSHTTPB\] 2.2.1.7]).
+    ///
+    /// [\[MS-FSSHTTPB\] 2.2.1.7]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/bff58e9f-8222-4fbb-b112-5826d5febedd
+    pub fn data_element(&self) -> Option<(Uuid, u32)> {
+        self.context
+            .as_ref()?
+            .data_element
+            .map(|id| (id.guid.0, id.value))
+    }
+
+    /// The JCID (object type) of the object that failed to parse.
+    ///
+    /// This is the innermost outline item or page content (e.g. a rich text or
+    /// table node) that contains the failing object.
+    pub fn jcid(&self) -> Option<u32> {
+        self.context.as_ref()?.jcid
+    }
+
+    /// The path of the object that failed to parse.
+    ///
+    /// The location consists of path segments starting at the outermost object,
+    /// e.g. `["section \"Notes\"", "page series 0", "page 2", "content 1"]`.
+    pub fn location(&self) -> &[String] {
+        self.context
+            .as_ref()
+            .map(|context| context.location.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        self.context.get_or_insert_default()
+    }
+
+    /// Record the file that failed to parse unless a file is already recorded.
+    pub(crate) fn with_file(mut self, path: &Path) -> Error {
+        self.context_mut()
+            .file
+            .get_or_insert_with(|| path.to_path_buf());
+        self
+    }
+
+    /// Record the stream offset unless an offset is already recorded.
+    pub(crate) fn with_offset(mut self, offset: usize) -> Error {
+        self.context_mut().offset.get_or_insert(offset);
+        self
+    }
+
+    /// Record the data element unless a data element is already recorded.
+    pub(crate) fn with_data_element(mut self, id: ExGuid) -> Error {
+        self.context_mut().data_element.get_or_insert(id);
+        self
+    }
+
+    /// Record the object's JCID unless a JCID is already recorded.
+    pub(crate) fn with_jcid(mut self, jcid: u32) -> Error {
+        self.context_mut().jcid.get_or_insert(jcid);
+        self
+    }
+
+    /// Prepend a segment to the location of the error.
+    pub(crate) fn with_location(mut self, segment: String) -> Error {
+        self.context_mut().location.insert(0, segment);
+        self
+    }
+}
+
+/// Where an error occurred.
+#[derive(Debug, Default)]
+struct ErrorContext {
+    file: Option<PathBuf>,
+    offset: Option<usize>,
+    data_element: Option<ExGuid>,
+    jcid: Option<u32>,
+    location: Vec<String>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, " in {}", file.display())?;
+        }
+
+        if let Some(offset) = self.offset {
+            write!(f, " at offset 0x{offset:X}")?;
+        }
+
+        if let Some(id) = self.data_element {
+            write!(f, " in data element {},{}", id.guid, id.value)?;
+        }
+
+        if let Some(jcid) = self.jcid {
+            write!(f, " in object with JCID 0x{jcid:08X}")?;
+        }
+
+        if !self.location.is_empty() {
+            write!(f, " at {}", self.location.join(" > "))?;
+        }
+
+        Ok(())
+    }
+}
+
 impl From<ErrorKind> for Error {
     #[cfg(feature = "backtrace")]
     fn from(kind: ErrorKind) -> Self {
         Error {
             kind,
+            context: None,
             backtrace: Backtrace::capture(),
         }
     }
 
     #[cfg(not(feature = "backtrace"))]
     fn from(kind: ErrorKind) -> Self {
-        Error { kind }
+        Error {
+            kind,
+            context: None,
+        }
     }
 }
 
@@ -63,9 +199,69 @@ impl From<uuid::Error> for Error {
     }
 }
 
+#[cfg(feature = "zip")]
+impl From<zip::result::ZipError> for ErrorKind {
+    fn from(err: zip::result::ZipError) -> Self {
+        ErrorKind::Zip { err: Box::new(err) }
+    }
+}
+
+#[cfg(feature = "zip")]
+impl From<zip::result::ZipError> for Error {
+    fn from(err: zip::result::ZipError) -> Self {
+        ErrorKind::from(err).into()
+    }
+}
+
+/// An error that was skipped when parsing in lenient mode.
+///
+/// A few problems that don't prevent parsing, like duplicate table of contents
+/// entries, are reported as diagnostics outside of lenient mode too. See [`Parser::with_lenient`] for details.
+///
+/// [`Parser::with_lenient`]: crate::Parser::with_lenient
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub(crate) location: Vec<String>,
+    error: Arc<Error>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(error: Error) -> Diagnostic {
+        Diagnostic {
+            location: vec![],
+            error: Arc::new(error),
+        }
+    }
+
+    /// The location of the skipped object.
+    ///
+    /// The location consists of path segments starting at the outermost object,
+    /// e.g. `["section \"Notes\"", "page series 0", "page 2", "content 1"]`.
+    pub fn location(&self) -> &[String] {
+        &self.location
+    }
+
+    /// The error that caused the object to be skipped.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// The kind of error that caused the object to be skipped.
+    pub fn kind(&self) -> &ErrorKind {
+        self.error.kind()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location.join(" > "), self.error)
+    }
+}
+
 /// Details about a parsing error
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// Hit the end of the OneNote file before it was expected.
     #[error("Unexpected end of file")]
@@ -103,6 +299,14 @@ pub enum ErrorKind {
     #[error("Malformed FSSHTTPB data: {0}")]
     MalformedFssHttpBData(Cow<'static, str>),
 
+    /// Malformed data was encountered when extracting a cabinet archive.
+    #[error("Malformed cabinet data: {0}")]
+    MalformedCabinetData(Cow<'static, str>),
+
+    /// Malformed data was encountered when converting an EMF or WMF image.
+    #[error("Malformed metafile data: {0}")]
+    MalformedMetafileData(Cow<'static, str>),
+
     /// A malformed UUID was encountered
     #[error("Invalid UUID: {err}")]
     InvalidUuid {
@@ -117,6 +321,15 @@ pub enum ErrorKind {
         err: io::Error,
     },
 
+    /// A ZIP archive couldn't be read.
+    ///
+    /// The error is boxed so that the variant doesn't depend on the `zip` feature.
+    #[error("ZIP archive failure: {err}")]
+    Zip {
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     /// A malformed UTF-16 string was encountered during parsing.
     #[error("Malformed UTF-16 string: {err}")]
     Utf16Error {
@@ -134,4 +347,49 @@ pub enum ErrorKind {
     /// A filesystem path was missing required components.
     #[error("Invalid path: {message}")]
     InvalidPath { message: Cow<'static, str> },
+
+    /// A resource limit was exceeded (see [`ParseLimits`]).
+    ///
+    /// [`ParseLimits`]: crate::ParseLimits
+    #[error("Parse limit exceeded: {limit} is larger than {max}")]
+    LimitExceeded { limit: Limit, max: u64 },
+
+    /// Parsing was cancelled using a [`CancellationToken`].
+    ///
+    /// [`CancellationToken`]: crate::CancellationToken
+    #[error("Parsing was cancelled")]
+    Cancelled,
+}
+
+/// A resource limit of the parser.
+///
+/// See [`ParseLimits`] for details.
+///
+/// [`ParseLimits`]: crate::ParseLimits
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Limit {
+    /// The size of an input file.
+    InputSize,
+    /// The size of a single allocation.
+    Allocation,
+    /// The number of data elements and objects in a file.
+    ObjectCount,
+    /// The nesting depth of outlines, table of contents entries and section groups.
+    Depth,
+    /// The total size of file data blobs.
+    BlobBytes,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Limit::InputSize => "input size",
+            Limit::Allocation => "allocation size",
+            Limit::ObjectCount => "object count",
+            Limit::Depth => "nesting depth",
+            Limit::BlobBytes => "total blob size",
+        };
+
+        f.write_str(name)
+    }
 }
@@ -1,23 +1,24 @@
 use crate::Reader;
 use crate::errors::Result;
 use crate::fsshttpb::data::compact_u64::CompactU64;
+use bytes::Bytes;
 
 /// A byte array with the length determined by a `CompactU64`.
 ///
 /// See [\[MS-FSSHTTPB\] 2.2.1.3].
 ///
 /// [\[MS-FSSHTTPB\] 2.2.1.3]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/6bdda105-af7f-4757-8dbe-0c7f3100647e
-pub(crate) struct BinaryItem(Vec<u8>);
+pub(crate) struct BinaryItem(Bytes);
 
 impl BinaryItem {
     pub(crate) fn parse(reader: Reader) -> Result<BinaryItem> {
         let size = CompactU64::parse(reader)?.value();
-        let data = reader.read(size as usize)?.to_vec();
+        let data = reader.read_bytes(size)?;
 
         Ok(BinaryItem(data))
     }
 
-    pub(crate) fn value(self) -> Vec<u8> {
+    pub(crate) fn value(self) -> Bytes {
         self.0
     }
 }
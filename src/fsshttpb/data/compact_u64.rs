@@ -118,6 +118,7 @@ impl CompactU64 {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod test {
     use crate::fsshttpb::data::compact_u64::CompactU64;
     use crate::reader::Reader;
@@ -11,7 +11,9 @@ use std::fmt;
 /// [\[MS-FSSHTTPB\] 2.2.1.7]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/bff58e9f-8222-4fbb-b112-5826d5febedd
 #[derive(Clone, Copy, PartialEq, Hash, Eq)]
 pub struct ExGuid {
+    /// The GUID.
     pub guid: Guid,
+    /// The value that extends the GUID.
     pub value: u32,
 }
 
@@ -100,6 +102,12 @@ impl ExGuid {
     }
 }
 
+impl fmt::Display for ExGuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.guid, self.value)
+    }
+}
+
 impl fmt::Debug for ExGuid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "ExGuid {{{}, {}}}", self.guid, self.value)
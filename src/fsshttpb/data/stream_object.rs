@@ -196,6 +196,18 @@ impl ObjectHeader {
         }
     }
 
+    pub(crate) fn has_end_16(reader: Reader, object_type: ObjectType) -> Result<bool> {
+        let data = match reader.bytes() {
+            [first, second, ..] => u16::from_le_bytes([*first, *second]),
+            _ => return Err(ErrorKind::UnexpectedEof.into()),
+        };
+        let expected = object_type.to_u16().ok_or_else(|| {
+            ErrorKind::MalformedFssHttpBData(format!("invalid object type: {object_type:?}").into())
+        })?;
+
+        Ok(data & 0b11 == 0x3 && data >> 2 == expected)
+    }
+
     pub(crate) fn has_end_8(reader: Reader, object_type: ObjectType) -> Result<bool> {
         let data = reader.bytes().first().ok_or(ErrorKind::UnexpectedEof)?;
         let expected = object_type.to_u8().ok_or_else(|| {
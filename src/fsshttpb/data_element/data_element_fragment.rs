@@ -5,6 +5,7 @@ use crate::fsshttpb::data::exguid::ExGuid;
 use crate::fsshttpb::data::object_types::ObjectType;
 use crate::fsshttpb::data::stream_object::ObjectHeader;
 use crate::fsshttpb::data_element::DataElement;
+use bytes::Bytes;
 
 /// A data element fragment.
 ///
@@ -17,7 +18,7 @@ pub(crate) struct DataElementFragment {
     pub(crate) id: ExGuid,
     pub(crate) size: u64,
     pub(crate) chunk_reference: DataElementFragmentChunkReference,
-    pub(crate) data: Vec<u8>,
+    pub(crate) data: Bytes,
 }
 
 #[derive(Debug)]
@@ -41,7 +42,8 @@ impl DataElement {
         let offset = CompactU64::parse(reader)?.value();
         let length = CompactU64::parse(reader)?.value();
 
-        let data = reader.read(size as usize)?.to_vec();
+        let data = reader.read_bytes(size)?;
+        reader.add_blob_bytes(data.len())?;
 
         let chunk_reference = DataElementFragmentChunkReference { offset, length };
         let fragment = DataElementFragment {
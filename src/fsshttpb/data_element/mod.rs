@@ -11,7 +11,8 @@ use crate::fsshttpb::data_element::object_group::ObjectGroup;
 use crate::fsshttpb::data_element::revision_manifest::RevisionManifest;
 use crate::fsshttpb::data_element::storage_index::StorageIndex;
 use crate::fsshttpb::data_element::storage_manifest::StorageManifest;
-use std::collections::HashMap;
+use bytes::Bytes;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 
 pub(crate) mod cell_manifest;
@@ -36,6 +37,16 @@ pub(crate) struct DataElementPackage {
     pub(crate) object_groups: HashMap<ExGuid, ObjectGroup>,
     pub(crate) data_element_fragments: HashMap<ExGuid, DataElementFragment>,
     pub(crate) object_data_blobs: HashMap<ExGuid, ObjectDataBlob>,
+    pub(crate) spans: Vec<DataElementSpan>,
+}
+
+/// The location of a data element in the package stream.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(feature = "raw"), allow(dead_code))]
+pub(crate) struct DataElementSpan {
+    pub(crate) id: ExGuid,
+    pub(crate) offset: usize,
+    pub(crate) size: usize,
 }
 
 impl DataElementPackage {
@@ -54,6 +65,7 @@ impl DataElementPackage {
             object_groups: Default::default(),
             data_element_fragments: Default::default(),
             object_data_blobs: Default::default(),
+            spans: vec![],
         };
 
         loop {
@@ -61,7 +73,15 @@ impl DataElementPackage {
                 break;
             }
 
-            DataElement::parse(reader, &mut package)?
+            reader.add_object()?;
+
+            let offset = reader.offset();
+            let id = DataElement::parse(reader, &mut package)?;
+            package.spans.push(DataElementSpan {
+                id,
+                offset,
+                size: reader.offset() - offset,
+            });
         }
 
         ObjectHeader::try_parse_end_8(reader, ObjectType::DataElementPackage)?;
@@ -69,6 +89,58 @@ impl DataElementPackage {
         Ok(package)
     }
 
+    /// Merge the data elements of an incremental update into this package.
+    ///
+    /// Data elements of the update replace data elements with the same ID. The
+    /// mappings of the update's storage indexes are added to the storage index
+    /// with the ID `storage_index` (or the first storage index if there is no
+    /// such index) and a storage manifest in the update replaces the existing
+    /// one. The update's spans are dropped as they refer to the update's stream,
+    /// and so are the spans of the data elements the update replaces.
+    ///
+    /// See [\[MS-FSSHTTPB\] 2.2.1.12].
+    ///
+    /// [\[MS-FSSHTTPB\] 2.2.1.12]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/99a25464-99b5-4262-a964-baabed2170eb
+    pub(crate) fn merge(
+        &mut self,
+        update: DataElementPackage,
+        storage_index: ExGuid,
+    ) -> Result<()> {
+        let storage_index = if self.storage_indexes.contains_key(&storage_index) {
+            self.storage_indexes.get_mut(&storage_index)
+        } else {
+            self.storage_indexes.values_mut().next()
+        }
+        .ok_or_else(|| ErrorKind::MalformedFssHttpBData("storage index is missing".into()))?;
+
+        let mut replaced: HashSet<_> = update
+            .spans
+            .iter()
+            .map(|span| span.id)
+            .filter(|id| !update.storage_indexes.contains_key(id))
+            .collect();
+
+        for (_, index) in update.storage_indexes {
+            storage_index.merge(index);
+        }
+
+        if !update.storage_manifests.is_empty() {
+            replaced.extend(self.storage_manifests.keys().copied());
+            self.storage_manifests = update.storage_manifests;
+        }
+
+        self.spans.retain(|span| !replaced.contains(&span.id));
+
+        self.cell_manifests.extend(update.cell_manifests);
+        self.revision_manifests.extend(update.revision_manifests);
+        self.object_groups.extend(update.object_groups);
+        self.data_element_fragments
+            .extend(update.data_element_fragments);
+        self.object_data_blobs.extend(update.object_data_blobs);
+
+        Ok(())
+    }
+
     /// Look up the object groups referenced by a cell.
     pub(crate) fn find_objects(
         &self,
@@ -107,7 +179,7 @@ impl DataElementPackage {
     }
 
     /// Look up a blob by its ID.
-    pub(crate) fn find_blob(&self, id: ExGuid) -> Option<&[u8]> {
+    pub(crate) fn find_blob(&self, id: ExGuid) -> Option<&Bytes> {
         self.object_data_blobs.get(&id).map(|blob| blob.value())
     }
 
@@ -173,10 +245,17 @@ impl DataElementPackage {
 pub(crate) struct DataElement;
 
 impl DataElement {
-    pub(crate) fn parse(reader: Reader, package: &mut DataElementPackage) -> Result<()> {
+    pub(crate) fn parse(reader: Reader, package: &mut DataElementPackage) -> Result<ExGuid> {
         ObjectHeader::try_parse_16(reader, ObjectType::DataElement)?;
 
         let id = ExGuid::parse(reader)?;
+
+        Self::parse_element(reader, id, package).map_err(|err| err.with_data_element(id))?;
+
+        Ok(id)
+    }
+
+    fn parse_element(reader: Reader, id: ExGuid, package: &mut DataElementPackage) -> Result<()> {
         let _serial = SerialNumber::parse(reader)?;
         let element_type = CompactU64::parse(reader)?;
 
@@ -227,3 +306,66 @@ impl DataElement {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DataElementPackage;
+    use crate::fsshttpb::data::exguid::ExGuid;
+    use crate::fsshttpb::writer::{Element, Serial, Writer};
+    use crate::reader::Reader;
+
+    fn id(value: u32) -> ExGuid {
+        ExGuid::from_guid(guid!("1A5A319C-C26B-41AA-B9C5-9BD8C44E07D4"), value)
+    }
+
+    fn package(elements: &[(ExGuid, Serial, Element)]) -> DataElementPackage {
+        let mut writer = Writer::new();
+        writer.put_data_element_package(elements);
+        let data = writer.into_bytes();
+
+        DataElementPackage::parse(&mut Reader::new(&data)).unwrap()
+    }
+
+    fn storage_index() -> Element {
+        Element::StorageIndex {
+            manifest_mappings: vec![],
+            cell_mappings: vec![],
+            revision_mappings: vec![],
+        }
+    }
+
+    fn blob(data: &[u8]) -> Element {
+        Element::Blob {
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_merge_drops_spans_of_replaced_elements() {
+        let mut base = package(&[
+            (id(1), Serial::Null, storage_index()),
+            (id(2), Serial::Null, blob(&[1, 2, 3])),
+            (id(3), Serial::Null, blob(&[4, 5, 6])),
+        ]);
+        let update = package(&[
+            (id(1), Serial::Null, storage_index()),
+            (id(2), Serial::Null, blob(&[7])),
+        ]);
+
+        let kept = base.spans[2];
+
+        base.merge(update, id(1)).unwrap();
+
+        assert_eq!(base.find_blob(id(2)).unwrap().as_ref(), &[7]);
+        assert!(base.spans.iter().all(|span| span.id != id(2)));
+
+        let spans: Vec<_> = base
+            .spans
+            .iter()
+            .map(|span| (span.id, span.offset, span.size))
+            .collect();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].0, id(1));
+        assert_eq!(spans[1], (id(3), kept.offset, kept.size));
+    }
+}
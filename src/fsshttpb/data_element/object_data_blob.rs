@@ -4,6 +4,7 @@ use crate::fsshttpb::data::binary_item::BinaryItem;
 use crate::fsshttpb::data::object_types::ObjectType;
 use crate::fsshttpb::data::stream_object::ObjectHeader;
 use crate::fsshttpb::data_element::DataElement;
+use bytes::Bytes;
 use std::fmt;
 
 /// An object data blob.
@@ -11,10 +12,10 @@ use std::fmt;
 /// See [\[MS-FSSHTTPB\] 2.2.1.12.8]
 ///
 /// [\[MS-FSSHTTPB\] 2.2.1.12.8]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/d36dd2b4-bad1-441b-93c7-adbe3069152c
-pub(crate) struct ObjectDataBlob(Vec<u8>);
+pub(crate) struct ObjectDataBlob(Bytes);
 
 impl ObjectDataBlob {
-    pub(crate) fn value(&self) -> &[u8] {
+    pub(crate) fn value(&self) -> &Bytes {
         &self.0
     }
 }
@@ -29,10 +30,11 @@ impl DataElement {
     pub(crate) fn parse_object_data_blob(reader: Reader) -> Result<ObjectDataBlob> {
         ObjectHeader::try_parse(reader, ObjectType::ObjectDataBlob)?;
 
-        let data = BinaryItem::parse(reader)?;
+        let data = BinaryItem::parse(reader)?.value();
+        reader.add_blob_bytes(data.len())?;
 
         ObjectHeader::try_parse_end_8(reader, ObjectType::DataElement)?;
 
-        Ok(ObjectDataBlob(data.value()))
+        Ok(ObjectDataBlob(data))
     }
 }
@@ -7,6 +7,7 @@ use crate::fsshttpb::data::exguid::ExGuid;
 use crate::fsshttpb::data::object_types::ObjectType;
 use crate::fsshttpb::data::stream_object::ObjectHeader;
 use crate::fsshttpb::data_element::DataElement;
+use bytes::Bytes;
 use std::fmt;
 
 /// An object group.
@@ -115,7 +116,7 @@ pub(crate) enum ObjectGroupData {
     Object {
         group: Vec<ExGuid>,
         cells: Vec<CellId>,
-        data: Vec<u8>,
+        data: Bytes,
     },
     /// An excluded object.
     ///
@@ -225,6 +226,8 @@ impl DataElement {
                 break;
             }
 
+            reader.add_object()?;
+
             let object_header = ObjectHeader::parse(reader)?;
             match object_header.object_type {
                 ObjectType::ObjectGroupObject => {
@@ -275,7 +278,7 @@ impl DataElement {
         let mut declarations = vec![];
 
         loop {
-            if ObjectHeader::has_end_8(reader, ObjectType::ObjectGroupMetadataBlock)? {
+            if ObjectHeader::has_end_16(reader, ObjectType::ObjectGroupMetadataBlock)? {
                 break;
             }
 
@@ -287,7 +290,7 @@ impl DataElement {
             })
         }
 
-        ObjectHeader::try_parse_end_8(reader, ObjectType::ObjectGroupMetadataBlock)?;
+        ObjectHeader::try_parse_end_16(reader, ObjectType::ObjectGroupMetadataBlock)?;
 
         Ok(declarations)
     }
@@ -341,3 +344,31 @@ impl DataElement {
         Ok(objects)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ObjectChangeFrequency;
+    use crate::fsshttpb::data_element::DataElement;
+    use crate::reader::Reader;
+
+    #[test]
+    fn test_parse_metadata_block_end() {
+        // An object group metadata entry (0x78) with a change frequency of 1,
+        // followed by the end of the metadata block (0x79). The block's type
+        // doesn't fit into the 6 bits of an 8-bit end, so it uses a 16-bit end.
+        // See [MS-FSSHTTPB] 2.2.1.5.3 and 2.2.1.5.4.
+        let header = (0x2u32 | (0x78 << 3) | (1 << 17)).to_le_bytes();
+        let end = (0x3u16 | (0x79 << 2)).to_le_bytes();
+        let data = [&header[..], &[0x03], &end[..], &[0xff]].concat();
+
+        let mut reader = Reader::new(&data);
+        let metadata = DataElement::parse_object_group_metadata(&mut reader).unwrap();
+
+        assert_eq!(metadata.len(), 1);
+        assert!(matches!(
+            metadata[0].change_frequency,
+            ObjectChangeFrequency::Frequent
+        ));
+        assert_eq!(reader.bytes(), [0xff]);
+    }
+}
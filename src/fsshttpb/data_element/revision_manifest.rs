@@ -12,6 +12,7 @@ use crate::fsshttpb::data_element::DataElement;
 /// [\[MS-FSSHTTPB\] 2.2.1.12.5]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/eb3351db-8626-4804-a35b-f3eeda13c74d
 #[derive(Debug)]
 pub(crate) struct RevisionManifest {
+    #[cfg_attr(not(feature = "raw"), allow(dead_code))]
     pub(crate) rev_id: ExGuid,
     pub(crate) base_rev_id: ExGuid,
     pub(crate) root_declare: Vec<RevisionManifestRootDeclare>,
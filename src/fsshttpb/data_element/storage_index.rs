@@ -32,6 +32,21 @@ impl StorageIndex {
             .map(|mapping| mapping.revision_mapping)
     }
 
+    /// Add the mappings of another storage index.
+    ///
+    /// Mappings of the other index replace mappings for the same manifest,
+    /// cell or revision.
+    pub(crate) fn merge(&mut self, other: StorageIndex) {
+        for mapping in other.manifest_mappings {
+            self.manifest_mappings
+                .retain(|existing| existing.mapping_id != mapping.mapping_id);
+            self.manifest_mappings.push(mapping);
+        }
+
+        self.cell_mappings.extend(other.cell_mappings);
+        self.revision_mappings.extend(other.revision_mappings);
+    }
+
     pub(crate) fn find_revision_mapping_by_serial(&self, serial: &SerialNumber) -> Option<ExGuid> {
         self.revision_mappings
             .values()
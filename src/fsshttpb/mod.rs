@@ -10,3 +10,5 @@
 pub(crate) mod data;
 pub(crate) mod data_element;
 pub(crate) mod packaging;
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) mod writer;
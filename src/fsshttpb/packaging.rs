@@ -20,9 +20,24 @@ pub(crate) struct OneStorePackaging {
     pub(crate) file_format: Guid,
     pub(crate) storage_index: ExGuid,
     pub(crate) cell_schema: Guid,
+    pub(crate) versions: Option<FileVersions>,
     pub(crate) data_element_package: DataElementPackage,
 }
 
+/// The format versions from a revision store file header.
+///
+/// See [\[MS-ONESTORE\] 2.8.1]
+///
+/// [\[MS-ONESTORE\] 2.8.1]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/a2f046ea-109a-49c4-912d-dc2888cf0565
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(feature = "raw"), allow(dead_code))]
+pub(crate) struct FileVersions {
+    pub(crate) last_writer: u32,
+    pub(crate) oldest_writer: u32,
+    pub(crate) newest_writer: u32,
+    pub(crate) oldest_reader: u32,
+}
+
 impl OneStorePackaging {
     pub(crate) fn parse(reader: Reader) -> Result<OneStorePackaging> {
         let start = reader.remaining();
@@ -36,7 +51,14 @@ impl OneStorePackaging {
         let package_store_format = guid!("638DE92F-A6D4-4BC1-9A36-B3FC2511A5B7");
 
         if file_format == package_store_format {
-            return Self::parse_legacy(reader, file_type, file, legacy_file_version, file_format);
+            return Self::parse_legacy(
+                reader,
+                file_type,
+                file,
+                legacy_file_version,
+                file_format,
+                None,
+            );
         }
 
         if file_format != revision_store_format {
@@ -47,7 +69,14 @@ impl OneStorePackaging {
         }
 
         if !legacy_file_version.is_nil() {
-            return Self::parse_legacy(reader, file_type, file, legacy_file_version, file_format);
+            return Self::parse_legacy(
+                reader,
+                file_type,
+                file,
+                legacy_file_version,
+                file_format,
+                None,
+            );
         }
 
         Self::parse_packaging(
@@ -60,6 +89,16 @@ impl OneStorePackaging {
         )
     }
 
+    /// Apply an incremental update to the package.
+    ///
+    /// The `reader` must contain a FSSHTTPB data element package, e.g. from a
+    /// file synchronization response. See [`DataElementPackage::merge`].
+    pub(crate) fn apply_update(&mut self, reader: Reader) -> Result<()> {
+        let update = DataElementPackage::parse(reader)?;
+
+        self.data_element_package.merge(update, self.storage_index)
+    }
+
     fn parse_packaging(
         reader: Reader,
         _file_type: Guid,
@@ -129,7 +168,21 @@ impl OneStorePackaging {
         let legacy_file_version = Guid::parse(reader)?;
         let file_format = Guid::parse(reader)?;
 
-        Self::parse_legacy(reader, file_type, file, legacy_file_version, file_format)
+        let versions = FileVersions {
+            last_writer: last_writer_version,
+            oldest_writer: oldest_writer_version,
+            newest_writer: newest_writer_version,
+            oldest_reader: oldest_reader_version,
+        };
+
+        Self::parse_legacy(
+            reader,
+            file_type,
+            file,
+            legacy_file_version,
+            file_format,
+            Some(versions),
+        )
     }
 
     fn parse_legacy(
@@ -138,6 +191,7 @@ impl OneStorePackaging {
         file: Guid,
         legacy_file_version: Guid,
         file_format: Guid,
+        versions: Option<FileVersions>,
     ) -> Result<OneStorePackaging> {
         if reader.get_u32()? != 0 {
             return Err(ErrorKind::MalformedFssHttpBData("invalid padding data".into()).into());
@@ -159,6 +213,7 @@ impl OneStorePackaging {
             file_format,
             storage_index,
             cell_schema,
+            versions,
             data_element_package,
         })
     }
@@ -0,0 +1,813 @@
+//! An encoder for the FSSHTTPB packaging format.
+//!
+//! The writer produces the binary structures read by the parsers in this module. It is used to
+//! build synthetic packages for unit tests and fuzzing and doesn't aim to produce files that
+//! OneNote can open.
+
+// Not all encoders are used by the unit tests
+#![cfg_attr(not(feature = "fuzzing"), allow(dead_code))]
+
+use crate::fsshttpb::data::cell_id::CellId;
+use crate::fsshttpb::data::exguid::ExGuid;
+use crate::fsshttpb::data::object_types::ObjectType;
+use crate::shared::guid::Guid;
+use num_traits::ToPrimitive;
+
+/// A serial number as stored in data elements.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.9].
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.9]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/9db15fa4-0dc2-4b17-b091-d33886d8a0f6
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Serial {
+    Null,
+    Value(Guid, u64),
+}
+
+/// A data element to encode.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12.1].
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.12.1]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/f0901ac0-4f26-413f-805b-a6830781f64c
+#[derive(Debug, Clone)]
+pub(crate) enum Element {
+    StorageIndex {
+        manifest_mappings: Vec<(ExGuid, Serial)>,
+        cell_mappings: Vec<(CellId, ExGuid, Serial)>,
+        revision_mappings: Vec<(ExGuid, ExGuid, Serial)>,
+    },
+    StorageManifest {
+        schema: Guid,
+        roots: Vec<(ExGuid, CellId)>,
+    },
+    CellManifest {
+        revision: ExGuid,
+    },
+    RevisionManifest {
+        rev_id: ExGuid,
+        base_rev_id: ExGuid,
+        roots: Vec<(ExGuid, ExGuid)>,
+        group_references: Vec<ExGuid>,
+    },
+    ObjectGroup {
+        declarations: Vec<Declaration>,
+        metadata: Option<Vec<u64>>,
+        objects: Vec<GroupData>,
+    },
+    Fragment {
+        id: ExGuid,
+        size: u64,
+        offset: u64,
+        length: u64,
+        data: Vec<u8>,
+    },
+    Blob {
+        data: Vec<u8>,
+    },
+}
+
+/// An object group declaration.
+#[derive(Debug, Clone)]
+pub(crate) enum Declaration {
+    Object {
+        object_id: ExGuid,
+        partition_id: u64,
+        data_size: u64,
+        object_reference_count: u64,
+        cell_reference_count: u64,
+    },
+    Blob {
+        object_id: ExGuid,
+        blob_id: ExGuid,
+        partition_id: u64,
+        object_reference_count: u64,
+        cell_reference_count: u64,
+    },
+}
+
+/// The data of an object in an object group.
+#[derive(Debug, Clone)]
+pub(crate) enum GroupData {
+    Object {
+        group: Vec<ExGuid>,
+        cells: Vec<CellId>,
+        data: Vec<u8>,
+    },
+    Excluded {
+        group: Vec<ExGuid>,
+        cells: Vec<CellId>,
+        size: u64,
+    },
+    BlobReference {
+        objects: Vec<ExGuid>,
+        cells: Vec<CellId>,
+        blob: ExGuid,
+    },
+}
+
+/// A OneStore property value.
+///
+/// See [\[MS-ONESTORE\] 2.6.6].
+///
+/// [\[MS-ONESTORE\] 2.6.6]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/17d8c39e-6cc2-4fcd-8d10-aee950fd0ab2
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    Empty,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Vec(Vec<u8>),
+    ObjectId,
+    ObjectIds(u32),
+    ObjectSpaceId,
+    ObjectSpaceIds(u32),
+    ContextId,
+    ContextIds(u32),
+    PropertyValues(u32, Vec<Vec<(u32, Value)>>),
+    PropertySet(Vec<(u32, Value)>),
+}
+
+impl Value {
+    fn prop_type(&self) -> u32 {
+        match self {
+            Value::Empty => 0x1,
+            Value::Bool(_) => 0x2,
+            Value::U8(_) => 0x3,
+            Value::U16(_) => 0x4,
+            Value::U32(_) => 0x5,
+            Value::U64(_) => 0x6,
+            Value::Vec(_) => 0x7,
+            Value::ObjectId => 0x8,
+            Value::ObjectIds(_) => 0x9,
+            Value::ObjectSpaceId => 0xA,
+            Value::ObjectSpaceIds(_) => 0xB,
+            Value::ContextId => 0xC,
+            Value::ContextIds(_) => 0xD,
+            Value::PropertyValues(..) => 0x10,
+            Value::PropertySet(_) => 0x11,
+        }
+    }
+
+    /// The encoded property ID for a property with the ID `id`.
+    fn property_id(&self, id: u32) -> u32 {
+        let flag = matches!(self, Value::Bool(true)) as u32;
+
+        flag << 31 | self.prop_type() << 26 | id & 0x3ffffff
+    }
+}
+
+/// An object's properties and references.
+///
+/// See [\[MS-ONESTORE\] 2.1.1]. The references are encoded as compact IDs.
+///
+/// [\[MS-ONESTORE\] 2.1.1]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/e9fb4b61-5128-45dd-9a96-6bad6f11dc18
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PropSet {
+    pub(crate) object_ids: Vec<u32>,
+    pub(crate) object_space_ids: Vec<u32>,
+    pub(crate) context_ids: Vec<u32>,
+    pub(crate) properties: Vec<(u32, Value)>,
+}
+
+/// A FSSHTTPB encoder.
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    data: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn new() -> Writer {
+        Writer::default()
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub(crate) fn put_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub(crate) fn put_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn put_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn put_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn put_bytes(&mut self, value: &[u8]) {
+        self.data.extend_from_slice(value);
+    }
+
+    pub(crate) fn put_guid(&mut self, guid: Guid) {
+        self.data.extend_from_slice(&guid.0.to_bytes_le());
+    }
+
+    /// See [\[MS-FSSHTTPB\] 2.2.1.1].
+    ///
+    /// [\[MS-FSSHTTPB\] 2.2.1.1]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/8eb74ebe-81d1-4569-a29a-308a6128a52f
+    pub(crate) fn put_compact_u64(&mut self, value: u64) {
+        if value == 0 {
+            self.put_u8(0);
+            return;
+        }
+
+        // Use the shortest encoding that fits: `width` bytes store `7 * width` bits of
+        // the value with the lowest set bit marking the width.
+        for width in 1..=7u32 {
+            if value < 1 << (7 * width) {
+                let encoded = value << width | 1 << (width - 1);
+                self.put_bytes(&encoded.to_le_bytes()[..width as usize]);
+                return;
+            }
+        }
+
+        self.put_u8(0x80);
+        self.put_u64(value);
+    }
+
+    /// See [\[MS-FSSHTTPB\] 2.2.1.7].
+    ///
+    /// [\[MS-FSSHTTPB\] 2.2.1.7]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/bff58e9f-8222-4fbb-b112-5826d5febedd
+    pub(crate) fn put_exguid(&mut self, id: ExGuid) {
+        if id.is_nil() {
+            self.put_u8(0);
+            return;
+        }
+
+        match id.value {
+            v if v < 1 << 5 => self.put_u8((v << 3) as u8 | 0x4),
+            v if v < 1 << 10 => {
+                self.put_u8(((v & 0x3) << 6) as u8 | 0x20);
+                self.put_u8((v >> 2) as u8);
+            }
+            v if v < 1 << 17 => {
+                self.put_u8(((v & 0x1) << 7) as u8 | 0x40);
+                self.put_u16((v >> 1) as u16);
+            }
+            v => {
+                self.put_u8(0x80);
+                self.put_u32(v);
+            }
+        }
+
+        self.put_guid(id.guid);
+    }
+
+    pub(crate) fn put_exguid_array(&mut self, ids: &[ExGuid]) {
+        self.put_compact_u64(ids.len() as u64);
+
+        for id in ids {
+            self.put_exguid(*id);
+        }
+    }
+
+    pub(crate) fn put_cell_id(&mut self, id: CellId) {
+        self.put_exguid(id.0);
+        self.put_exguid(id.1);
+    }
+
+    pub(crate) fn put_cell_id_array(&mut self, ids: &[CellId]) {
+        self.put_compact_u64(ids.len() as u64);
+
+        for id in ids {
+            self.put_cell_id(*id);
+        }
+    }
+
+    pub(crate) fn put_serial(&mut self, serial: Serial) {
+        match serial {
+            Serial::Null => self.put_u8(0),
+            Serial::Value(guid, value) => {
+                self.put_u8(1);
+                self.put_guid(guid);
+                self.put_u64(value);
+            }
+        }
+    }
+
+    pub(crate) fn put_binary_item(&mut self, data: &[u8]) {
+        self.put_compact_u64(data.len() as u64);
+        self.put_bytes(data);
+    }
+
+    /// Write a 16 bit stream object header.
+    ///
+    /// The parsers don't rely on the header's length field so it's always set to zero.
+    pub(crate) fn put_header_16(&mut self, object_type: ObjectType, compound: bool) {
+        let object_type = object_type.to_u16().expect("object type fits into u16");
+        assert!(object_type <= 0x3f, "object type requires a 32 bit header");
+
+        self.put_u16((compound as u16) << 2 | object_type << 3);
+    }
+
+    /// Write a 32 bit stream object header.
+    pub(crate) fn put_header_32(&mut self, object_type: ObjectType, compound: bool) {
+        let object_type = object_type.to_u32().expect("object type fits into u32");
+
+        self.put_u32(0x2 | (compound as u32) << 2 | object_type << 3);
+    }
+
+    pub(crate) fn put_end_8(&mut self, object_type: ObjectType) {
+        let object_type = object_type.to_u8().expect("object type fits into u8");
+
+        self.put_u8(0x1 | object_type << 2);
+    }
+
+    pub(crate) fn put_end_16(&mut self, object_type: ObjectType) {
+        let object_type = object_type.to_u16().expect("object type fits into u16");
+
+        self.put_u16(0x3 | object_type << 2);
+    }
+
+    /// Write a OneStore package containing the data elements.
+    ///
+    /// See [\[MS-ONESTORE\] 2.8.1].
+    ///
+    /// [\[MS-ONESTORE\] 2.8.1]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/a2f046ea-109a-49c4-912d-dc2888cf0565
+    pub(crate) fn put_packaging(
+        &mut self,
+        file_type: Guid,
+        storage_index: ExGuid,
+        cell_schema: Guid,
+        elements: &[(ExGuid, Serial, Element)],
+    ) {
+        self.put_guid(file_type);
+        self.put_guid(Guid::nil());
+        self.put_guid(Guid::nil());
+        self.put_guid(guid!("638DE92F-A6D4-4BC1-9A36-B3FC2511A5B7"));
+        self.put_u32(0);
+
+        self.put_header_32(ObjectType::OneNotePackaging, false);
+        self.put_exguid(storage_index);
+        self.put_guid(cell_schema);
+        self.put_data_element_package(elements);
+        self.put_end_16(ObjectType::OneNotePackaging);
+    }
+
+    /// See [\[MS-FSSHTTPB\] 2.2.1.12].
+    ///
+    /// [\[MS-FSSHTTPB\] 2.2.1.12]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/99a25464-99b5-4262-a964-baabed2170eb
+    pub(crate) fn put_data_element_package(&mut self, elements: &[(ExGuid, Serial, Element)]) {
+        self.put_header_16(ObjectType::DataElementPackage, false);
+        self.put_u8(0);
+
+        for (id, serial, element) in elements {
+            self.put_data_element(*id, *serial, element);
+        }
+
+        self.put_end_8(ObjectType::DataElementPackage);
+    }
+
+    pub(crate) fn put_data_element(&mut self, id: ExGuid, serial: Serial, element: &Element) {
+        self.put_header_16(ObjectType::DataElement, true);
+        self.put_exguid(id);
+        self.put_serial(serial);
+
+        match element {
+            Element::StorageIndex {
+                manifest_mappings,
+                cell_mappings,
+                revision_mappings,
+            } => {
+                self.put_compact_u64(0x01);
+
+                for (mapping_id, serial) in manifest_mappings {
+                    self.put_header_16(ObjectType::StorageIndexManifestMapping, false);
+                    self.put_exguid(*mapping_id);
+                    self.put_serial(*serial);
+                }
+
+                for (cell_id, id, serial) in cell_mappings {
+                    self.put_header_16(ObjectType::StorageIndexCellMapping, false);
+                    self.put_cell_id(*cell_id);
+                    self.put_exguid(*id);
+                    self.put_serial(*serial);
+                }
+
+                for (id, revision_mapping, serial) in revision_mappings {
+                    self.put_header_16(ObjectType::StorageIndexRevisionMapping, false);
+                    self.put_exguid(*id);
+                    self.put_exguid(*revision_mapping);
+                    self.put_serial(*serial);
+                }
+            }
+            Element::StorageManifest { schema, roots } => {
+                self.put_compact_u64(0x02);
+                self.put_header_16(ObjectType::StorageManifest, true);
+                self.put_guid(*schema);
+
+                for (root_manifest, cell) in roots {
+                    self.put_header_16(ObjectType::StorageManifestRoot, false);
+                    self.put_exguid(*root_manifest);
+                    self.put_cell_id(*cell);
+                }
+            }
+            Element::CellManifest { revision } => {
+                self.put_compact_u64(0x03);
+                self.put_header_16(ObjectType::CellManifest, false);
+                self.put_exguid(*revision);
+            }
+            Element::RevisionManifest {
+                rev_id,
+                base_rev_id,
+                roots,
+                group_references,
+            } => {
+                self.put_compact_u64(0x04);
+                self.put_header_16(ObjectType::RevisionManifest, true);
+                self.put_exguid(*rev_id);
+                self.put_exguid(*base_rev_id);
+
+                for (root_id, object_id) in roots {
+                    self.put_header_16(ObjectType::RevisionManifestRoot, false);
+                    self.put_exguid(*root_id);
+                    self.put_exguid(*object_id);
+                }
+
+                for reference in group_references {
+                    self.put_header_16(ObjectType::RevisionManifestGroupReference, false);
+                    self.put_exguid(*reference);
+                }
+            }
+            Element::ObjectGroup {
+                declarations,
+                metadata,
+                objects,
+            } => {
+                self.put_compact_u64(0x05);
+                self.put_object_group(declarations, metadata.as_deref(), objects);
+            }
+            Element::Fragment {
+                id,
+                size,
+                offset,
+                length,
+                data,
+            } => {
+                self.put_compact_u64(0x06);
+                self.put_header_32(ObjectType::DataElementFragment, false);
+                self.put_exguid(*id);
+                self.put_compact_u64(*size);
+                self.put_compact_u64(*offset);
+                self.put_compact_u64(*length);
+                self.put_bytes(data);
+
+                // Fragments don't have an end marker
+                return;
+            }
+            Element::Blob { data } => {
+                self.put_compact_u64(0x0A);
+                self.put_header_32(ObjectType::ObjectDataBlob, false);
+                self.put_binary_item(data);
+            }
+        }
+
+        self.put_end_8(ObjectType::DataElement);
+    }
+
+    /// See [\[MS-FSSHTTPB\] 2.2.1.12.6].
+    ///
+    /// [\[MS-FSSHTTPB\] 2.2.1.12.6]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/21404be6-0334-490e-80b5-82fccb9c04af
+    fn put_object_group(
+        &mut self,
+        declarations: &[Declaration],
+        metadata: Option<&[u64]>,
+        objects: &[GroupData],
+    ) {
+        self.put_header_16(ObjectType::ObjectGroupDeclaration, true);
+
+        for declaration in declarations {
+            match declaration {
+                Declaration::Object {
+                    object_id,
+                    partition_id,
+                    data_size,
+                    object_reference_count,
+                    cell_reference_count,
+                } => {
+                    self.put_header_16(ObjectType::ObjectGroupObject, false);
+                    self.put_exguid(*object_id);
+                    self.put_compact_u64(*partition_id);
+                    self.put_compact_u64(*data_size);
+                    self.put_compact_u64(*object_reference_count);
+                    self.put_compact_u64(*cell_reference_count);
+                }
+                Declaration::Blob {
+                    object_id,
+                    blob_id,
+                    partition_id,
+                    object_reference_count,
+                    cell_reference_count,
+                } => {
+                    self.put_header_16(ObjectType::ObjectGroupDataBlob, false);
+                    self.put_exguid(*object_id);
+                    self.put_exguid(*blob_id);
+                    self.put_compact_u64(*partition_id);
+                    self.put_compact_u64(*object_reference_count);
+                    self.put_compact_u64(*cell_reference_count);
+                }
+            }
+        }
+
+        self.put_end_8(ObjectType::ObjectGroupDeclaration);
+
+        if let Some(metadata) = metadata {
+            self.put_header_32(ObjectType::ObjectGroupMetadataBlock, true);
+
+            for frequency in metadata {
+                self.put_header_32(ObjectType::ObjectGroupMetadata, false);
+                self.put_compact_u64(*frequency);
+            }
+
+            self.put_end_16(ObjectType::ObjectGroupMetadataBlock);
+        }
+
+        self.put_header_16(ObjectType::ObjectGroupData, true);
+
+        for object in objects {
+            match object {
+                GroupData::Object { group, cells, data } => {
+                    self.put_header_16(ObjectType::ObjectGroupDataObject, false);
+                    self.put_exguid_array(group);
+                    self.put_cell_id_array(cells);
+                    self.put_binary_item(data);
+                }
+                GroupData::Excluded { group, cells, size } => {
+                    self.put_header_16(ObjectType::ObjectGroupDataExcluded, false);
+                    self.put_exguid_array(group);
+                    self.put_cell_id_array(cells);
+                    self.put_compact_u64(*size);
+                }
+                GroupData::BlobReference {
+                    objects,
+                    cells,
+                    blob,
+                } => {
+                    self.put_header_16(ObjectType::ObjectGroupBlobReference, false);
+                    self.put_exguid_array(objects);
+                    self.put_cell_id_array(cells);
+                    self.put_exguid(*blob);
+                }
+            }
+        }
+
+        self.put_end_8(ObjectType::ObjectGroupData);
+    }
+
+    /// Write an object's property set and the streams of its references.
+    pub(crate) fn put_object_prop_set(&mut self, prop_set: &PropSet) {
+        let has_osids = !prop_set.object_space_ids.is_empty() || !prop_set.context_ids.is_empty();
+        let has_context_ids = !prop_set.context_ids.is_empty();
+
+        self.put_stream_header(prop_set.object_ids.len(), has_context_ids, !has_osids);
+        self.put_compact_ids(&prop_set.object_ids);
+
+        if has_osids {
+            self.put_stream_header(prop_set.object_space_ids.len(), has_context_ids, false);
+            self.put_compact_ids(&prop_set.object_space_ids);
+
+            if has_context_ids {
+                self.put_stream_header(prop_set.context_ids.len(), false, false);
+                self.put_compact_ids(&prop_set.context_ids);
+            }
+        }
+
+        self.put_property_set(&prop_set.properties);
+    }
+
+    fn put_stream_header(&mut self, count: usize, extended_streams: bool, no_osids: bool) {
+        let count = u32::try_from(count).expect("stream fits into the header") & 0xFFFFFF;
+
+        self.put_u32(count | (extended_streams as u32) << 30 | (no_osids as u32) << 31);
+    }
+
+    fn put_compact_ids(&mut self, ids: &[u32]) {
+        for id in ids {
+            self.put_u32(*id);
+        }
+    }
+
+    /// See [\[MS-ONESTORE\] 2.6.7].
+    ///
+    /// [\[MS-ONESTORE\] 2.6.7]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/88a64c18-f815-4ebc-8590-ddd432024ab9
+    pub(crate) fn put_property_set(&mut self, properties: &[(u32, Value)]) {
+        self.put_u16(u16::try_from(properties.len()).expect("property count fits into u16"));
+
+        for (id, value) in properties {
+            self.put_u32(value.property_id(*id));
+        }
+
+        for (_, value) in properties {
+            self.put_property_value(value);
+        }
+    }
+
+    fn put_property_value(&mut self, value: &Value) {
+        match value {
+            Value::Empty
+            | Value::Bool(_)
+            | Value::ObjectId
+            | Value::ObjectSpaceId
+            | Value::ContextId => {}
+            Value::U8(v) => self.put_u8(*v),
+            Value::U16(v) => self.put_u16(*v),
+            Value::U32(v) => self.put_u32(*v),
+            Value::U64(v) => self.put_u64(*v),
+            Value::Vec(data) => {
+                self.put_u32(u32::try_from(data.len()).expect("data fits into u32"));
+                self.put_bytes(data);
+            }
+            Value::ObjectIds(count) | Value::ObjectSpaceIds(count) | Value::ContextIds(count) => {
+                self.put_u32(*count)
+            }
+            Value::PropertyValues(id, sets) => {
+                self.put_u32(u32::try_from(sets.len()).expect("count fits into u32"));
+                self.put_u32(Value::PropertySet(vec![]).property_id(*id));
+
+                for set in sets {
+                    self.put_property_set(set);
+                }
+            }
+            Value::PropertySet(properties) => self.put_property_set(properties),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Declaration, Element, GroupData, PropSet, Serial, Value, Writer};
+    use crate::fsshttpb::data::cell_id::CellId;
+    use crate::fsshttpb::data::compact_u64::CompactU64;
+    use crate::fsshttpb::data::exguid::ExGuid;
+    use crate::fsshttpb::packaging::OneStorePackaging;
+    use crate::onestore::types::object_prop_set::ObjectPropSet;
+    use crate::onestore::types::property::PropertyId;
+    use crate::reader::Reader;
+
+    #[test]
+    fn test_compact_u64_round_trip() {
+        for value in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, 1 << 40, 1 << 56, u64::MAX] {
+            let mut writer = Writer::new();
+            writer.put_compact_u64(value);
+            let data = writer.into_bytes();
+
+            let mut reader = Reader::new(&data);
+            assert_eq!(CompactU64::parse(&mut reader).unwrap().value(), value);
+            assert_eq!(reader.remaining(), 0);
+        }
+    }
+
+    #[test]
+    fn test_exguid_round_trip() {
+        let guid = guid!("1A5A319C-C26B-41AA-B9C5-9BD8C44E07D4");
+
+        for value in [0, 1, 31, 32, 1023, 1024, 0x1ffff, 0x20000, u32::MAX] {
+            let id = ExGuid::from_guid(guid, value);
+
+            let mut writer = Writer::new();
+            writer.put_exguid(id);
+            let data = writer.into_bytes();
+
+            let mut reader = Reader::new(&data);
+            assert_eq!(ExGuid::parse(&mut reader).unwrap(), id);
+            assert_eq!(reader.remaining(), 0);
+        }
+    }
+
+    #[test]
+    fn test_packaging_round_trip() {
+        let guid = guid!("1A5A319C-C26B-41AA-B9C5-9BD8C44E07D4");
+        let id = |value| ExGuid::from_guid(guid, value);
+        let cell = CellId(id(1), id(2));
+
+        let elements = vec![
+            (
+                id(10),
+                Serial::Value(guid, 1),
+                Element::StorageIndex {
+                    manifest_mappings: vec![(id(11), Serial::Null)],
+                    cell_mappings: vec![(cell, id(12), Serial::Null)],
+                    revision_mappings: vec![(id(13), id(14), Serial::Null)],
+                },
+            ),
+            (
+                id(11),
+                Serial::Null,
+                Element::StorageManifest {
+                    schema: guid,
+                    roots: vec![(id(1), cell)],
+                },
+            ),
+            (
+                id(12),
+                Serial::Null,
+                Element::CellManifest { revision: id(13) },
+            ),
+            (
+                id(14),
+                Serial::Null,
+                Element::RevisionManifest {
+                    rev_id: id(13),
+                    base_rev_id: ExGuid::from_guid(guid, 0),
+                    roots: vec![(id(1), id(20))],
+                    group_references: vec![id(15)],
+                },
+            ),
+            (
+                id(15),
+                Serial::Null,
+                Element::ObjectGroup {
+                    declarations: vec![Declaration::Object {
+                        object_id: id(20),
+                        partition_id: 1,
+                        data_size: 3,
+                        object_reference_count: 0,
+                        cell_reference_count: 0,
+                    }],
+                    metadata: Some(vec![1]),
+                    objects: vec![GroupData::Object {
+                        group: vec![],
+                        cells: vec![],
+                        data: vec![1, 2, 3],
+                    }],
+                },
+            ),
+            (
+                id(16),
+                Serial::Null,
+                Element::Blob {
+                    data: vec![4, 5, 6],
+                },
+            ),
+        ];
+
+        let mut writer = Writer::new();
+        writer.put_packaging(guid, id(10), guid, &elements);
+        let data = writer.into_bytes();
+
+        let mut reader = Reader::new(&data);
+        let packaging = OneStorePackaging::parse(&mut reader).unwrap();
+        let package = packaging.data_element_package;
+
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!(packaging.storage_index, id(10));
+        assert_eq!(package.storage_indexes.len(), 1);
+        assert_eq!(package.storage_manifests[&id(11)].roots[&id(1)], cell);
+        assert_eq!(package.cell_manifests[&id(12)], id(13));
+        assert_eq!(
+            package.revision_manifests[&id(14)].group_references,
+            [id(15)]
+        );
+        assert_eq!(package.object_groups[&id(15)].objects.len(), 1);
+        assert_eq!(package.find_blob(id(16)).unwrap()[..], [4, 5, 6]);
+    }
+
+    #[test]
+    fn test_object_prop_set_round_trip() {
+        let prop_set = PropSet {
+            object_ids: vec![0x100, 0x201],
+            object_space_ids: vec![],
+            context_ids: vec![0x300],
+            properties: vec![
+                (0x1C04, Value::Bool(true)),
+                (0x1C05, Value::ObjectIds(2)),
+                (0x1C06, Value::Vec(vec![1, 2])),
+                (
+                    0x1C07,
+                    Value::PropertyValues(0x1C08, vec![vec![(0x1C09, Value::U32(7))]]),
+                ),
+            ],
+        };
+
+        let mut writer = Writer::new();
+        writer.put_object_prop_set(&prop_set);
+        let data = writer.into_bytes();
+
+        let mut reader = Reader::new(&data);
+        let parsed = ObjectPropSet::parse(&mut reader).unwrap();
+
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!(parsed.object_ids().len(), 2);
+        assert_eq!(parsed.context_ids().len(), 1);
+
+        let properties = parsed.properties();
+        let get = |id| properties.get(PropertyId::new(id)).unwrap();
+        assert_eq!(get(0x1C04).to_bool(), Some(true));
+        assert_eq!(get(0x1C05).to_object_ids(), Some(2));
+        assert_eq!(get(0x1C06).to_vec(), Some(&[1, 2][..]));
+
+        let (_, sets) = get(0x1C07).to_property_values().unwrap();
+        assert_eq!(
+            sets[0].get(PropertyId::new(0x1C09)).unwrap().to_u32(),
+            Some(7)
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Entry points for the fuzz targets in `fuzz/` and the benchmarks in `benches/`.
+//!
+//! This module exposes the internal parsers, a structure-aware input generator and synthetic
+//! benchmark inputs. It isn't covered by semantic versioning.
+
+use crate::Parser;
+use crate::errors::Result;
+use crate::fsshttpb::data::cell_id::CellId;
+use crate::fsshttpb::data::exguid::ExGuid;
+use crate::fsshttpb::data_element::DataElementPackage;
+use crate::fsshttpb::packaging::OneStorePackaging;
+use crate::fsshttpb::writer::{Declaration, Element, GroupData, PropSet, Serial, Value, Writer};
+use crate::limits::{Budget, ParseLimits};
+use crate::onestore::types::object_prop_set::ObjectPropSet;
+use crate::reader::Reader;
+use crate::shared::guid::Guid;
+use crate::shared::multi_byte;
+use crate::test_support::{StoreBuilder, page_space, section_root};
+use arbitrary::Arbitrary;
+use std::path::Path;
+
+/// Parse a OneStore package.
+pub fn parse_packaging(data: &[u8]) -> Result<()> {
+    let budget = Budget::new(ParseLimits::default());
+    OneStorePackaging::parse(&mut Reader::with_budget(data, &budget))?;
+
+    Ok(())
+}
+
+/// Parse a FSSHTTPB data element package.
+pub fn parse_data_element_package(data: &[u8]) -> Result<()> {
+    let budget = Budget::new(ParseLimits::default());
+    DataElementPackage::parse(&mut Reader::with_budget(data, &budget))?;
+
+    Ok(())
+}
+
+/// Parse an object's property set.
+pub fn parse_object_prop_set(data: &[u8]) -> Result<()> {
+    let budget = Budget::new(ParseLimits::default());
+    ObjectPropSet::parse(&mut Reader::with_budget(data, &budget))?;
+
+    Ok(())
+}
+
+/// Decode multi-byte encoded signed numbers.
+pub fn decode_signed(data: &[u8]) -> Result<Vec<i64>> {
+    multi_byte::decode_signed(data)
+}
+
+/// Parse a section file.
+pub fn parse_section(data: &[u8]) -> Result<()> {
+    Parser::new().parse_section_buffer(data, Path::new("Fuzz.one"))?;
+
+    Ok(())
+}
+
+/// Encode a section with `pages` pages that share a chain of `revisions` revisions.
+///
+/// Every revision of the chain adds 20 objects, so each page's object space contains
+/// `20 * revisions` objects besides its page content.
+pub fn synthetic_section(pages: u32, revisions: u32) -> Vec<u8> {
+    let pages: Vec<_> = (0..pages)
+        .map(|index| page_space(0x100 + index, index as u64 + 2, 10.0))
+        .collect();
+    let page_ids: Vec<_> = pages.iter().map(|page| page.cell_id()).collect();
+
+    pages
+        .into_iter()
+        .fold(
+            StoreBuilder::section(section_root(1, &page_ids)),
+            StoreBuilder::space,
+        )
+        .shared_revisions(revisions, 20)
+        .build()
+}
+
+/// The GUIDs that IDs are picked from.
+///
+/// Using a small pool makes it likely that generated IDs reference each other.
+const GUIDS: [&str; 3] = [
+    "1A5A319C-C26B-41AA-B9C5-9BD8C44E07D4",
+    "7111497F-1B6B-4209-9491-C98B04CF4C5A",
+    "22A8C031-3600-42EE-B714-D7ACDA2435E8",
+];
+
+/// A structurally valid FSSHTTPB package.
+///
+/// The package passes the header and framing checks of the parser so fuzzing
+/// exercises the data element and object parsers. Use [`ArbitraryPackage::to_bytes`]
+/// to encode it.
+#[derive(Debug, Arbitrary)]
+pub struct ArbitraryPackage {
+    file_type: Id,
+    storage_index: Id,
+    cell_schema: Id,
+    elements: Vec<(Id, ArbitrarySerial, ArbitraryElement)>,
+}
+
+impl ArbitraryPackage {
+    /// Encode the package.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let elements: Vec<_> = self
+            .elements
+            .iter()
+            .map(|(id, serial, element)| (id.exguid(), serial.serial(), element.element()))
+            .collect();
+
+        let mut writer = Writer::new();
+        writer.put_packaging(
+            self.file_type.guid(),
+            self.storage_index.exguid(),
+            self.cell_schema.guid(),
+            &elements,
+        );
+
+        writer.into_bytes()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Arbitrary)]
+struct Id {
+    guid: u8,
+    value: u16,
+}
+
+impl Id {
+    fn guid(&self) -> Guid {
+        match GUIDS.get(self.guid as usize % (GUIDS.len() + 1)) {
+            Some(guid) => Guid::from_str(guid).expect("valid guid"),
+            None => Guid::nil(),
+        }
+    }
+
+    fn exguid(&self) -> ExGuid {
+        ExGuid::from_guid(self.guid(), self.value as u32)
+    }
+
+    fn cell_id(pair: &(Id, Id)) -> CellId {
+        CellId(pair.0.exguid(), pair.1.exguid())
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum ArbitrarySerial {
+    Null,
+    Value(Id, u64),
+}
+
+impl ArbitrarySerial {
+    fn serial(&self) -> Serial {
+        match self {
+            ArbitrarySerial::Null => Serial::Null,
+            ArbitrarySerial::Value(id, value) => Serial::Value(id.guid(), *value),
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum ArbitraryElement {
+    StorageIndex {
+        manifest_mappings: Vec<(Id, ArbitrarySerial)>,
+        cell_mappings: Vec<((Id, Id), Id, ArbitrarySerial)>,
+        revision_mappings: Vec<(Id, Id, ArbitrarySerial)>,
+    },
+    StorageManifest {
+        schema: Id,
+        roots: Vec<(Id, (Id, Id))>,
+    },
+    CellManifest {
+        revision: Id,
+    },
+    RevisionManifest {
+        rev_id: Id,
+        base_rev_id: Id,
+        roots: Vec<(Id, Id)>,
+        group_references: Vec<Id>,
+    },
+    ObjectGroup {
+        declarations: Vec<ArbitraryDeclaration>,
+        metadata: Option<Vec<u8>>,
+        objects: Vec<ArbitraryGroupData>,
+    },
+    Fragment {
+        id: Id,
+        offset: u64,
+        length: u64,
+        data: Vec<u8>,
+    },
+    Blob {
+        data: Vec<u8>,
+    },
+}
+
+impl ArbitraryElement {
+    fn element(&self) -> Element {
+        match self {
+            ArbitraryElement::StorageIndex {
+                manifest_mappings,
+                cell_mappings,
+                revision_mappings,
+            } => Element::StorageIndex {
+                manifest_mappings: manifest_mappings
+                    .iter()
+                    .map(|(id, serial)| (id.exguid(), serial.serial()))
+                    .collect(),
+                cell_mappings: cell_mappings
+                    .iter()
+                    .map(|(cell, id, serial)| (Id::cell_id(cell), id.exguid(), serial.serial()))
+                    .collect(),
+                revision_mappings: revision_mappings
+                    .iter()
+                    .map(|(id, mapping, serial)| (id.exguid(), mapping.exguid(), serial.serial()))
+                    .collect(),
+            },
+            ArbitraryElement::StorageManifest { schema, roots } => Element::StorageManifest {
+                schema: schema.guid(),
+                roots: roots
+                    .iter()
+                    .map(|(id, cell)| (id.exguid(), Id::cell_id(cell)))
+                    .collect(),
+            },
+            ArbitraryElement::CellManifest { revision } => Element::CellManifest {
+                revision: revision.exguid(),
+            },
+            ArbitraryElement::RevisionManifest {
+                rev_id,
+                base_rev_id,
+                roots,
+                group_references,
+            } => Element::RevisionManifest {
+                rev_id: rev_id.exguid(),
+                base_rev_id: base_rev_id.exguid(),
+                roots: roots
+                    .iter()
+                    .map(|(root, object)| (root.exguid(), object.exguid()))
+                    .collect(),
+                group_references: group_references.iter().map(Id::exguid).collect(),
+            },
+            ArbitraryElement::ObjectGroup {
+                declarations,
+                metadata,
+                objects,
+            } => Element::ObjectGroup {
+                declarations: declarations
+                    .iter()
+                    .map(ArbitraryDeclaration::declaration)
+                    .collect(),
+                // Only the known change frequencies are valid
+                metadata: metadata
+                    .as_ref()
+                    .map(|metadata| metadata.iter().map(|v| *v as u64 % 5).collect()),
+                objects: objects.iter().map(ArbitraryGroupData::data).collect(),
+            },
+            ArbitraryElement::Fragment {
+                id,
+                offset,
+                length,
+                data,
+            } => Element::Fragment {
+                id: id.exguid(),
+                size: data.len() as u64,
+                offset: *offset,
+                length: *length,
+                data: data.clone(),
+            },
+            ArbitraryElement::Blob { data } => Element::Blob { data: data.clone() },
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum ArbitraryDeclaration {
+    Object {
+        object_id: Id,
+        partition_id: u8,
+        data_size: u32,
+        object_reference_count: u8,
+        cell_reference_count: u8,
+    },
+    Blob {
+        object_id: Id,
+        blob_id: Id,
+        partition_id: u8,
+        object_reference_count: u8,
+        cell_reference_count: u8,
+    },
+}
+
+impl ArbitraryDeclaration {
+    fn declaration(&self) -> Declaration {
+        match *self {
+            ArbitraryDeclaration::Object {
+                object_id,
+                partition_id,
+                data_size,
+                object_reference_count,
+                cell_reference_count,
+            } => Declaration::Object {
+                object_id: object_id.exguid(),
+                partition_id: partition_id as u64,
+                data_size: data_size as u64,
+                object_reference_count: object_reference_count as u64,
+                cell_reference_count: cell_reference_count as u64,
+            },
+            ArbitraryDeclaration::Blob {
+                object_id,
+                blob_id,
+                partition_id,
+                object_reference_count,
+                cell_reference_count,
+            } => Declaration::Blob {
+                object_id: object_id.exguid(),
+                blob_id: blob_id.exguid(),
+                partition_id: partition_id as u64,
+                object_reference_count: object_reference_count as u64,
+                cell_reference_count: cell_reference_count as u64,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum ArbitraryGroupData {
+    Object {
+        group: Vec<Id>,
+        cells: Vec<(Id, Id)>,
+        data: ArbitraryObjectData,
+    },
+    Excluded {
+        group: Vec<Id>,
+        cells: Vec<(Id, Id)>,
+        size: u32,
+    },
+    BlobReference {
+        objects: Vec<Id>,
+        cells: Vec<(Id, Id)>,
+        blob: Id,
+    },
+}
+
+impl ArbitraryGroupData {
+    fn data(&self) -> GroupData {
+        match self {
+            ArbitraryGroupData::Object { group, cells, data } => GroupData::Object {
+                group: group.iter().map(Id::exguid).collect(),
+                cells: cells.iter().map(Id::cell_id).collect(),
+                data: data.to_bytes(),
+            },
+            ArbitraryGroupData::Excluded { group, cells, size } => GroupData::Excluded {
+                group: group.iter().map(Id::exguid).collect(),
+                cells: cells.iter().map(Id::cell_id).collect(),
+                size: *size as u64,
+            },
+            ArbitraryGroupData::BlobReference {
+                objects,
+                cells,
+                blob,
+            } => GroupData::BlobReference {
+                objects: objects.iter().map(Id::exguid).collect(),
+                cells: cells.iter().map(Id::cell_id).collect(),
+                blob: blob.exguid(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum ArbitraryObjectData {
+    Raw(Vec<u8>),
+    PropSet {
+        object_ids: Vec<u32>,
+        object_space_ids: Vec<u32>,
+        context_ids: Vec<u32>,
+        properties: Vec<(u16, ArbitraryProperty)>,
+    },
+}
+
+impl ArbitraryObjectData {
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            ArbitraryObjectData::Raw(data) => data.clone(),
+            ArbitraryObjectData::PropSet {
+                object_ids,
+                object_space_ids,
+                context_ids,
+                properties,
+            } => {
+                let prop_set = PropSet {
+                    object_ids: object_ids.clone(),
+                    object_space_ids: object_space_ids.clone(),
+                    context_ids: context_ids.clone(),
+                    properties: ArbitraryProperty::properties(properties),
+                };
+
+                let mut writer = Writer::new();
+                writer.put_object_prop_set(&prop_set);
+                writer.into_bytes()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum ArbitraryProperty {
+    Empty,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Vec(Vec<u8>),
+    ObjectId,
+    ObjectIds(u8),
+    ObjectSpaceId,
+    ObjectSpaceIds(u8),
+    ContextId,
+    ContextIds(u8),
+    PropertyValues(u16, Vec<Vec<(u16, ArbitraryProperty)>>),
+    PropertySet(Vec<(u16, ArbitraryProperty)>),
+}
+
+impl ArbitraryProperty {
+    fn properties(properties: &[(u16, ArbitraryProperty)]) -> Vec<(u32, Value)> {
+        properties
+            .iter()
+            .map(|(id, value)| (*id as u32, value.property()))
+            .collect()
+    }
+
+    fn property(&self) -> Value {
+        match self {
+            ArbitraryProperty::Empty => Value::Empty,
+            ArbitraryProperty::Bool(v) => Value::Bool(*v),
+            ArbitraryProperty::U8(v) => Value::U8(*v),
+            ArbitraryProperty::U16(v) => Value::U16(*v),
+            ArbitraryProperty::U32(v) => Value::U32(*v),
+            ArbitraryProperty::U64(v) => Value::U64(*v),
+            ArbitraryProperty::Vec(v) => Value::Vec(v.clone()),
+            ArbitraryProperty::ObjectId => Value::ObjectId,
+            ArbitraryProperty::ObjectIds(v) => Value::ObjectIds(*v as u32),
+            ArbitraryProperty::ObjectSpaceId => Value::ObjectSpaceId,
+            ArbitraryProperty::ObjectSpaceIds(v) => Value::ObjectSpaceIds(*v as u32),
+            ArbitraryProperty::ContextId => Value::ContextId,
+            ArbitraryProperty::ContextIds(v) => Value::ContextIds(*v as u32),
+            ArbitraryProperty::PropertyValues(id, sets) => Value::PropertyValues(
+                *id as u32,
+                sets.iter().map(|set| Self::properties(set)).collect(),
+            ),
+            ArbitraryProperty::PropertySet(set) => Value::PropertySet(Self::properties(set)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArbitraryPackage, parse_packaging, parse_section};
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_generated_packages_parse() {
+        let seed: Vec<u8> = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+            .collect();
+
+        for offset in 0..64 {
+            let mut input = Unstructured::new(&seed[offset * 16..]);
+            let package = ArbitraryPackage::arbitrary(&mut input).unwrap();
+
+            let data = package.to_bytes();
+
+            parse_packaging(&data).unwrap();
+            let _ = parse_section(&data);
+        }
+    }
+}
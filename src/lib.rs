@@ -27,6 +27,22 @@
 //!
 //! - `backtrace`: Captures a `std::backtrace::Backtrace` on parse errors and
 //!   exposes it via `std::error::Error::backtrace()`.
+//! - `fuzzing`: Exposes the internal parsers used by the fuzz targets in `fuzz/`.
+//!   This is not covered by semantic versioning.
+//! - `metafile`: Adds the `metafile` module which converts EMF and WMF images
+//!   to SVG documents.
+//! - `metafile-png`: Additionally allows rasterizing EMF and WMF images to PNG
+//!   images.
+//! - `mmap`: Adds `source::MmapSource` which memory-maps section files. Images
+//!   and embedded files refer to the mapping instead of being copied.
+//! - `parallel`: Parses the sections of a notebook and the pages of a section
+//!   in parallel on the `rayon` thread pool. The parsed notebooks and sections
+//!   are identical to the ones parsed without this feature.
+//! - `raw`: Adds the `raw` module, a read-only view of the low-level file
+//!   structure for tooling and debugging. This is not covered by semantic
+//!   versioning. It also enables `validation::validate_store`.
+//! - `zip`: Adds `source::ZipSource` which reads notebooks from ZIP archives
+//!   (e.g. OneDrive notebook downloads).
 //!
 //! # Architecture
 //!
@@ -43,12 +59,19 @@
 //! and (with the `backtrace` feature enabled) access the captured backtrace via
 //! `std::error::Error::backtrace()`.
 //!
+//! When parsing untrusted files, configure resource limits using
+//! [`Parser::with_limits`] and [`ParseLimits`].
+//!
 //! # Input files
 //!
 //! Use `.onetoc2` and `.one` files from OneDrive downloads (FSSHTTP packaging). For `.onetoc2`
 //! files, the parser expects the `.one` file to be in the same directory. The parser does not
 //! support legacy OneNote 2016 desktop files.
 //!
+//! Notebooks exported as OneNote packages (`.onepkg`) can be loaded using
+//! [`Parser::parse_onepkg`]. The files contained in the package must use one of
+//! the supported formats.
+//!
 //! # Stability
 //!
 //! The public API follows semantic versioning and is intended to be stable.
@@ -70,27 +93,55 @@
 #[macro_use]
 mod macros;
 
+mod cab;
+mod cache;
 pub mod errors;
 mod fsshttpb;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+mod limits;
+#[cfg(feature = "metafile")]
+pub mod metafile;
 mod one;
 mod onenote;
 mod onestore;
+mod progress;
+#[cfg(feature = "raw")]
+pub mod raw;
 mod reader;
 mod shared;
+#[cfg(any(test, feature = "fuzzing"))]
+#[cfg_attr(not(test), allow(dead_code))]
+mod test_support;
 mod utils;
+pub mod validation;
 
 pub(crate) type Reader<'a, 'b> = &'b mut reader::Reader<'a>;
 
+pub use crate::cache::ParseCache;
+pub use crate::limits::ParseLimits;
 pub use crate::onenote::Parser;
+pub use crate::progress::{CancellationToken, Phase, Progress};
+
+/// Sources of notebook files.
+pub mod source {
+    #[cfg(feature = "mmap")]
+    pub use crate::onenote::source::MmapSource;
+    #[cfg(feature = "zip")]
+    pub use crate::onenote::source::ZipSource;
+    pub use crate::onenote::source::{FileBuffer, LocalSource, MemorySource, Source};
+}
 
 /// The data that represents a OneNote notebook.
 pub mod notebook {
-    pub use crate::onenote::notebook::Notebook;
+    pub use crate::onenote::notebook::{Notebook, TocEntry};
 }
 
 /// The data that represents a OneNote section.
 pub mod section {
     pub use crate::onenote::section::{Section, SectionEntry, SectionGroup};
+    pub use crate::onenote::section_package::SectionPackage;
 }
 
 /// The data that represents a OneNote page.
@@ -98,13 +149,14 @@ pub mod page {
     pub use crate::onenote::page::{Page, Title};
     pub use crate::onenote::page_content::PageContent;
     pub use crate::onenote::page_series::PageSeries;
+    pub use crate::onenote::page_tree::PageTreeNode;
 }
 
 /// The data that represents the contents of a OneNote section.
 pub mod contents {
     pub use crate::onenote::content::Content;
     pub use crate::onenote::embedded_file::EmbeddedFile;
-    pub use crate::onenote::image::Image;
+    pub use crate::onenote::image::{Image, Printout};
     pub use crate::onenote::ink::{Ink, InkBoundingBox, InkPoint, InkStroke};
     pub use crate::onenote::list::List;
     pub use crate::onenote::math_inline_object::{MathInlineObject, MathObjectType};
@@ -114,6 +166,8 @@ pub mod contents {
         EmbeddedInkContainer, EmbeddedInkSpace, EmbeddedObject, ParagraphStyling, RichText,
     };
     pub use crate::onenote::table::{Table, TableCell, TableRow};
+    pub use crate::onenote::unknown::{RawPropertyValue, UnknownContent};
+    pub use crate::shared::media::ContentType;
 }
 
 /// Collection of properties used by the OneNote file format.
@@ -0,0 +1,220 @@
+use crate::errors::{ErrorKind, Limit, Result};
+use std::cell::Cell;
+
+/// Resource limits for parsing untrusted files.
+///
+/// The limits protect against crafted files that declare huge sizes or deeply
+/// nested objects to exhaust memory or the stack. If a limit is exceeded,
+/// parsing fails with [`ErrorKind::LimitExceeded`].
+///
+/// The default limits are generous enough for regular notebooks. Use
+/// [`ParseLimits::unlimited`] to disable all limits.
+///
+/// ```
+/// use onenote_parser::{ParseLimits, Parser};
+///
+/// let limits = ParseLimits::default()
+///     .with_max_input_size(64 * 1024 * 1024)
+///     .with_max_depth(64);
+/// let parser = Parser::new().with_limits(limits);
+/// ```
+///
+/// [`ErrorKind::LimitExceeded`]: crate::errors::ErrorKind::LimitExceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    max_input_size: u64,
+    max_allocation: u64,
+    max_objects: u64,
+    max_depth: u64,
+    max_blob_bytes: u64,
+}
+
+impl ParseLimits {
+    /// Limits that never trigger.
+    pub fn unlimited() -> ParseLimits {
+        ParseLimits {
+            max_input_size: u64::MAX,
+            max_allocation: u64::MAX,
+            max_objects: u64::MAX,
+            max_depth: u64::MAX,
+            max_blob_bytes: u64::MAX,
+        }
+    }
+
+    /// Set the maximum size of a single input file in bytes.
+    ///
+    /// This applies to section, table of contents and package files. Files are
+    /// checked after they have been read from their source.
+    pub fn with_max_input_size(mut self, bytes: u64) -> ParseLimits {
+        self.max_input_size = bytes;
+        self
+    }
+
+    /// Set the maximum size of a single allocation in bytes whose size is
+    /// read from the file.
+    pub fn with_max_allocation(mut self, bytes: u64) -> ParseLimits {
+        self.max_allocation = bytes;
+        self
+    }
+
+    /// Set the maximum number of data elements and objects in a single file.
+    pub fn with_max_objects(mut self, count: u64) -> ParseLimits {
+        self.max_objects = count;
+        self
+    }
+
+    /// Set the maximum nesting depth of outlines, table of contents entries and
+    /// section groups.
+    pub fn with_max_depth(mut self, depth: u64) -> ParseLimits {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Set the maximum total size of the file data blobs of a single file
+    /// and the decompressed data of a package in bytes.
+    pub fn with_max_blob_bytes(mut self, bytes: u64) -> ParseLimits {
+        self.max_blob_bytes = bytes;
+        self
+    }
+
+    /// The maximum size of a single input file in bytes.
+    pub fn max_input_size(&self) -> u64 {
+        self.max_input_size
+    }
+
+    /// The maximum size of a single allocation in bytes.
+    pub fn max_allocation(&self) -> u64 {
+        self.max_allocation
+    }
+
+    /// The maximum number of data elements and objects in a single file.
+    pub fn max_objects(&self) -> u64 {
+        self.max_objects
+    }
+
+    /// The maximum nesting depth of outlines, table of contents entries and
+    /// section groups.
+    pub fn max_depth(&self) -> u64 {
+        self.max_depth
+    }
+
+    /// The maximum total size of file data blobs in bytes.
+    pub fn max_blob_bytes(&self) -> u64 {
+        self.max_blob_bytes
+    }
+
+    pub(crate) fn check_input_size(&self, size: usize) -> Result<()> {
+        check(Limit::InputSize, size as u64, self.max_input_size)
+    }
+
+    /// Check the size of an allocation and convert it to `usize`.
+    pub(crate) fn check_allocation(&self, size: u64) -> Result<usize> {
+        check(Limit::Allocation, size, self.max_allocation)?;
+
+        usize::try_from(size).map_err(|_| exceeded(Limit::Allocation, usize::MAX as u64))
+    }
+
+    pub(crate) fn check_depth(&self, depth: usize) -> Result<()> {
+        check(Limit::Depth, depth as u64, self.max_depth)
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_input_size: 2 << 30,
+            max_allocation: 1 << 30,
+            max_objects: 10_000_000,
+            max_depth: 256,
+            max_blob_bytes: 4 << 30,
+        }
+    }
+}
+
+/// Tracks the resources used while parsing a single file.
+pub(crate) struct Budget {
+    limits: ParseLimits,
+    objects: Cell<u64>,
+    blob_bytes: Cell<u64>,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: ParseLimits) -> Budget {
+        Budget {
+            limits,
+            objects: Cell::new(0),
+            blob_bytes: Cell::new(0),
+        }
+    }
+
+    pub(crate) fn limits(&self) -> &ParseLimits {
+        &self.limits
+    }
+
+    /// Record a parsed data element or object.
+    pub(crate) fn add_object(&self) -> Result<()> {
+        let count = self.objects.get().saturating_add(1);
+        check(Limit::ObjectCount, count, self.limits.max_objects)?;
+        self.objects.set(count);
+
+        Ok(())
+    }
+
+    /// Record the size of a file data blob.
+    pub(crate) fn add_blob_bytes(&self, size: u64) -> Result<()> {
+        let total = self.blob_bytes.get().saturating_add(size);
+        check(Limit::BlobBytes, total, self.limits.max_blob_bytes)?;
+        self.blob_bytes.set(total);
+
+        Ok(())
+    }
+}
+
+fn check(limit: Limit, value: u64, max: u64) -> Result<()> {
+    if value > max {
+        return Err(exceeded(limit, max));
+    }
+
+    Ok(())
+}
+
+fn exceeded(limit: Limit, max: u64) -> crate::errors::Error {
+    ErrorKind::LimitExceeded { limit, max }.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, ParseLimits};
+    use crate::errors::{ErrorKind, Limit};
+
+    #[test]
+    fn test_budget() {
+        let budget = Budget::new(
+            ParseLimits::default()
+                .with_max_objects(2)
+                .with_max_blob_bytes(10),
+        );
+
+        assert!(budget.add_object().is_ok());
+        assert!(budget.add_object().is_ok());
+        assert!(matches!(
+            budget.add_object().unwrap_err().kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::ObjectCount,
+                max: 2
+            }
+        ));
+
+        assert!(budget.add_blob_bytes(6).is_ok());
+        assert!(budget.add_blob_bytes(6).is_err());
+        assert!(budget.add_blob_bytes(4).is_ok());
+    }
+
+    #[test]
+    fn test_check_allocation() {
+        let limits = ParseLimits::default().with_max_allocation(100);
+
+        assert_eq!(limits.check_allocation(100).unwrap(), 100);
+        assert!(limits.check_allocation(101).is_err());
+    }
+}
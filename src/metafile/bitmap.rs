@@ -0,0 +1,257 @@
+use crate::errors::{ErrorKind, Result};
+use crate::limits::ParseLimits;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_JPEG: u32 = 4;
+const BI_PNG: u32 = 5;
+
+/// A bitmap converted to a format that can be embedded in an SVG document.
+pub(crate) struct Bitmap {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) data_uri: String,
+}
+
+/// Convert a device-independent bitmap to a PNG data URI.
+///
+/// `info` contains the bitmap info header including the color table and `bits`
+/// contains the pixel data (see `DeviceIndependentBitmap` in \[MS-WMF\] 2.2.2.9).
+pub(crate) fn convert(info: &[u8], bits: &[u8]) -> Result<Bitmap> {
+    let header_size = read_u32(info, 0)? as usize;
+
+    let (width, height, bit_count, compression, colors_used) = if header_size == 12 {
+        // BITMAPCOREHEADER
+        (
+            read_u16(info, 4)? as i32,
+            read_u16(info, 6)? as i16 as i32,
+            read_u16(info, 10)?,
+            BI_RGB,
+            0,
+        )
+    } else {
+        (
+            read_u32(info, 4)? as i32,
+            read_u32(info, 8)? as i32,
+            read_u16(info, 14)?,
+            read_u32(info, 16)?,
+            read_u32(info, 32)?,
+        )
+    };
+
+    let top_down = height < 0;
+    let width = width.unsigned_abs();
+    let height = height.unsigned_abs();
+
+    if width == 0 || height == 0 || width > 0x8000 || height > 0x8000 {
+        return Err(malformed("invalid bitmap size"));
+    }
+
+    match compression {
+        BI_JPEG => {
+            return Ok(Bitmap {
+                width,
+                height,
+                data_uri: data_uri("image/jpeg", bits),
+            });
+        }
+        BI_PNG => {
+            return Ok(Bitmap {
+                width,
+                height,
+                data_uri: data_uri("image/png", bits),
+            });
+        }
+        BI_RGB | BI_BITFIELDS => {}
+        _ => return Err(malformed("unsupported bitmap compression")),
+    }
+
+    if !matches!(bit_count, 1 | 4 | 8 | 16 | 24 | 32) {
+        return Err(malformed("unsupported bitmap bit count"));
+    }
+
+    let pixels_size = width as u64 * height as u64 * 4;
+    ParseLimits::default().check_allocation(pixels_size)?;
+
+    let masks = if compression == BI_BITFIELDS {
+        let offset = if header_size > 40 { 40 } else { header_size };
+        Some([
+            read_u32(info, offset)?,
+            read_u32(info, offset + 4)?,
+            read_u32(info, offset + 8)?,
+        ])
+    } else {
+        None
+    };
+
+    let palette = if bit_count <= 8 {
+        let entry_size = if header_size == 12 { 3 } else { 4 };
+        let count = if colors_used > 0 {
+            colors_used as usize
+        } else {
+            1 << bit_count
+        };
+
+        (0..count)
+            .map(|i| {
+                let offset = header_size + i * entry_size;
+                info.get(offset..offset + 3)
+                    .map(|c| [c[2], c[1], c[0]])
+                    .ok_or_else(|| malformed("bitmap color table is truncated"))
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        vec![]
+    };
+
+    let stride = (width as usize * bit_count as usize).div_ceil(32) * 4;
+    if bits.len() < stride * height as usize {
+        return Err(malformed("bitmap data is truncated"));
+    }
+
+    let mut pixels = Vec::with_capacity(pixels_size as usize);
+    for y in 0..height as usize {
+        let row = if top_down { y } else { height as usize - y - 1 };
+        let row = &bits[row * stride..(row + 1) * stride];
+
+        for x in 0..width as usize {
+            let rgba = read_pixel(row, x, bit_count, &palette, masks)?;
+            pixels.extend(rgba);
+        }
+    }
+
+    let png = encode_png(width, height, &pixels)?;
+
+    Ok(Bitmap {
+        width,
+        height,
+        data_uri: data_uri("image/png", &png),
+    })
+}
+
+/// Convert a packed device-independent bitmap to a PNG data URI.
+///
+/// Packed bitmaps store the info header, the color table and the pixel data in
+/// a single buffer.
+pub(crate) fn convert_packed(dib: &[u8]) -> Result<Bitmap> {
+    let header_size = read_u32(dib, 0)? as usize;
+
+    let bits_offset = if header_size == 12 {
+        let bit_count = read_u16(dib, 10)?;
+        let colors = if bit_count <= 8 { 1 << bit_count } else { 0 };
+        header_size + colors * 3
+    } else {
+        let bit_count = read_u16(dib, 14)?;
+        let compression = read_u32(dib, 16)?;
+        let colors_used = read_u32(dib, 32)? as usize;
+        let colors = if colors_used > 0 {
+            colors_used
+        } else if bit_count <= 8 {
+            1 << bit_count
+        } else {
+            0
+        };
+        let masks = if compression == BI_BITFIELDS && header_size == 40 {
+            12
+        } else {
+            0
+        };
+        header_size + masks + colors * 4
+    };
+
+    let (info, bits) = dib
+        .split_at_checked(bits_offset)
+        .ok_or_else(|| malformed("bitmap header is truncated"))?;
+
+    convert(info, bits)
+}
+
+fn read_pixel(
+    row: &[u8],
+    x: usize,
+    bit_count: u16,
+    palette: &[[u8; 3]],
+    masks: Option<[u32; 3]>,
+) -> Result<[u8; 4]> {
+    let indexed = |index: usize| {
+        palette
+            .get(index)
+            .map(|[r, g, b]| [*r, *g, *b, 0xFF])
+            .ok_or_else(|| malformed("bitmap color index out of range"))
+    };
+
+    match bit_count {
+        1 => indexed(((row[x / 8] >> (7 - x % 8)) & 0x1) as usize),
+        4 => indexed(((row[x / 2] >> (4 * (1 - x % 2))) & 0xF) as usize),
+        8 => indexed(row[x] as usize),
+        16 => {
+            let value = u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]) as u32;
+            let masks = masks.unwrap_or([0x7C00, 0x03E0, 0x001F]);
+            Ok(apply_masks(value, masks))
+        }
+        24 => Ok([row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 0xFF]),
+        32 => {
+            let value = read_u32(row, x * 4)?;
+            let masks = masks.unwrap_or([0xFF0000, 0x00FF00, 0x0000FF]);
+            Ok(apply_masks(value, masks))
+        }
+        _ => Err(malformed("unsupported bitmap bit count")),
+    }
+}
+
+fn apply_masks(value: u32, masks: [u32; 3]) -> [u8; 4] {
+    let channel = |mask: u32| {
+        if mask == 0 {
+            return 0;
+        }
+
+        let max = mask >> mask.trailing_zeros();
+        (((value & mask) >> mask.trailing_zeros()) * 255 / max) as u8
+    };
+
+    [
+        channel(masks[0]),
+        channel(masks[1]),
+        channel(masks[2]),
+        0xFF,
+    ]
+}
+
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>> {
+    let mut png = vec![];
+
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(pixels))
+        .map_err(|err| {
+            ErrorKind::MalformedMetafileData(format!("failed to encode bitmap: {err}").into())
+        })?;
+
+    Ok(png)
+}
+
+fn data_uri(mime_type: &str, data: &[u8]) -> String {
+    format!("data:{};base64,{}", mime_type, STANDARD.encode(data))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|v| u16::from_le_bytes([v[0], v[1]]))
+        .ok_or_else(|| malformed("bitmap header is truncated"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+        .ok_or_else(|| malformed("bitmap header is truncated"))
+}
+
+fn malformed(message: &'static str) -> crate::errors::Error {
+    ErrorKind::MalformedMetafileData(message.into()).into()
+}
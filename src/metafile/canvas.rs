@@ -0,0 +1,861 @@
+//! A GDI playback device that records drawing operations as SVG elements.
+
+use crate::metafile::bitmap::Bitmap;
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+/// An RGB color as stored in a `ColorRef` structure.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Color(u8, u8, u8);
+
+impl Color {
+    pub(crate) const BLACK: Color = Color(0, 0, 0);
+    pub(crate) const WHITE: Color = Color(0xFF, 0xFF, 0xFF);
+
+    pub(crate) fn from_color_ref(value: u32) -> Color {
+        Color(value as u8, (value >> 8) as u8, (value >> 16) as u8)
+    }
+
+    fn to_svg(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// A 2D affine transformation (`x' = a*x + c*y + e`, `y' = b*x + d*y + f`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Transform {
+    pub(crate) a: f64,
+    pub(crate) b: f64,
+    pub(crate) c: f64,
+    pub(crate) d: f64,
+    pub(crate) e: f64,
+    pub(crate) f: f64,
+}
+
+impl Transform {
+    pub(crate) const IDENTITY: Transform = Transform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    /// Apply `self` first, then `other`.
+    pub(crate) fn then(&self, other: &Transform) -> Transform {
+        Transform {
+            a: self.a * other.a + self.b * other.c,
+            b: self.a * other.b + self.b * other.d,
+            c: self.c * other.a + self.d * other.c,
+            d: self.c * other.b + self.d * other.d,
+            e: self.e * other.a + self.f * other.c + other.e,
+            f: self.e * other.b + self.f * other.d + other.f,
+        }
+    }
+
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    fn scale(&self) -> f64 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+
+    fn to_svg(self) -> String {
+        format!(
+            "matrix({} {} {} {} {} {})",
+            num(self.a),
+            num(self.b),
+            num(self.c),
+            num(self.d),
+            num(self.e),
+            num(self.f)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Pen {
+    pub(crate) style: u32,
+    pub(crate) width: f64,
+    pub(crate) color: Color,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Brush {
+    pub(crate) style: u32,
+    pub(crate) color: Color,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Font {
+    pub(crate) height: f64,
+    pub(crate) escapement: f64,
+    pub(crate) weight: u32,
+    pub(crate) italic: bool,
+    pub(crate) underline: bool,
+    pub(crate) strikeout: bool,
+    pub(crate) face_name: String,
+}
+
+/// A GDI object that can be selected into the device context.
+#[derive(Debug, Clone)]
+pub(crate) enum GdiObject {
+    Pen(Pen),
+    Brush(Brush),
+    Font(Font),
+
+    /// An object that isn't used for drawing (e.g. palettes and regions).
+    Other,
+}
+
+const PS_NULL: u32 = 5;
+const BS_NULL: u32 = 1;
+
+const TA_UPDATECP: u32 = 0x0001;
+const TA_RIGHT: u32 = 0x0002;
+const TA_CENTER: u32 = 0x0006;
+const TA_BOTTOM: u32 = 0x0008;
+const TA_BASELINE: u32 = 0x0018;
+
+const MM_ISOTROPIC: u32 = 7;
+const MM_ANISOTROPIC: u32 = 8;
+
+/// The arc-like shapes that are defined by a bounding box and two radials.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum ArcKind {
+    Arc,
+    ArcTo,
+    Chord,
+    Pie,
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    pen: Pen,
+    brush: Brush,
+    font: Font,
+
+    text_color: Color,
+    text_align: u32,
+    winding_fill: bool,
+
+    map_mode: u32,
+    window_org: (f64, f64),
+    window_ext: (f64, f64),
+    viewport_org: (f64, f64),
+    viewport_ext: (f64, f64),
+    world: Transform,
+
+    position: (f64, f64),
+}
+
+/// A device context that records drawing operations as SVG elements.
+///
+/// Coordinates passed to the drawing methods are logical coordinates. They are
+/// mapped to device coordinates using the world transform and the window/viewport
+/// mapping of the device context.
+pub(crate) struct Canvas {
+    state: State,
+    saved: Vec<State>,
+    objects: Vec<Option<GdiObject>>,
+
+    /// The path that is being constructed if a path bracket is open.
+    path: Option<String>,
+
+    /// The last completed path (for path fill/stroke operations).
+    completed_path: Option<String>,
+
+    elements: String,
+}
+
+impl Canvas {
+    pub(crate) fn new(map_mode: u32) -> Canvas {
+        Canvas {
+            state: State {
+                pen: Pen {
+                    style: 0,
+                    width: 0.0,
+                    color: Color::BLACK,
+                },
+                brush: Brush {
+                    style: 0,
+                    color: Color::WHITE,
+                },
+                font: Font {
+                    height: 12.0,
+                    escapement: 0.0,
+                    weight: 400,
+                    italic: false,
+                    underline: false,
+                    strikeout: false,
+                    face_name: "sans-serif".to_string(),
+                },
+                text_color: Color::BLACK,
+                text_align: 0,
+                winding_fill: false,
+                map_mode,
+                window_org: (0.0, 0.0),
+                window_ext: (1.0, 1.0),
+                viewport_org: (0.0, 0.0),
+                viewport_ext: (1.0, 1.0),
+                world: Transform::IDENTITY,
+                position: (0.0, 0.0),
+            },
+            saved: vec![],
+            objects: vec![],
+            path: None,
+            completed_path: None,
+            elements: String::new(),
+        }
+    }
+
+    /// Render the recorded elements into an SVG document.
+    pub(crate) fn into_svg(self, view_box: (f64, f64, f64, f64), size: (f64, f64)) -> String {
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" "#,
+                r#"width="{}" height="{}" viewBox="{} {} {} {}">"#,
+                "{}</svg>"
+            ),
+            num(size.0),
+            num(size.1),
+            num(view_box.0),
+            num(view_box.1),
+            num(view_box.2),
+            num(view_box.3),
+            self.elements
+        )
+    }
+
+    // Device context state
+
+    pub(crate) fn set_map_mode(&mut self, map_mode: u32) {
+        self.state.map_mode = map_mode;
+    }
+
+    pub(crate) fn set_window_org(&mut self, x: f64, y: f64) {
+        self.state.window_org = (x, y);
+    }
+
+    pub(crate) fn set_window_ext(&mut self, x: f64, y: f64) {
+        if x != 0.0 && y != 0.0 {
+            self.state.window_ext = (x, y);
+        }
+    }
+
+    pub(crate) fn window(&self) -> ((f64, f64), (f64, f64)) {
+        (self.state.window_org, self.state.window_ext)
+    }
+
+    pub(crate) fn set_viewport_org(&mut self, x: f64, y: f64) {
+        self.state.viewport_org = (x, y);
+    }
+
+    pub(crate) fn set_viewport_ext(&mut self, x: f64, y: f64) {
+        if x != 0.0 && y != 0.0 {
+            self.state.viewport_ext = (x, y);
+        }
+    }
+
+    pub(crate) fn world_transform(&self) -> Transform {
+        self.state.world
+    }
+
+    pub(crate) fn set_world_transform(&mut self, transform: Transform) {
+        self.state.world = transform;
+    }
+
+    pub(crate) fn set_text_color(&mut self, color: Color) {
+        self.state.text_color = color;
+    }
+
+    pub(crate) fn set_text_align(&mut self, align: u32) {
+        self.state.text_align = align;
+    }
+
+    pub(crate) fn set_winding_fill(&mut self, winding: bool) {
+        self.state.winding_fill = winding;
+    }
+
+    pub(crate) fn save(&mut self) {
+        self.saved.push(self.state.clone());
+    }
+
+    /// Restore a saved state. Negative values are relative to the current state.
+    pub(crate) fn restore(&mut self, index: i32) {
+        let index = if index < 0 {
+            self.saved.len() as i64 + index as i64
+        } else {
+            index as i64 - 1
+        };
+
+        if index >= 0 && (index as usize) < self.saved.len() {
+            self.saved.truncate(index as usize + 1);
+            if let Some(state) = self.saved.pop() {
+                self.state = state;
+            }
+        }
+    }
+
+    // Object table
+
+    /// Store an object at the given index of the object table.
+    pub(crate) fn create_object_at(&mut self, index: usize, object: GdiObject) {
+        if self.objects.len() <= index {
+            self.objects.resize(index + 1, None);
+        }
+
+        self.objects[index] = Some(object);
+    }
+
+    /// Store an object at the lowest free index of the object table.
+    pub(crate) fn create_object(&mut self, object: GdiObject) {
+        match self.objects.iter().position(|o| o.is_none()) {
+            Some(index) => self.objects[index] = Some(object),
+            None => self.objects.push(Some(object)),
+        }
+    }
+
+    pub(crate) fn delete_object(&mut self, index: usize) {
+        if let Some(object) = self.objects.get_mut(index) {
+            *object = None;
+        }
+    }
+
+    pub(crate) fn select_object(&mut self, index: usize) {
+        if let Some(Some(object)) = self.objects.get(index) {
+            self.select(object.clone());
+        }
+    }
+
+    pub(crate) fn select(&mut self, object: GdiObject) {
+        match object {
+            GdiObject::Pen(pen) => self.state.pen = pen,
+            GdiObject::Brush(brush) => self.state.brush = brush,
+            GdiObject::Font(font) => self.state.font = font,
+            GdiObject::Other => {}
+        }
+    }
+
+    // Drawing
+
+    pub(crate) fn move_to(&mut self, x: f64, y: f64) {
+        self.state.position = (x, y);
+
+        if let Some(path) = &mut self.path {
+            let _ = write!(path, "M{} {}", num(x), num(y));
+        }
+    }
+
+    pub(crate) fn line_to(&mut self, x: f64, y: f64) {
+        let (x0, y0) = self.state.position;
+        self.state.position = (x, y);
+
+        match &mut self.path {
+            Some(path) => {
+                if path.is_empty() {
+                    let _ = write!(path, "M{} {}", num(x0), num(y0));
+                }
+                let _ = write!(path, "L{} {}", num(x), num(y));
+            }
+            None => {
+                let d = format!("M{} {}L{} {}", num(x0), num(y0), num(x), num(y));
+                self.draw(&d, false, true);
+            }
+        }
+    }
+
+    /// Draw a series of connected lines (or a polygon if `closed` is set).
+    pub(crate) fn polyline(&mut self, points: &[(f64, f64)], closed: bool) {
+        self.polypolyline(&[points], closed);
+    }
+
+    pub(crate) fn polypolyline(&mut self, polys: &[&[(f64, f64)]], closed: bool) {
+        let mut d = String::new();
+        for points in polys {
+            for (i, (x, y)) in points.iter().enumerate() {
+                let command = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(d, "{}{} {}", command, num(*x), num(*y));
+            }
+            if closed && !points.is_empty() {
+                d.push('Z');
+            }
+        }
+
+        self.add_shape(&d, closed, true);
+    }
+
+    /// Continue the current figure with lines from the current position.
+    pub(crate) fn polyline_to(&mut self, points: &[(f64, f64)]) {
+        for (x, y) in points {
+            self.line_to(*x, *y);
+        }
+    }
+
+    /// Draw Bézier curves. The first point is the start point unless `from_current`
+    /// is set, in which case the curves start at the current position.
+    pub(crate) fn poly_bezier(&mut self, points: &[(f64, f64)], from_current: bool) {
+        let (start, rest) = if from_current {
+            (self.state.position, points)
+        } else {
+            match points.split_first() {
+                Some((start, rest)) => (*start, rest),
+                None => return,
+            }
+        };
+
+        let mut d = String::new();
+        let continue_path = from_current && self.path.as_ref().is_some_and(|p| !p.is_empty());
+        if !continue_path {
+            let _ = write!(d, "M{} {}", num(start.0), num(start.1));
+        }
+
+        for curve in rest.chunks_exact(3) {
+            let _ = write!(
+                d,
+                "C{} {} {} {} {} {}",
+                num(curve[0].0),
+                num(curve[0].1),
+                num(curve[1].0),
+                num(curve[1].1),
+                num(curve[2].0),
+                num(curve[2].1)
+            );
+            self.state.position = curve[2];
+        }
+
+        self.add_shape(&d, false, true);
+    }
+
+    pub(crate) fn rectangle(&mut self, left: f64, top: f64, right: f64, bottom: f64) {
+        let d = format!(
+            "M{} {}H{}V{}H{}Z",
+            num(left),
+            num(top),
+            num(right),
+            num(bottom),
+            num(left)
+        );
+        self.add_shape(&d, true, true);
+    }
+
+    pub(crate) fn round_rect(
+        &mut self,
+        left: f64,
+        top: f64,
+        right: f64,
+        bottom: f64,
+        corner_width: f64,
+        corner_height: f64,
+    ) {
+        let rx = (corner_width / 2.0).abs().min((right - left).abs() / 2.0);
+        let ry = (corner_height / 2.0).abs().min((bottom - top).abs() / 2.0);
+        let (left, right) = (left.min(right), left.max(right));
+        let (top, bottom) = (top.min(bottom), top.max(bottom));
+
+        let d = format!(
+            "M{} {}H{}A{rx} {ry} 0 0 1 {} {}V{}A{rx} {ry} 0 0 1 {} {}H{}A{rx} {ry} 0 0 1 {} {}V{}A{rx} {ry} 0 0 1 {} {}Z",
+            num(left + rx),
+            num(top),
+            num(right - rx),
+            num(right),
+            num(top + ry),
+            num(bottom - ry),
+            num(right - rx),
+            num(bottom),
+            num(left + rx),
+            num(left),
+            num(bottom - ry),
+            num(top + ry),
+            num(left + rx),
+            num(top),
+            rx = num(rx),
+            ry = num(ry),
+        );
+        self.add_shape(&d, true, true);
+    }
+
+    pub(crate) fn ellipse(&mut self, left: f64, top: f64, right: f64, bottom: f64) {
+        let cx = (left + right) / 2.0;
+        let cy = (top + bottom) / 2.0;
+        let rx = ((right - left) / 2.0).abs();
+        let ry = ((bottom - top) / 2.0).abs();
+
+        let d = format!(
+            "M{} {}A{rx} {ry} 0 1 0 {} {}A{rx} {ry} 0 1 0 {} {}Z",
+            num(cx - rx),
+            num(cy),
+            num(cx + rx),
+            num(cy),
+            num(cx - rx),
+            num(cy),
+            rx = num(rx),
+            ry = num(ry),
+        );
+        self.add_shape(&d, true, true);
+    }
+
+    /// Draw an elliptical arc, chord or pie.
+    ///
+    /// The arc is drawn counter-clockwise on the ellipse bounded by the box from the
+    /// radial through `start` to the radial through `end`.
+    pub(crate) fn arc(
+        &mut self,
+        kind: ArcKind,
+        bounds: (f64, f64, f64, f64),
+        start: (f64, f64),
+        end: (f64, f64),
+    ) {
+        let (left, top, right, bottom) = bounds;
+        let cx = (left + right) / 2.0;
+        let cy = (top + bottom) / 2.0;
+        let rx = ((right - left) / 2.0).abs();
+        let ry = ((bottom - top) / 2.0).abs();
+        if rx == 0.0 || ry == 0.0 {
+            return;
+        }
+
+        let angle = |(x, y): (f64, f64)| ((y - cy) / ry).atan2((x - cx) / rx);
+        let start_angle = angle(start);
+        let end_angle = angle(end);
+
+        // Counter-clockwise on screen means decreasing angles with the y-axis pointing down
+        let mut sweep = start_angle - end_angle;
+        if sweep <= 0.0 {
+            sweep += 2.0 * PI;
+        }
+
+        let point = |a: f64| (cx + rx * a.cos(), cy + ry * a.sin());
+        let (x0, y0) = point(start_angle);
+        let (x1, y1) = point(end_angle);
+        let large_arc = if sweep > PI { 1 } else { 0 };
+
+        let mut d = String::new();
+        match kind {
+            ArcKind::Pie => {
+                let _ = write!(d, "M{} {}L{} {}", num(cx), num(cy), num(x0), num(y0));
+            }
+            ArcKind::ArcTo => {
+                let (px, py) = self.state.position;
+                let _ = write!(d, "M{} {}L{} {}", num(px), num(py), num(x0), num(y0));
+            }
+            ArcKind::Arc | ArcKind::Chord => {
+                let _ = write!(d, "M{} {}", num(x0), num(y0));
+            }
+        }
+
+        let _ = write!(
+            d,
+            "A{} {} 0 {} 0 {} {}",
+            num(rx),
+            num(ry),
+            large_arc,
+            num(x1),
+            num(y1)
+        );
+
+        if kind == ArcKind::ArcTo {
+            self.state.position = (x1, y1);
+        }
+
+        match kind {
+            ArcKind::Arc | ArcKind::ArcTo => self.add_shape(&d, false, true),
+            ArcKind::Chord | ArcKind::Pie => {
+                d.push('Z');
+                self.add_shape(&d, true, true)
+            }
+        }
+    }
+
+    // Paths
+
+    pub(crate) fn begin_path(&mut self) {
+        self.path = Some(String::new());
+    }
+
+    pub(crate) fn end_path(&mut self) {
+        self.completed_path = self.path.take();
+    }
+
+    pub(crate) fn abort_path(&mut self) {
+        self.path = None;
+        self.completed_path = None;
+    }
+
+    pub(crate) fn close_figure(&mut self) {
+        if let Some(path) = &mut self.path {
+            path.push('Z');
+        }
+    }
+
+    pub(crate) fn fill_path(&mut self, fill: bool, stroke: bool) {
+        if let Some(d) = self.completed_path.take() {
+            self.draw(&d, fill, stroke);
+        }
+    }
+
+    // Text and images
+
+    pub(crate) fn text_out(&mut self, x: f64, y: f64, text: &str) {
+        let text = text.trim_end_matches('\0');
+        if text.is_empty() {
+            return;
+        }
+
+        let align = self.state.text_align;
+        let (x, y) = if align & TA_UPDATECP != 0 {
+            self.state.position
+        } else {
+            (x, y)
+        };
+
+        // Text is drawn in device space so that it's never mirrored
+        let transform = self.transform();
+        let (dx, dy) = transform.apply(x, y);
+        let font = &self.state.font;
+        let size = (font.height.abs() * transform.scale()).max(1.0);
+
+        let anchor = match align & TA_CENTER {
+            TA_CENTER => "middle",
+            TA_RIGHT => "end",
+            _ => "start",
+        };
+        let baseline = match align & TA_BASELINE {
+            TA_BASELINE => "auto",
+            TA_BOTTOM => "text-after-edge",
+            _ => "text-before-edge",
+        };
+
+        let mut decoration = vec![];
+        if font.underline {
+            decoration.push("underline");
+        }
+        if font.strikeout {
+            decoration.push("line-through");
+        }
+
+        let _ = write!(
+            self.elements,
+            r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" text-anchor="{}" dominant-baseline="{}" xml:space="preserve""#,
+            num(dx),
+            num(dy),
+            escape(&font.face_name),
+            num(size),
+            self.state.text_color.to_svg(),
+            anchor,
+            baseline,
+        );
+        if font.weight >= 600 {
+            let _ = write!(self.elements, r#" font-weight="bold""#);
+        }
+        if font.italic {
+            let _ = write!(self.elements, r#" font-style="italic""#);
+        }
+        if !decoration.is_empty() {
+            let _ = write!(
+                self.elements,
+                r#" text-decoration="{}""#,
+                decoration.join(" ")
+            );
+        }
+        if font.escapement != 0.0 {
+            let _ = write!(
+                self.elements,
+                r#" transform="rotate({} {} {})""#,
+                num(-font.escapement / 10.0),
+                num(dx),
+                num(dy)
+            );
+        }
+        let _ = write!(self.elements, ">{}</text>", escape(text));
+    }
+
+    /// Draw a bitmap into the destination rectangle.
+    ///
+    /// The source rectangle specifies the part of the bitmap that is drawn.
+    pub(crate) fn draw_bitmap(
+        &mut self,
+        bitmap: &Bitmap,
+        dest: (f64, f64, f64, f64),
+        source: (f64, f64, f64, f64),
+    ) {
+        let (x, y, width, height) = dest;
+        let (sx, sy, sw, sh) = source;
+        let (sw, sh) = if sw <= 0.0 || sh <= 0.0 {
+            (bitmap.width as f64, bitmap.height as f64)
+        } else {
+            (sw, sh)
+        };
+
+        // Mirror the image if the destination extents are negative
+        let image_transform = Transform {
+            a: width / sw,
+            b: 0.0,
+            c: 0.0,
+            d: height / sh,
+            e: x - sx * width / sw,
+            f: y - sy * height / sh,
+        };
+        let transform = image_transform.then(&self.transform());
+
+        let _ = write!(
+            self.elements,
+            concat!(
+                r#"<g transform="{}">"#,
+                r#"<svg x="{}" y="{}" width="{}" height="{}" viewBox="{} {} {} {}" preserveAspectRatio="none">"#,
+                r#"<image width="{}" height="{}" preserveAspectRatio="none" href="{}"/>"#,
+                "</svg></g>"
+            ),
+            transform.to_svg(),
+            num(sx),
+            num(sy),
+            num(sw),
+            num(sh),
+            num(sx),
+            num(sy),
+            num(sw),
+            num(sh),
+            bitmap.width,
+            bitmap.height,
+            bitmap.data_uri,
+        );
+    }
+
+    /// Fill a rectangle with the current brush (used for pattern block transfers).
+    pub(crate) fn fill_rect(&mut self, left: f64, top: f64, right: f64, bottom: f64) {
+        let d = format!(
+            "M{} {}H{}V{}H{}Z",
+            num(left),
+            num(top),
+            num(right),
+            num(bottom),
+            num(left)
+        );
+        self.draw(&d, true, false);
+    }
+
+    // Helpers
+
+    /// The transformation from logical to device coordinates.
+    fn transform(&self) -> Transform {
+        let state = &self.state;
+        let (sx, sy) = match state.map_mode {
+            MM_ISOTROPIC | MM_ANISOTROPIC => {
+                let sx = state.viewport_ext.0 / state.window_ext.0;
+                let sy = state.viewport_ext.1 / state.window_ext.1;
+                if state.map_mode == MM_ISOTROPIC {
+                    let s = sx.abs().min(sy.abs());
+                    (s * sx.signum(), s * sy.signum())
+                } else {
+                    (sx, sy)
+                }
+            }
+            _ => (1.0, 1.0),
+        };
+
+        let page = Transform {
+            a: sx,
+            b: 0.0,
+            c: 0.0,
+            d: sy,
+            e: state.viewport_org.0 - state.window_org.0 * sx,
+            f: state.viewport_org.1 - state.window_org.1 * sy,
+        };
+
+        state.world.then(&page)
+    }
+
+    /// Add a shape to the current path or draw it directly.
+    fn add_shape(&mut self, d: &str, fill: bool, stroke: bool) {
+        match &mut self.path {
+            Some(path) => path.push_str(d),
+            None => self.draw(d, fill, stroke),
+        }
+    }
+
+    fn draw(&mut self, d: &str, fill: bool, stroke: bool) {
+        let state = &self.state;
+
+        let fill = if fill && state.brush.style != BS_NULL {
+            state.brush.color.to_svg()
+        } else {
+            "none".to_string()
+        };
+
+        let _ = write!(
+            self.elements,
+            r#"<path d="{}" transform="{}" fill="{}""#,
+            d,
+            self.transform().to_svg(),
+            fill
+        );
+
+        if state.winding_fill {
+            let _ = write!(self.elements, r#" fill-rule="nonzero""#);
+        } else {
+            let _ = write!(self.elements, r#" fill-rule="evenodd""#);
+        }
+
+        let pen = &state.pen;
+        if stroke && pen.style & 0xF != PS_NULL {
+            let _ = write!(self.elements, r#" stroke="{}""#, pen.color.to_svg());
+
+            if pen.width <= 1.0 {
+                // Cosmetic pens are always one device unit wide
+                let _ = write!(
+                    self.elements,
+                    r#" stroke-width="1" vector-effect="non-scaling-stroke""#
+                );
+            } else {
+                let _ = write!(self.elements, r#" stroke-width="{}""#, num(pen.width));
+            }
+
+            let dash = match pen.style & 0xF {
+                1 => Some("6 2"),
+                2 => Some("1 1"),
+                3 => Some("6 2 1 2"),
+                4 => Some("6 2 1 2 1 2"),
+                _ => None,
+            };
+            if let Some(dash) = dash {
+                let _ = write!(self.elements, r#" stroke-dasharray="{}""#, dash);
+            }
+        } else {
+            let _ = write!(self.elements, r#" stroke="none""#);
+        }
+
+        self.elements.push_str("/>");
+    }
+}
+
+/// Format a number for use in an SVG document.
+fn num(value: f64) -> String {
+    let value = if value.is_finite() { value } else { 0.0 };
+    let value = (value * 1000.0).round() / 1000.0;
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    format!("{value}")
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c if (c as u32) < 0x20 && c != '\t' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,545 @@
+//! Playback of Enhanced Metafile (EMF) records.
+//!
+//! See [\[MS-EMF\]].
+//!
+//! [\[MS-EMF\]]: https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-emf/91c257d7-c39d-4a36-9b1f-63e3f73d30ca
+
+use crate::errors::{ErrorKind, Result};
+use crate::metafile::bitmap;
+use crate::metafile::canvas::{ArcKind, Brush, Canvas, Color, Font, GdiObject, Pen, Transform};
+
+const EMR_HEADER: u32 = 1;
+const EMR_POLYBEZIER: u32 = 2;
+const EMR_POLYGON: u32 = 3;
+const EMR_POLYLINE: u32 = 4;
+const EMR_POLYBEZIERTO: u32 = 5;
+const EMR_POLYLINETO: u32 = 6;
+const EMR_POLYPOLYLINE: u32 = 7;
+const EMR_POLYPOLYGON: u32 = 8;
+const EMR_SETWINDOWEXTEX: u32 = 9;
+const EMR_SETWINDOWORGEX: u32 = 10;
+const EMR_SETVIEWPORTEXTEX: u32 = 11;
+const EMR_SETVIEWPORTORGEX: u32 = 12;
+const EMR_EOF: u32 = 14;
+const EMR_SETMAPMODE: u32 = 17;
+const EMR_SETPOLYFILLMODE: u32 = 19;
+const EMR_SETTEXTALIGN: u32 = 22;
+const EMR_SETTEXTCOLOR: u32 = 24;
+const EMR_MOVETOEX: u32 = 27;
+const EMR_SAVEDC: u32 = 33;
+const EMR_RESTOREDC: u32 = 34;
+const EMR_SETWORLDTRANSFORM: u32 = 35;
+const EMR_MODIFYWORLDTRANSFORM: u32 = 36;
+const EMR_SELECTOBJECT: u32 = 37;
+const EMR_CREATEPEN: u32 = 38;
+const EMR_CREATEBRUSHINDIRECT: u32 = 39;
+const EMR_DELETEOBJECT: u32 = 40;
+const EMR_ELLIPSE: u32 = 42;
+const EMR_RECTANGLE: u32 = 43;
+const EMR_ROUNDRECT: u32 = 44;
+const EMR_ARC: u32 = 45;
+const EMR_CHORD: u32 = 46;
+const EMR_PIE: u32 = 47;
+const EMR_LINETO: u32 = 54;
+const EMR_ARCTO: u32 = 55;
+const EMR_BEGINPATH: u32 = 59;
+const EMR_ENDPATH: u32 = 60;
+const EMR_CLOSEFIGURE: u32 = 61;
+const EMR_FILLPATH: u32 = 62;
+const EMR_STROKEANDFILLPATH: u32 = 63;
+const EMR_STROKEPATH: u32 = 64;
+const EMR_ABORTPATH: u32 = 68;
+const EMR_BITBLT: u32 = 76;
+const EMR_STRETCHBLT: u32 = 77;
+const EMR_STRETCHDIBITS: u32 = 81;
+const EMR_EXTCREATEFONTINDIRECTW: u32 = 82;
+const EMR_EXTTEXTOUTW: u32 = 84;
+const EMR_POLYBEZIER16: u32 = 85;
+const EMR_POLYGON16: u32 = 86;
+const EMR_POLYLINE16: u32 = 87;
+const EMR_POLYBEZIERTO16: u32 = 88;
+const EMR_POLYLINETO16: u32 = 89;
+const EMR_POLYPOLYLINE16: u32 = 90;
+const EMR_POLYPOLYGON16: u32 = 91;
+const EMR_CREATEDIBPATTERNBRUSHPT: u32 = 94;
+const EMR_EXTCREATEPEN: u32 = 95;
+
+const EMF_SIGNATURE: u32 = 0x464D4520;
+
+/// Convert an EMF image to an SVG document.
+pub(crate) fn convert(data: &[u8]) -> Result<String> {
+    let header = Record::read(data, 0)?;
+    if header.kind != EMR_HEADER || header.u32(40)? != EMF_SIGNATURE {
+        return Err(malformed("EMF header is missing"));
+    }
+
+    // The bounds are in device units, the frame in 0.01 millimeters
+    let bounds = header.rect(8)?;
+    let frame = header.rect(24)?;
+
+    // The size of the object table, including the reserved index 0
+    let handles = header.u16(56)?;
+
+    let mut canvas = Canvas::new(1);
+    let mut offset = header.data.len();
+
+    while offset < data.len() {
+        let record = Record::read(data, offset)?;
+        offset += record.data.len();
+
+        if record.kind == EMR_EOF {
+            break;
+        }
+
+        play_record(&record, &mut canvas, handles)?;
+    }
+
+    let view_box = (
+        bounds.0,
+        bounds.1,
+        (bounds.2 - bounds.0 + 1.0).max(1.0),
+        (bounds.3 - bounds.1 + 1.0).max(1.0),
+    );
+
+    // Use the physical frame size (converted to pixels at 96 DPI) if available
+    let frame_width = (frame.2 - frame.0) / 2540.0 * 96.0;
+    let frame_height = (frame.3 - frame.1) / 2540.0 * 96.0;
+    let size = if frame_width > 0.0 && frame_height > 0.0 {
+        (frame_width, frame_height)
+    } else {
+        (view_box.2, view_box.3)
+    };
+
+    Ok(canvas.into_svg(view_box, size))
+}
+
+fn play_record(record: &Record, canvas: &mut Canvas, handles: u16) -> Result<()> {
+    match record.kind {
+        EMR_POLYBEZIER => canvas.poly_bezier(&record.points32(24)?, false),
+        EMR_POLYGON => canvas.polyline(&record.points32(24)?, true),
+        EMR_POLYLINE => canvas.polyline(&record.points32(24)?, false),
+        EMR_POLYBEZIERTO => canvas.poly_bezier(&record.points32(24)?, true),
+        EMR_POLYLINETO => canvas.polyline_to(&record.points32(24)?),
+        EMR_POLYPOLYLINE => play_poly_polygon(record, canvas, false, false)?,
+        EMR_POLYPOLYGON => play_poly_polygon(record, canvas, true, false)?,
+        EMR_POLYBEZIER16 => canvas.poly_bezier(&record.points16(24)?, false),
+        EMR_POLYGON16 => canvas.polyline(&record.points16(24)?, true),
+        EMR_POLYLINE16 => canvas.polyline(&record.points16(24)?, false),
+        EMR_POLYBEZIERTO16 => canvas.poly_bezier(&record.points16(24)?, true),
+        EMR_POLYLINETO16 => canvas.polyline_to(&record.points16(24)?),
+        EMR_POLYPOLYLINE16 => play_poly_polygon(record, canvas, false, true)?,
+        EMR_POLYPOLYGON16 => play_poly_polygon(record, canvas, true, true)?,
+        EMR_SETWINDOWEXTEX => canvas.set_window_ext(record.f64(8)?, record.f64(12)?),
+        EMR_SETWINDOWORGEX => canvas.set_window_org(record.f64(8)?, record.f64(12)?),
+        EMR_SETVIEWPORTEXTEX => canvas.set_viewport_ext(record.f64(8)?, record.f64(12)?),
+        EMR_SETVIEWPORTORGEX => canvas.set_viewport_org(record.f64(8)?, record.f64(12)?),
+        EMR_SETMAPMODE => canvas.set_map_mode(record.u32(8)?),
+        EMR_SETPOLYFILLMODE => canvas.set_winding_fill(record.u32(8)? == 2),
+        EMR_SETTEXTALIGN => canvas.set_text_align(record.u32(8)?),
+        EMR_SETTEXTCOLOR => canvas.set_text_color(Color::from_color_ref(record.u32(8)?)),
+        EMR_MOVETOEX => canvas.move_to(record.f64(8)?, record.f64(12)?),
+        EMR_LINETO => canvas.line_to(record.f64(8)?, record.f64(12)?),
+        EMR_SAVEDC => canvas.save(),
+        EMR_RESTOREDC => canvas.restore(record.u32(8)? as i32),
+        EMR_SETWORLDTRANSFORM => canvas.set_world_transform(record.transform(8)?),
+        EMR_MODIFYWORLDTRANSFORM => {
+            let transform = record.transform(8)?;
+            let current = canvas.world_transform();
+            let transform = match record.u32(32)? {
+                1 => Transform::IDENTITY,
+                2 => transform.then(&current),
+                3 => current.then(&transform),
+                4 => transform,
+                _ => current,
+            };
+            canvas.set_world_transform(transform);
+        }
+        EMR_SELECTOBJECT => {
+            let index = record.u32(8)?;
+            if index & 0x80000000 != 0 {
+                if let Some(object) = stock_object(index & 0x7FFFFFFF) {
+                    canvas.select(object);
+                }
+            } else {
+                canvas.select_object(index as usize);
+            }
+        }
+        EMR_CREATEPEN => {
+            let pen = Pen {
+                style: record.u32(12)?,
+                width: record.f64(16)?,
+                color: Color::from_color_ref(record.u32(24)?),
+            };
+            create_object(record, canvas, handles, GdiObject::Pen(pen))?;
+        }
+        EMR_EXTCREATEPEN => {
+            let pen = Pen {
+                style: record.u32(28)?,
+                width: record.u32(32)? as f64,
+                color: Color::from_color_ref(record.u32(40)?),
+            };
+            create_object(record, canvas, handles, GdiObject::Pen(pen))?;
+        }
+        EMR_CREATEBRUSHINDIRECT => {
+            let brush = Brush {
+                style: record.u32(12)?,
+                color: Color::from_color_ref(record.u32(16)?),
+            };
+            create_object(record, canvas, handles, GdiObject::Brush(brush))?;
+        }
+        EMR_CREATEDIBPATTERNBRUSHPT => {
+            // Pattern brushes are approximated with a gray fill
+            let brush = Brush {
+                style: 0,
+                color: Color::from_color_ref(0x808080),
+            };
+            create_object(record, canvas, handles, GdiObject::Brush(brush))?;
+        }
+        EMR_EXTCREATEFONTINDIRECTW => {
+            let font = Font {
+                height: record.i32(12)? as f64,
+                escapement: record.i32(20)? as f64,
+                weight: record.u32(28)?,
+                italic: record.u8(32)? != 0,
+                underline: record.u8(33)? != 0,
+                strikeout: record.u8(34)? != 0,
+                face_name: record.utf16(40, 32)?,
+            };
+            create_object(record, canvas, handles, GdiObject::Font(font))?;
+        }
+        EMR_DELETEOBJECT => canvas.delete_object(record.u32(8)? as usize),
+        EMR_ELLIPSE => {
+            let (l, t, r, b) = record.rect(8)?;
+            canvas.ellipse(l, t, r, b);
+        }
+        EMR_RECTANGLE => {
+            let (l, t, r, b) = record.rect(8)?;
+            canvas.rectangle(l, t, r, b);
+        }
+        EMR_ROUNDRECT => {
+            let (l, t, r, b) = record.rect(8)?;
+            canvas.round_rect(l, t, r, b, record.f64(24)?, record.f64(28)?);
+        }
+        EMR_ARC | EMR_ARCTO | EMR_CHORD | EMR_PIE => {
+            let kind = match record.kind {
+                EMR_ARC => ArcKind::Arc,
+                EMR_ARCTO => ArcKind::ArcTo,
+                EMR_CHORD => ArcKind::Chord,
+                _ => ArcKind::Pie,
+            };
+            let start = (record.f64(24)?, record.f64(28)?);
+            let end = (record.f64(32)?, record.f64(36)?);
+            canvas.arc(kind, record.rect(8)?, start, end);
+        }
+        EMR_BEGINPATH => canvas.begin_path(),
+        EMR_ENDPATH => canvas.end_path(),
+        EMR_CLOSEFIGURE => canvas.close_figure(),
+        EMR_FILLPATH => canvas.fill_path(true, false),
+        EMR_STROKEANDFILLPATH => canvas.fill_path(true, true),
+        EMR_STROKEPATH => canvas.fill_path(false, true),
+        EMR_ABORTPATH => canvas.abort_path(),
+        EMR_EXTTEXTOUTW => {
+            let x = record.f64(36)?;
+            let y = record.f64(40)?;
+            let count = record.u32(44)? as usize;
+            let offset = record.u32(48)? as usize;
+            let text = record.utf16_at(offset, count)?;
+            canvas.text_out(x, y, &text);
+        }
+        EMR_BITBLT => {
+            let dest = (
+                record.f64(24)?,
+                record.f64(28)?,
+                record.f64(32)?,
+                record.f64(36)?,
+            );
+            let source = (record.f64(44)?, record.f64(48)?, dest.2, dest.3);
+            let info = record.blob(84, 88)?;
+            let bits = record.blob(92, 96)?;
+
+            if info.is_empty() {
+                // Pattern copy using the current brush
+                canvas.fill_rect(dest.0, dest.1, dest.0 + dest.2, dest.1 + dest.3);
+            } else {
+                let bitmap = bitmap::convert(info, bits)?;
+                canvas.draw_bitmap(&bitmap, dest, source);
+            }
+        }
+        EMR_STRETCHBLT => {
+            let dest = (
+                record.f64(24)?,
+                record.f64(28)?,
+                record.f64(32)?,
+                record.f64(36)?,
+            );
+            let source = (
+                record.f64(44)?,
+                record.f64(48)?,
+                record.f64(100)?,
+                record.f64(104)?,
+            );
+            let info = record.blob(84, 88)?;
+            let bits = record.blob(92, 96)?;
+
+            if info.is_empty() {
+                canvas.fill_rect(dest.0, dest.1, dest.0 + dest.2, dest.1 + dest.3);
+            } else {
+                let bitmap = bitmap::convert(info, bits)?;
+                canvas.draw_bitmap(&bitmap, dest, source);
+            }
+        }
+        EMR_STRETCHDIBITS => {
+            let dest = (
+                record.f64(24)?,
+                record.f64(28)?,
+                record.f64(72)?,
+                record.f64(76)?,
+            );
+            let source = (
+                record.f64(32)?,
+                record.f64(36)?,
+                record.f64(40)?,
+                record.f64(44)?,
+            );
+            let info = record.blob(48, 52)?;
+            let bits = record.blob(56, 60)?;
+
+            if !info.is_empty() {
+                let bitmap = bitmap::convert(info, bits)?;
+                canvas.draw_bitmap(&bitmap, dest, source);
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Store an object at the object table index given by the record.
+///
+/// Records with an index outside of the object table declared in the header
+/// are ignored.
+fn create_object(
+    record: &Record,
+    canvas: &mut Canvas,
+    handles: u16,
+    object: GdiObject,
+) -> Result<()> {
+    let index = record.u32(8)?;
+    if index != 0 && index < handles as u32 {
+        canvas.create_object_at(index as usize, object);
+    }
+
+    Ok(())
+}
+
+fn play_poly_polygon(
+    record: &Record,
+    canvas: &mut Canvas,
+    closed: bool,
+    short: bool,
+) -> Result<()> {
+    let poly_count = record.u32(24)? as usize;
+    let point_count = record.u32(28)? as usize;
+
+    let counts = (0..poly_count)
+        .map(|i| record.u32(32 + i * 4).map(|c| c as usize))
+        .collect::<Result<Vec<_>>>()?;
+
+    let points_offset = 32 + poly_count * 4;
+    let points = if short {
+        record.points16_n(points_offset, point_count)?
+    } else {
+        record.points32_n(points_offset, point_count)?
+    };
+
+    let mut polys = vec![];
+    let mut start = 0;
+    for count in counts {
+        let poly = points
+            .get(start..start + count)
+            .ok_or_else(|| malformed("polygon point count mismatch"))?;
+        polys.push(poly);
+        start += count;
+    }
+
+    canvas.polypolyline(&polys, closed);
+
+    Ok(())
+}
+
+fn stock_object(index: u32) -> Option<GdiObject> {
+    let brush = |style: u32, color: u32| {
+        Some(GdiObject::Brush(Brush {
+            style,
+            color: Color::from_color_ref(color),
+        }))
+    };
+    let pen = |style: u32, color: u32| {
+        Some(GdiObject::Pen(Pen {
+            style,
+            width: 0.0,
+            color: Color::from_color_ref(color),
+        }))
+    };
+
+    match index {
+        0 => brush(0, 0xFFFFFF),
+        1 => brush(0, 0xC0C0C0),
+        2 => brush(0, 0x808080),
+        3 => brush(0, 0x404040),
+        4 => brush(0, 0x000000),
+        5 => brush(1, 0),
+        6 => pen(0, 0xFFFFFF),
+        7 => pen(0, 0x000000),
+        8 => pen(5, 0),
+        _ => None,
+    }
+}
+
+/// An EMF record including its type and size fields.
+struct Record<'a> {
+    kind: u32,
+    data: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    fn read(data: &'a [u8], offset: usize) -> Result<Record<'a>> {
+        let header = data
+            .get(offset..offset + 8)
+            .ok_or_else(|| malformed("record header is truncated"))?;
+        let kind = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+
+        if size < 8 || size % 4 != 0 {
+            return Err(malformed("invalid record size"));
+        }
+
+        let data = data
+            .get(offset..offset + size)
+            .ok_or_else(|| malformed("record is truncated"))?;
+
+        Ok(Record { kind, data })
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8]> {
+        self.data
+            .get(offset..offset + len)
+            .ok_or_else(|| malformed("record is truncated"))
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8> {
+        Ok(self.bytes(offset, 1)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        let v = self.bytes(offset, 2)?;
+        Ok(u16::from_le_bytes([v[0], v[1]]))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        let v = self.bytes(offset, 4)?;
+        Ok(u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+    }
+
+    fn i32(&self, offset: usize) -> Result<i32> {
+        Ok(self.u32(offset)? as i32)
+    }
+
+    fn f64(&self, offset: usize) -> Result<f64> {
+        Ok(self.i32(offset)? as f64)
+    }
+
+    fn f32(&self, offset: usize) -> Result<f64> {
+        Ok(f32::from_bits(self.u32(offset)?) as f64)
+    }
+
+    fn rect(&self, offset: usize) -> Result<(f64, f64, f64, f64)> {
+        Ok((
+            self.f64(offset)?,
+            self.f64(offset + 4)?,
+            self.f64(offset + 8)?,
+            self.f64(offset + 12)?,
+        ))
+    }
+
+    fn transform(&self, offset: usize) -> Result<Transform> {
+        Ok(Transform {
+            a: self.f32(offset)?,
+            b: self.f32(offset + 4)?,
+            c: self.f32(offset + 8)?,
+            d: self.f32(offset + 12)?,
+            e: self.f32(offset + 16)?,
+            f: self.f32(offset + 20)?,
+        })
+    }
+
+    /// Read a point array that is preceded by its point count.
+    fn points32(&self, offset: usize) -> Result<Vec<(f64, f64)>> {
+        let count = self.u32(offset)? as usize;
+        self.points32_n(offset + 4, count)
+    }
+
+    fn points32_n(&self, offset: usize, count: usize) -> Result<Vec<(f64, f64)>> {
+        let data = self.bytes(offset, count.saturating_mul(8))?;
+        Ok(data
+            .chunks_exact(8)
+            .map(|p| {
+                (
+                    i32::from_le_bytes([p[0], p[1], p[2], p[3]]) as f64,
+                    i32::from_le_bytes([p[4], p[5], p[6], p[7]]) as f64,
+                )
+            })
+            .collect())
+    }
+
+    /// Read a 16-bit point array that is preceded by its point count.
+    fn points16(&self, offset: usize) -> Result<Vec<(f64, f64)>> {
+        let count = self.u32(offset)? as usize;
+        self.points16_n(offset + 4, count)
+    }
+
+    fn points16_n(&self, offset: usize, count: usize) -> Result<Vec<(f64, f64)>> {
+        let data = self.bytes(offset, count.saturating_mul(4))?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|p| {
+                (
+                    i16::from_le_bytes([p[0], p[1]]) as f64,
+                    i16::from_le_bytes([p[2], p[3]]) as f64,
+                )
+            })
+            .collect())
+    }
+
+    /// Read a blob that is referenced by offset and size fields.
+    fn blob(&self, offset_field: usize, size_field: usize) -> Result<&'a [u8]> {
+        let offset = self.u32(offset_field)? as usize;
+        let size = self.u32(size_field)? as usize;
+        if size == 0 {
+            return Ok(&[]);
+        }
+
+        self.bytes(offset, size)
+    }
+
+    /// Read a null-terminated UTF-16 string with a maximum length of `len` characters.
+    fn utf16(&self, offset: usize, len: usize) -> Result<String> {
+        let text = self.utf16_at(offset, len)?;
+        Ok(text.split('\0').next().unwrap_or_default().to_string())
+    }
+
+    fn utf16_at(&self, offset: usize, len: usize) -> Result<String> {
+        let data = self.bytes(offset, len.saturating_mul(2))?;
+        let chars: Vec<_> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+
+        Ok(String::from_utf16_lossy(&chars))
+    }
+}
+
+fn malformed(message: &'static str) -> crate::errors::Error {
+    ErrorKind::MalformedMetafileData(message.into()).into()
+}
@@ -0,0 +1,270 @@
+//! Conversion of Windows metafile images to SVG and PNG.
+//!
+//! OneNote stores many clipped images and printouts as Enhanced Metafiles (EMF) or
+//! Windows Metafiles (WMF) which can't be displayed by browsers. This module
+//! converts them to SVG documents. With the `metafile-png` feature enabled, the
+//! SVG documents can also be rasterized to PNG images.
+//!
+//! The conversion supports the drawing records commonly emitted by OneNote and
+//! Office: lines, polygons and Bézier curves, rectangles, ellipses and arcs,
+//! GDI paths, text output and device-independent bitmaps. Clipping, raster
+//! operations other than plain copies and EMF+ records are ignored.
+//!
+//! See [\[MS-EMF\]] and [\[MS-WMF\]].
+//!
+//! [\[MS-EMF\]]: https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-emf/91c257d7-c39d-4a36-9b1f-63e3f73d30ca
+//! [\[MS-WMF\]]: https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-wmf/4813e7fd-52d0-4f42-965f-228c8b7488d2
+
+use crate::errors::{ErrorKind, Result};
+use crate::shared::media::ContentType;
+
+mod bitmap;
+mod canvas;
+mod emf;
+mod wmf;
+
+/// Convert an EMF or WMF image to an SVG document.
+///
+/// Returns [`ErrorKind::MalformedMetafileData`] if the data is not a supported
+/// metafile or is malformed and [`ErrorKind::LimitExceeded`] if an embedded
+/// bitmap would need more memory than the default [`ParseLimits::max_allocation()`].
+///
+/// [`ErrorKind::MalformedMetafileData`]: crate::errors::ErrorKind::MalformedMetafileData
+/// [`ErrorKind::LimitExceeded`]: crate::errors::ErrorKind::LimitExceeded
+/// [`ParseLimits::max_allocation()`]: crate::ParseLimits::max_allocation
+pub fn to_svg(data: &[u8]) -> Result<String> {
+    match ContentType::detect(data) {
+        Some(ContentType::Emf) => emf::convert(data),
+        Some(ContentType::Wmf) => wmf::convert(data),
+        _ => Err(ErrorKind::MalformedMetafileData("data is not an EMF or WMF image".into()).into()),
+    }
+}
+
+/// Rasterize an EMF or WMF image to a PNG image.
+///
+/// The `scale` factor is applied to the metafile's natural size in pixels
+/// (at 96 DPI). Text is rendered using the fonts installed on the system, which
+/// are loaded on the first call.
+///
+/// Returns [`ErrorKind::MalformedMetafileData`] if the data is not a supported
+/// metafile or is malformed and [`ErrorKind::LimitExceeded`] if the image would
+/// need more memory than the default [`ParseLimits::max_allocation()`].
+///
+/// [`ErrorKind::MalformedMetafileData`]: crate::errors::ErrorKind::MalformedMetafileData
+/// [`ErrorKind::LimitExceeded`]: crate::errors::ErrorKind::LimitExceeded
+/// [`ParseLimits::max_allocation()`]: crate::ParseLimits::max_allocation
+#[cfg(feature = "metafile-png")]
+pub fn to_png(data: &[u8], scale: f32) -> Result<Vec<u8>> {
+    use crate::limits::ParseLimits;
+    use resvg::{tiny_skia, usvg};
+    use std::sync::{Arc, OnceLock};
+
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+
+    let svg = to_svg(data)?;
+
+    let options = usvg::Options {
+        fontdb: FONTS
+            .get_or_init(|| {
+                let mut fonts = usvg::fontdb::Database::new();
+                fonts.load_system_fonts();
+
+                Arc::new(fonts)
+            })
+            .clone(),
+        ..Default::default()
+    };
+
+    let tree = usvg::Tree::from_str(&svg, &options).map_err(|err| {
+        ErrorKind::MalformedMetafileData(format!("failed to parse SVG: {err}").into())
+    })?;
+
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| ErrorKind::MalformedMetafileData("invalid image size".into()))?;
+
+    // The size is declared by the metafile, so check it before allocating the pixels
+    ParseLimits::default().check_allocation(size.width() as u64 * size.height() as u64 * 4)?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| ErrorKind::MalformedMetafileData("invalid image size".into()))?;
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap.encode_png().map_err(|err| {
+        ErrorKind::MalformedMetafileData(format!("failed to encode PNG: {err}").into()).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bitmap, to_svg};
+    use crate::errors::{ErrorKind, Limit};
+
+    fn emf_record(kind: u32, params: &[u32]) -> Vec<u8> {
+        let mut record = vec![];
+        record.extend(kind.to_le_bytes());
+        record.extend((8 + params.len() as u32 * 4).to_le_bytes());
+        for param in params {
+            record.extend(param.to_le_bytes());
+        }
+
+        record
+    }
+
+    fn wmf_record(function: u16, params: &[i16]) -> Vec<u8> {
+        let mut record = vec![];
+        record.extend((3 + params.len() as u32).to_le_bytes());
+        record.extend(function.to_le_bytes());
+        for param in params {
+            record.extend(param.to_le_bytes());
+        }
+
+        record
+    }
+
+    fn emf_header() -> Vec<u8> {
+        // Header: bounds, frame (in 0.01 mm), signature, version, size, record count,
+        // handle count, description, palette entries and reference device size
+        emf_record(
+            1,
+            &[
+                0, 0, 99, 99, 0, 0, 2540, 2540, 0x464D4520, 0x10000, 0, 5, 2, 0, 0, 0, 1024, 768,
+                320, 240,
+            ],
+        )
+    }
+
+    fn emf() -> Vec<u8> {
+        let mut data = emf_header();
+        data.extend(emf_record(38, &[1, 0, 1, 0, 0x0000FF]));
+        data.extend(emf_record(37, &[1]));
+        data.extend(emf_record(43, &[10, 10, 50, 50]));
+        data.extend(emf_record(14, &[0, 0, 20]));
+
+        data
+    }
+
+    fn wmf() -> Vec<u8> {
+        let mut data = vec![];
+
+        // Placeable header: key, handle, bounding box, units per inch, reserved, checksum
+        data.extend(0x9AC6CDD7u32.to_le_bytes());
+        for value in [0i16, 0, 0, 200, 100, 100, 0, 0, 0] {
+            data.extend(value.to_le_bytes());
+        }
+
+        // Header: type, header size, version, file size, object count, max record, members
+        for value in [1i16, 9, 0x300, 0, 0, 1, 0, 0, 0] {
+            data.extend(value.to_le_bytes());
+        }
+
+        data.extend(wmf_record(0x020C, &[100, 200]));
+        data.extend(wmf_record(0x02FC, &[0, 0x00FF, 0, 0]));
+        data.extend(wmf_record(0x012D, &[0]));
+        data.extend(wmf_record(0x041B, &[90, 190, 10, 10]));
+        data.extend(wmf_record(0, &[]));
+
+        data
+    }
+
+    #[test]
+    fn test_emf_to_svg() {
+        let svg = to_svg(&emf()).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="96" height="96" viewBox="0 0 100 100""#));
+        assert!(svg.contains(r#"d="M10 10H50V50H10Z""#));
+        assert!(svg.contains(r##"stroke="#ff0000""##));
+    }
+
+    #[test]
+    fn test_emf_object_index_out_of_range() {
+        // The header declares 2 handles
+        let mut data = emf_header();
+        data.extend(emf_record(38, &[0x7FFFFFFF, 0, 1, 0, 0x0000FF]));
+        data.extend(emf_record(37, &[0x7FFFFFFF]));
+        data.extend(emf_record(43, &[10, 10, 50, 50]));
+        data.extend(emf_record(14, &[0, 0, 20]));
+
+        let svg = to_svg(&data).unwrap();
+
+        assert!(svg.contains(r#"d="M10 10H50V50H10Z""#));
+        assert!(!svg.contains(r##"stroke="#ff0000""##));
+    }
+
+    #[test]
+    fn test_bitmap_header_checks() {
+        let info = |bit_count: u16| {
+            let mut info = vec![];
+            for value in [40u32, 0x8000, 0x8000] {
+                info.extend(value.to_le_bytes());
+            }
+            info.extend(1u16.to_le_bytes());
+            info.extend(bit_count.to_le_bytes());
+            info.extend([0; 24]);
+
+            info
+        };
+
+        let err = bitmap::convert(&info(0), &[]).err().unwrap();
+        assert!(matches!(err.kind(), ErrorKind::MalformedMetafileData(_)));
+
+        let err = bitmap::convert(&info(32), &[]).err().unwrap();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::Allocation,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_wmf_to_svg() {
+        let svg = to_svg(&wmf()).unwrap();
+
+        assert!(svg.contains(r#"width="192" height="96" viewBox="0 0 200 100""#));
+        assert!(svg.contains(r#"d="M10 10H190V90H10Z""#));
+        assert!(svg.contains(r##"fill="#ff0000""##));
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        assert!(to_svg(b"not a metafile").is_err());
+
+        let mut truncated = emf();
+        truncated.truncate(100);
+        assert!(to_svg(&truncated).is_err());
+    }
+
+    #[cfg(feature = "metafile-png")]
+    #[test]
+    fn test_to_png() {
+        let png = super::to_png(&emf(), 2.0).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1A\n"));
+    }
+
+    #[cfg(feature = "metafile-png")]
+    #[test]
+    fn test_to_png_size_limit() {
+        use crate::errors::{ErrorKind, Limit};
+
+        let err = super::to_png(&emf(), 100_000.0).unwrap_err();
+
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::Allocation,
+                ..
+            }
+        ));
+    }
+}
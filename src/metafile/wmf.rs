@@ -0,0 +1,414 @@
+//! Playback of Windows Metafile (WMF) records.
+//!
+//! See [\[MS-WMF\]].
+//!
+//! [\[MS-WMF\]]: https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-wmf/4813e7fd-52d0-4f42-965f-228c8b7488d2
+
+use crate::errors::{ErrorKind, Result};
+use crate::metafile::bitmap;
+use crate::metafile::canvas::{ArcKind, Brush, Canvas, Color, Font, GdiObject, Pen};
+use encoding_rs::WINDOWS_1252;
+
+const META_EOF: u16 = 0x0000;
+const META_SAVEDC: u16 = 0x001E;
+const META_CREATEPALETTE: u16 = 0x00F7;
+const META_SETPOLYFILLMODE: u16 = 0x0106;
+const META_RESTOREDC: u16 = 0x0127;
+const META_SELECTOBJECT: u16 = 0x012D;
+const META_SETTEXTALIGN: u16 = 0x012E;
+const META_DIBCREATEPATTERNBRUSH: u16 = 0x0142;
+const META_DELETEOBJECT: u16 = 0x01F0;
+const META_CREATEPATTERNBRUSH: u16 = 0x01F9;
+const META_SETTEXTCOLOR: u16 = 0x0209;
+const META_SETWINDOWORG: u16 = 0x020B;
+const META_SETWINDOWEXT: u16 = 0x020C;
+const META_LINETO: u16 = 0x0213;
+const META_MOVETO: u16 = 0x0214;
+const META_CREATEPENINDIRECT: u16 = 0x02FA;
+const META_CREATEFONTINDIRECT: u16 = 0x02FB;
+const META_CREATEBRUSHINDIRECT: u16 = 0x02FC;
+const META_POLYGON: u16 = 0x0324;
+const META_POLYLINE: u16 = 0x0325;
+const META_ELLIPSE: u16 = 0x0418;
+const META_RECTANGLE: u16 = 0x041B;
+const META_TEXTOUT: u16 = 0x0521;
+const META_POLYPOLYGON: u16 = 0x0538;
+const META_ROUNDRECT: u16 = 0x061C;
+const META_CREATEREGION: u16 = 0x06FF;
+const META_ARC: u16 = 0x0817;
+const META_PIE: u16 = 0x081A;
+const META_CHORD: u16 = 0x0830;
+const META_DIBBITBLT: u16 = 0x0940;
+const META_EXTTEXTOUT: u16 = 0x0A32;
+const META_DIBSTRETCHBLT: u16 = 0x0B41;
+const META_STRETCHDIB: u16 = 0x0F43;
+
+const PLACEABLE_KEY: u32 = 0x9AC6CDD7;
+const MM_ANISOTROPIC: u32 = 8;
+
+const ETO_OPAQUE: u16 = 0x0002;
+const ETO_CLIPPED: u16 = 0x0004;
+
+/// Convert a WMF image to an SVG document.
+pub(crate) fn convert(data: &[u8]) -> Result<String> {
+    let mut offset = 0;
+
+    // The placeable header is optional and specifies the image bounds
+    let mut placement = None;
+    if read_u32(data, 0)? == PLACEABLE_KEY {
+        let bounds = (
+            read_i16(data, 6)? as f64,
+            read_i16(data, 8)? as f64,
+            read_i16(data, 10)? as f64,
+            read_i16(data, 12)? as f64,
+        );
+        let inch = read_u16(data, 14)? as f64;
+        placement = Some((bounds, inch));
+        offset += 22;
+    }
+
+    let header_size = read_u16(data, offset + 2)? as usize * 2;
+    if header_size < 18 {
+        return Err(malformed("WMF header is invalid"));
+    }
+    offset += header_size;
+
+    // WMF images are played back into a device context that maps the logical
+    // window to the image bounds.
+    let mut canvas = Canvas::new(MM_ANISOTROPIC);
+    if let Some(((left, top, right, bottom), _)) = placement {
+        canvas.set_window_org(left, top);
+        fit_window(&mut canvas, right - left, bottom - top);
+    }
+
+    while offset < data.len() {
+        let record = Record::read(data, offset)?;
+        offset += record.data.len();
+
+        if record.function == META_EOF {
+            break;
+        }
+
+        play_record(&record, &mut canvas)?;
+    }
+
+    let (_, (width, height)) = canvas.window();
+    let view_box = (0.0, 0.0, width.abs(), height.abs());
+
+    let size = match placement {
+        Some(((left, top, right, bottom), inch)) if inch > 0.0 => (
+            (right - left).abs() / inch * 96.0,
+            (bottom - top).abs() / inch * 96.0,
+        ),
+        _ => (view_box.2, view_box.3),
+    };
+
+    Ok(canvas.into_svg(view_box, size))
+}
+
+fn play_record(record: &Record, canvas: &mut Canvas) -> Result<()> {
+    match record.function {
+        META_SAVEDC => canvas.save(),
+        META_RESTOREDC => canvas.restore(record.i16(6)? as i32),
+        META_SETPOLYFILLMODE => canvas.set_winding_fill(record.u16(6)? == 2),
+        META_SETTEXTALIGN => canvas.set_text_align(record.u16(6)? as u32),
+        META_SETTEXTCOLOR => canvas.set_text_color(Color::from_color_ref(record.u32(6)?)),
+        META_SETWINDOWORG => canvas.set_window_org(record.f64(8)?, record.f64(6)?),
+        META_SETWINDOWEXT => {
+            fit_window(canvas, record.f64(8)?, record.f64(6)?);
+        }
+        META_MOVETO => canvas.move_to(record.f64(8)?, record.f64(6)?),
+        META_LINETO => canvas.line_to(record.f64(8)?, record.f64(6)?),
+        META_POLYGON => canvas.polyline(&record.points(6)?, true),
+        META_POLYLINE => canvas.polyline(&record.points(6)?, false),
+        META_POLYPOLYGON => {
+            let poly_count = record.u16(6)? as usize;
+            let counts = (0..poly_count)
+                .map(|i| record.u16(8 + i * 2).map(|c| c as usize))
+                .collect::<Result<Vec<_>>>()?;
+            let points = record.points_n(8 + poly_count * 2, counts.iter().sum())?;
+
+            let mut polys = vec![];
+            let mut start = 0;
+            for count in counts {
+                polys.push(&points[start..start + count]);
+                start += count;
+            }
+
+            canvas.polypolyline(&polys, true);
+        }
+        META_RECTANGLE => {
+            let (l, t, r, b) = record.rect(6)?;
+            canvas.rectangle(l, t, r, b);
+        }
+        META_ELLIPSE => {
+            let (l, t, r, b) = record.rect(6)?;
+            canvas.ellipse(l, t, r, b);
+        }
+        META_ROUNDRECT => {
+            let (l, t, r, b) = record.rect(10)?;
+            canvas.round_rect(l, t, r, b, record.f64(8)?, record.f64(6)?);
+        }
+        META_ARC | META_PIE | META_CHORD => {
+            let kind = match record.function {
+                META_ARC => ArcKind::Arc,
+                META_PIE => ArcKind::Pie,
+                _ => ArcKind::Chord,
+            };
+            let end = (record.f64(8)?, record.f64(6)?);
+            let start = (record.f64(12)?, record.f64(10)?);
+            canvas.arc(kind, record.rect(14)?, start, end);
+        }
+        META_CREATEPENINDIRECT => {
+            let pen = Pen {
+                style: record.u16(6)? as u32,
+                width: record.f64(8)?,
+                color: Color::from_color_ref(record.u32(12)?),
+            };
+            canvas.create_object(GdiObject::Pen(pen));
+        }
+        META_CREATEBRUSHINDIRECT => {
+            let brush = Brush {
+                style: record.u16(6)? as u32,
+                color: Color::from_color_ref(record.u32(8)?),
+            };
+            canvas.create_object(GdiObject::Brush(brush));
+        }
+        META_CREATEFONTINDIRECT => {
+            let face_name = record.bytes(24, record.data.len().saturating_sub(24).min(32))?;
+            let face_name = face_name.split(|b| *b == 0).next().unwrap_or_default();
+
+            let font = Font {
+                height: record.f64(6)?,
+                escapement: record.f64(10)?,
+                weight: record.u16(14)? as u32,
+                italic: record.u8(16)? != 0,
+                underline: record.u8(17)? != 0,
+                strikeout: record.u8(18)? != 0,
+                face_name: decode(face_name),
+            };
+            canvas.create_object(GdiObject::Font(font));
+        }
+        META_DIBCREATEPATTERNBRUSH | META_CREATEPATTERNBRUSH => {
+            // Pattern brushes are approximated with a gray fill
+            let brush = Brush {
+                style: 0,
+                color: Color::from_color_ref(0x808080),
+            };
+            canvas.create_object(GdiObject::Brush(brush));
+        }
+        META_CREATEPALETTE | META_CREATEREGION => {
+            // These objects occupy a slot in the object table
+            canvas.create_object(GdiObject::Other);
+        }
+        META_SELECTOBJECT => canvas.select_object(record.u16(6)? as usize),
+        META_DELETEOBJECT => canvas.delete_object(record.u16(6)? as usize),
+        META_TEXTOUT => {
+            let length = record.u16(6)? as usize;
+            let text = record.bytes(8, length)?;
+            let offset = 8 + length.div_ceil(2) * 2;
+            let y = record.f64(offset)?;
+            let x = record.f64(offset + 2)?;
+            canvas.text_out(x, y, &decode(text));
+        }
+        META_EXTTEXTOUT => {
+            let y = record.f64(6)?;
+            let x = record.f64(8)?;
+            let length = record.u16(10)? as usize;
+            let options = record.u16(12)?;
+            let offset = if options & (ETO_OPAQUE | ETO_CLIPPED) != 0 {
+                22
+            } else {
+                14
+            };
+            let text = record.bytes(offset, length)?;
+            canvas.text_out(x, y, &decode(text));
+        }
+        META_STRETCHDIB => {
+            let source = (
+                record.f64(18)?,
+                record.f64(16)?,
+                record.f64(14)?,
+                record.f64(12)?,
+            );
+            let dest = (
+                record.f64(26)?,
+                record.f64(24)?,
+                record.f64(22)?,
+                record.f64(20)?,
+            );
+            let bitmap = bitmap::convert_packed(record.bytes_from(28)?)?;
+            canvas.draw_bitmap(&bitmap, dest, source);
+        }
+        META_DIBSTRETCHBLT => {
+            // The record has an additional reserved field if it doesn't contain a bitmap
+            if record.has_bitmap() {
+                let source = (
+                    record.f64(16)?,
+                    record.f64(14)?,
+                    record.f64(12)?,
+                    record.f64(10)?,
+                );
+                let dest = (
+                    record.f64(24)?,
+                    record.f64(22)?,
+                    record.f64(20)?,
+                    record.f64(18)?,
+                );
+                let bitmap = bitmap::convert_packed(record.bytes_from(26)?)?;
+                canvas.draw_bitmap(&bitmap, dest, source);
+            } else {
+                let (x, y) = (record.f64(26)?, record.f64(24)?);
+                let (width, height) = (record.f64(22)?, record.f64(20)?);
+                canvas.fill_rect(x, y, x + width, y + height);
+            }
+        }
+        META_DIBBITBLT => {
+            if record.has_bitmap() {
+                let (width, height) = (record.f64(16)?, record.f64(14)?);
+                let source = (record.f64(12)?, record.f64(10)?, width, height);
+                let dest = (record.f64(20)?, record.f64(18)?, width, height);
+                let bitmap = bitmap::convert_packed(record.bytes_from(22)?)?;
+                canvas.draw_bitmap(&bitmap, dest, source);
+            } else {
+                let (x, y) = (record.f64(22)?, record.f64(20)?);
+                let (width, height) = (record.f64(18)?, record.f64(16)?);
+                canvas.fill_rect(x, y, x + width, y + height);
+            }
+        }
+        // The logical window is always mapped to the image bounds, so we ignore
+        // records that modify the mapping of the device context.
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Set the window extent and map the window to the image bounds.
+fn fit_window(canvas: &mut Canvas, width: f64, height: f64) {
+    canvas.set_window_ext(width, height);
+    canvas.set_viewport_ext(width.abs(), height.abs());
+
+    // Flipped axes start at the opposite side of the image
+    canvas.set_viewport_org(
+        if width < 0.0 { width.abs() } else { 0.0 },
+        if height < 0.0 { height.abs() } else { 0.0 },
+    );
+}
+
+/// A WMF record including its size and function fields.
+struct Record<'a> {
+    function: u16,
+    data: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    fn read(data: &'a [u8], offset: usize) -> Result<Record<'a>> {
+        let size = read_u32(data, offset)? as usize;
+        let function = read_u16(data, offset + 4)?;
+
+        let size = size
+            .checked_mul(2)
+            .filter(|size| *size >= 6)
+            .ok_or_else(|| malformed("invalid record size"))?;
+
+        let data = data
+            .get(offset..offset.saturating_add(size))
+            .ok_or_else(|| malformed("record is truncated"))?;
+
+        Ok(Record { function, data })
+    }
+
+    /// Check whether a bit block transfer record contains a bitmap.
+    fn has_bitmap(&self) -> bool {
+        self.data.len() != ((self.function >> 8) as usize + 3) * 2
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8]> {
+        self.data
+            .get(offset..offset.saturating_add(len))
+            .ok_or_else(|| malformed("record is truncated"))
+    }
+
+    fn bytes_from(&self, offset: usize) -> Result<&'a [u8]> {
+        self.data
+            .get(offset..)
+            .ok_or_else(|| malformed("record is truncated"))
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8> {
+        Ok(self.bytes(offset, 1)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16> {
+        read_u16(self.data, offset)
+    }
+
+    fn i16(&self, offset: usize) -> Result<i16> {
+        read_i16(self.data, offset)
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32> {
+        read_u32(self.data, offset)
+    }
+
+    fn f64(&self, offset: usize) -> Result<f64> {
+        Ok(self.i16(offset)? as f64)
+    }
+
+    /// Read a rectangle that is stored as bottom, right, top, left.
+    fn rect(&self, offset: usize) -> Result<(f64, f64, f64, f64)> {
+        Ok((
+            self.f64(offset + 6)?,
+            self.f64(offset + 4)?,
+            self.f64(offset + 2)?,
+            self.f64(offset)?,
+        ))
+    }
+
+    /// Read a point array that is preceded by its point count.
+    fn points(&self, offset: usize) -> Result<Vec<(f64, f64)>> {
+        let count = self.u16(offset)? as usize;
+        self.points_n(offset + 2, count)
+    }
+
+    fn points_n(&self, offset: usize, count: usize) -> Result<Vec<(f64, f64)>> {
+        let data = self.bytes(offset, count * 4)?;
+        Ok(data
+            .chunks_exact(4)
+            .map(|p| {
+                (
+                    i16::from_le_bytes([p[0], p[1]]) as f64,
+                    i16::from_le_bytes([p[2], p[3]]) as f64,
+                )
+            })
+            .collect())
+    }
+}
+
+fn decode(text: &[u8]) -> String {
+    WINDOWS_1252
+        .decode_without_bom_handling(text)
+        .0
+        .into_owned()
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|v| u16::from_le_bytes([v[0], v[1]]))
+        .ok_or_else(|| malformed("record is truncated"))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Result<i16> {
+    Ok(read_u16(data, offset)? as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+        .ok_or_else(|| malformed("record is truncated"))
+}
+
+fn malformed(message: &'static str) -> crate::errors::Error {
+    ErrorKind::MalformedMetafileData(message.into()).into()
+}
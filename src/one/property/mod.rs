@@ -23,11 +23,32 @@ mod references;
 pub(crate) mod simple;
 pub(crate) mod time;
 
-#[repr(u32)]
-#[derive(Debug, Copy, Clone, PartialEq)]
-#[allow(dead_code)]
-#[allow(clippy::enum_clike_unportable_variant)]
-pub(crate) enum PropertyType {
+/// Defines the `PropertyType` enum along with a lookup by property ID.
+macro_rules! property_types {
+    ($($name:ident = $value:literal,)*) => {
+        #[repr(u32)]
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        #[allow(dead_code)]
+        #[allow(clippy::enum_clike_unportable_variant)]
+        pub(crate) enum PropertyType {
+            $($name = $value,)*
+        }
+
+        impl PropertyType {
+            /// Look up a property type by its property ID.
+            ///
+            /// Only the ID bits are compared, the property type and the boolean flag are ignored.
+            #[cfg_attr(not(feature = "raw"), allow(dead_code))]
+            pub(crate) fn from_id(id: u32) -> Option<PropertyType> {
+                [$(PropertyType::$name,)*]
+                    .into_iter()
+                    .find(|prop_type| *prop_type as u32 & 0x3ffffff == id & 0x3ffffff)
+            }
+        }
+    };
+}
+
+property_types! {
     ActionItemSchemaVersion = 0x0C003473,
     ActionItemStatus = 0x10003470,
     ActionItemType = 0x10003463,
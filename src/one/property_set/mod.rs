@@ -41,6 +41,7 @@ pub(crate) mod table_row_node;
 pub(crate) mod text_run_data;
 pub(crate) mod title_node;
 pub(crate) mod toc_container;
+pub(crate) mod xps_container;
 
 #[derive(Debug, Copy, Clone, Primitive)]
 pub(crate) enum PropertySetId {
@@ -32,6 +32,7 @@ pub(crate) struct Data {
     pub(crate) page_margin_bottom: Option<f32>,
     pub(crate) page_size: PageSize,
     pub(crate) rtl: bool,
+    pub(crate) is_conflict_page: bool,
 }
 
 pub(crate) fn parse(object: &Object) -> Result<Data> {
@@ -58,6 +59,8 @@ pub(crate) fn parse(object: &Object) -> Result<Data> {
     let page_margin_bottom = simple::parse_f32(PropertyType::PageMarginBottom, object)?;
     let page_size = PageSize::parse(PropertyType::PageSize, object)?.unwrap_or_default();
     let rtl = simple::parse_bool(PropertyType::EditRootRtl, object)?.unwrap_or_default();
+    let is_conflict_page =
+        simple::parse_bool(PropertyType::IsConflictPage, object)?.unwrap_or_default();
 
     let data = Data {
         last_modified,
@@ -76,6 +79,7 @@ pub(crate) fn parse(object: &Object) -> Result<Data> {
         page_margin_bottom,
         page_size,
         rtl,
+        is_conflict_page,
     };
 
     Ok(data)
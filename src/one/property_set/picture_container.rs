@@ -2,6 +2,7 @@ use crate::errors::{ErrorKind, Result};
 use crate::one::property::{PropertyType, simple};
 use crate::one::property_set::PropertySetId;
 use crate::onestore::object::Object;
+use crate::shared::file_data::FileData;
 
 /// A picture container.
 ///
@@ -11,7 +12,7 @@ use crate::onestore::object::Object;
 #[derive(Debug)]
 #[allow(dead_code)]
 pub(crate) struct Data {
-    pub(crate) data: Vec<u8>,
+    pub(crate) data: FileData,
     pub(crate) extension: Option<String>,
 }
 
@@ -25,7 +26,10 @@ pub(crate) fn parse(object: &Object) -> Result<Data> {
         .into());
     }
 
-    let data = object.file_data().map(|v| v.to_vec()).unwrap_or_default();
+    let data = object
+        .shared_file_data()
+        .map(FileData::from)
+        .unwrap_or_default();
     let extension = simple::parse_string(PropertyType::PictureFileExtension, object)?;
 
     Ok(Data { data, extension })
@@ -0,0 +1,26 @@
+use crate::errors::Result;
+use crate::one::property::{PropertyType, simple};
+use crate::one::property_set::{PropertySetId, assert_property_set};
+use crate::onestore::object::Object;
+use crate::shared::file_data::FileData;
+
+/// An XPS container.
+///
+/// Holds the document a printout image was created from (undocumented).
+#[derive(Debug)]
+pub(crate) struct Data {
+    pub(crate) data: FileData,
+    pub(crate) extension: Option<String>,
+}
+
+pub(crate) fn parse(object: &Object) -> Result<Data> {
+    assert_property_set(object, PropertySetId::XpsContainer)?;
+
+    let data = object
+        .shared_file_data()
+        .map(FileData::from)
+        .unwrap_or_default();
+    let extension = simple::parse_string(PropertyType::PictureFileExtension, object)?;
+
+    Ok(Data { data, extension })
+}
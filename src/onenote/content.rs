@@ -1,11 +1,13 @@
 use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property_set::PropertySetId;
+use crate::onenote::context::Context;
 use crate::onenote::embedded_file::{EmbeddedFile, parse_embedded_file};
 use crate::onenote::image::{Image, parse_image};
 use crate::onenote::ink::{Ink, parse_ink};
 use crate::onenote::rich_text::{RichText, parse_rich_text};
 use crate::onenote::table::{Table, parse_table};
+use crate::onenote::unknown::{UnknownContent, parse_unknown};
 use crate::onestore::object_space::ObjectSpace;
 
 /// The content of an outline.
@@ -26,8 +28,8 @@ pub enum Content {
     /// An ink drawing.
     Ink(Ink),
 
-    /// Content of unknown type.
-    Unknown,
+    /// Content of a type the parser doesn't support.
+    Unknown(UnknownContent),
 }
 
 impl Content {
@@ -75,29 +77,61 @@ impl Content {
             None
         }
     }
+
+    /// Return the preserved data if it's content of an unsupported type.
+    pub fn unknown(&self) -> Option<&UnknownContent> {
+        if let Content::Unknown(unknown) = self {
+            Some(unknown)
+        } else {
+            None
+        }
+    }
 }
 
-pub(crate) fn parse_content(content_id: ExGuid, space: &ObjectSpace) -> Result<Content> {
-    let content_type = space
+pub(crate) fn parse_content(
+    content_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+) -> Result<Content> {
+    let object = space
         .get_object(content_id)
-        .ok_or_else(|| ErrorKind::MalformedOneNoteData("page content is missing".into()))?
-        .id();
-    let id = PropertySetId::from_jcid(content_type).ok_or_else(|| {
-        ErrorKind::MalformedOneNoteData(
-            format!("invalid property set id: 0x{:X}", content_type.0).into(),
-        )
-    })?;
-
-    let content = match id {
-        PropertySetId::ImageNode => Content::Image(parse_image(content_id, space)?),
-        PropertySetId::EmbeddedFileNode => {
-            Content::EmbeddedFile(parse_embedded_file(content_id, space)?)
+        .ok_or_else(|| ErrorKind::MalformedOneNoteData("page content is missing".into()))?;
+
+    let content = match PropertySetId::from_jcid(object.id()) {
+        Some(PropertySetId::ImageNode) => parse_image(content_id, space).map(Content::Image),
+        Some(PropertySetId::EmbeddedFileNode) => {
+            parse_embedded_file(content_id, space).map(Content::EmbeddedFile)
+        }
+        Some(PropertySetId::RichTextNode) => {
+            parse_rich_text(content_id, space, ctx).map(Content::RichText)
         }
-        PropertySetId::RichTextNode => Content::RichText(parse_rich_text(content_id, space)?),
-        PropertySetId::TableNode => Content::Table(parse_table(content_id, space)?),
-        PropertySetId::InkContainer => Content::Ink(parse_ink(content_id, space)?),
-        _ => Content::Unknown,
+        Some(PropertySetId::TableNode) => parse_table(content_id, space, ctx).map(Content::Table),
+        Some(PropertySetId::InkContainer) => parse_ink(content_id, space, ctx).map(Content::Ink),
+        _ => Ok(Content::Unknown(parse_unknown(content_id, object, ctx))),
     };
 
-    Ok(content)
+    content.map_err(|err| err.with_jcid(object.id().0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_content;
+    use crate::limits::ParseLimits;
+    use crate::one::property_set::PropertySetId;
+    use crate::onenote::context::Context;
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, id, with_space};
+
+    #[test]
+    fn test_parse_content_errors_have_jcid() {
+        // The table has no rows and columns
+        let space =
+            SpaceBuilder::new(1).content_root(ObjectBuilder::new(id(1), PropertySetId::TableNode));
+        let ctx = Context::new(false, ParseLimits::default());
+
+        let err = with_space(space, |space| {
+            parse_content(id(1), space, &ctx).err().unwrap()
+        });
+
+        assert_eq!(err.jcid(), Some(PropertySetId::TableNode as u32));
+    }
 }
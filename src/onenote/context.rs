@@ -0,0 +1,320 @@
+use crate::errors::{Diagnostic, Error, ErrorKind, Result};
+use crate::limits::ParseLimits;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+/// Diagnostics and unsupported JCIDs that have been recorded while parsing an object.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Records {
+    diagnostics: Vec<Diagnostic>,
+    unknown_jcids: BTreeMap<u32, usize>,
+}
+
+/// State that is shared while parsing a section or notebook.
+pub(crate) struct Context {
+    lenient: bool,
+    limits: ParseLimits,
+    depth: Cell<usize>,
+    diagnostics: RefCell<Vec<Diagnostic>>,
+    unknown_jcids: RefCell<BTreeMap<u32, usize>>,
+}
+
+impl Context {
+    pub(crate) fn new(lenient: bool, limits: ParseLimits) -> Context {
+        Context {
+            lenient,
+            limits,
+            depth: Cell::new(0),
+            diagnostics: RefCell::new(vec![]),
+            unknown_jcids: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Run `f` one nesting level deeper.
+    ///
+    /// Fails if this exceeds the maximum nesting depth. This must be used for
+    /// objects that can (indirectly) contain themselves, e.g. outline elements.
+    pub(crate) fn nested<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let depth = self.depth.get() + 1;
+        self.limits.check_depth(depth)?;
+
+        self.depth.set(depth);
+        let result = f();
+        self.depth.set(depth - 1);
+
+        result
+    }
+
+    /// Run `f` and recover from errors in lenient mode.
+    ///
+    /// In lenient mode a failure is recorded as a diagnostic and `None` is returned.
+    /// Cancellation is never recovered from.
+    /// The `segment` function describes the location of the parsed object relative to
+    /// its parent. It's only evaluated if the parsing fails.
+    pub(crate) fn recover<T>(
+        &self,
+        segment: impl FnOnce() -> String,
+        f: impl FnOnce() -> Result<T>,
+    ) -> Result<Option<T>> {
+        let start = self.diagnostics.borrow().len();
+
+        let result = f();
+
+        let mut diagnostics = self.diagnostics.borrow_mut();
+        let value = match result {
+            Ok(value) => Some(value),
+            Err(error) if self.lenient && !matches!(error.kind(), ErrorKind::Cancelled) => {
+                diagnostics.push(Diagnostic::new(error));
+                None
+            }
+            Err(error) => return Err(error.with_location(segment())),
+        };
+
+        if diagnostics.len() > start {
+            let segment = segment();
+            for diagnostic in &mut diagnostics[start..] {
+                diagnostic.location.insert(0, segment.clone());
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Record a problem that doesn't prevent parsing as a diagnostic.
+    ///
+    /// Unlike [`Context::recover`], this also records the problem outside of
+    /// lenient mode. The `segment` describes the location of the problem.
+    pub(crate) fn report(&self, segment: String, error: Error) {
+        let mut diagnostic = Diagnostic::new(error);
+        diagnostic.location.push(segment);
+
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    /// Parse a list of items, skipping items that fail in lenient mode.
+    ///
+    /// The location of each item is described by its `kind` and its index.
+    pub(crate) fn collect<I, T>(
+        &self,
+        kind: &str,
+        items: impl IntoIterator<Item = I>,
+        mut f: impl FnMut(I) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut values = vec![];
+        for (index, item) in items.into_iter().enumerate() {
+            if let Some(value) = self.recover(|| format!("{kind} {index}"), || f(item))? {
+                values.push(value);
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Parse a list of items like [`Context::collect`] but in parallel.
+    ///
+    /// Each item is parsed with its own context. The results, diagnostics and
+    /// unsupported JCIDs are merged in the order of the items, so the output
+    /// doesn't depend on the order in which the items finished parsing.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn collect_parallel<I, T>(
+        &self,
+        kind: &str,
+        items: Vec<I>,
+        f: impl Fn(I, &Context) -> Result<T> + Sync,
+    ) -> Result<Vec<T>>
+    where
+        I: Send,
+        T: Send,
+    {
+        use rayon::prelude::*;
+
+        let (lenient, limits, depth) = (self.lenient, self.limits, self.depth.get());
+
+        let results: Vec<_> = items
+            .into_par_iter()
+            .map(|item| {
+                let ctx = Context::new(lenient, limits);
+                ctx.depth.set(depth);
+
+                let result = f(item, &ctx);
+                (result, ctx)
+            })
+            .collect();
+
+        let mut values = vec![];
+        for (index, (result, ctx)) in results.into_iter().enumerate() {
+            let value = self.recover(
+                || format!("{kind} {index}"),
+                || {
+                    self.merge(ctx);
+                    result
+                },
+            )?;
+
+            values.extend(value);
+        }
+
+        Ok(values)
+    }
+
+    /// Parse a list of items like [`Context::collect`].
+    ///
+    /// This is the fallback for [`Context::collect_parallel`] when the
+    /// `parallel` feature is disabled.
+    #[cfg(not(feature = "parallel"))]
+    pub(crate) fn collect_parallel<I, T>(
+        &self,
+        kind: &str,
+        items: Vec<I>,
+        f: impl Fn(I, &Context) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        self.collect(kind, items, |item| f(item, self))
+    }
+
+    /// Run `f` with its own context and return what it recorded.
+    ///
+    /// The diagnostics and unsupported JCIDs are also added to this context,
+    /// so the result can later be reused with [`Context::replay`].
+    pub(crate) fn record<T>(&self, f: impl FnOnce(&Context) -> Result<T>) -> Result<(T, Records)> {
+        let ctx = Context::new(self.lenient, self.limits);
+        ctx.depth.set(self.depth.get());
+
+        let result = f(&ctx);
+        let records = Records {
+            diagnostics: ctx.diagnostics.into_inner(),
+            unknown_jcids: ctx.unknown_jcids.into_inner(),
+        };
+        self.replay(&records);
+
+        result.map(|value| (value, records))
+    }
+
+    /// Add the diagnostics and unsupported JCIDs recorded by [`Context::record`].
+    pub(crate) fn replay(&self, records: &Records) {
+        self.diagnostics
+            .borrow_mut()
+            .extend(records.diagnostics.iter().cloned());
+
+        let mut unknown_jcids = self.unknown_jcids.borrow_mut();
+        for (jcid, count) in &records.unknown_jcids {
+            *unknown_jcids.entry(*jcid).or_default() += count;
+        }
+    }
+
+    /// Add the diagnostics and unsupported JCIDs recorded by another context.
+    #[cfg(feature = "parallel")]
+    fn merge(&self, other: Context) {
+        self.diagnostics
+            .borrow_mut()
+            .extend(other.diagnostics.into_inner());
+
+        let mut unknown_jcids = self.unknown_jcids.borrow_mut();
+        for (jcid, count) in other.unknown_jcids.into_inner() {
+            *unknown_jcids.entry(jcid).or_default() += count;
+        }
+    }
+
+    /// Take the diagnostics that have been recorded so far.
+    pub(crate) fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.take()
+    }
+
+    /// Record that content with an unsupported JCID has been encountered.
+    pub(crate) fn record_unknown_jcid(&self, jcid: u32) {
+        *self.unknown_jcids.borrow_mut().entry(jcid).or_default() += 1;
+    }
+
+    /// Take the unsupported JCIDs that have been recorded so far.
+    pub(crate) fn take_unknown_jcids(&self) -> BTreeMap<u32, usize> {
+        self.unknown_jcids.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+    use crate::errors::{ErrorKind, Limit, Result};
+    use crate::limits::ParseLimits;
+
+    fn parse(value: u32) -> Result<u32> {
+        if value % 2 == 0 {
+            Ok(value)
+        } else {
+            Err(ErrorKind::MalformedOneNoteData("odd value".into()).into())
+        }
+    }
+
+    #[test]
+    fn test_strict_mode_fails() {
+        let context = Context::new(false, ParseLimits::default());
+        let result = context.collect("item", [0, 1, 2], parse);
+
+        assert_eq!(result.unwrap_err().location(), ["item 1"]);
+        assert!(context.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_lenient_mode_skips_items() {
+        let context = Context::new(true, ParseLimits::default());
+        let values = context
+            .recover(
+                || "list".to_string(),
+                || context.collect("item", [0, 1, 2, 3], parse),
+            )
+            .unwrap();
+
+        assert_eq!(values, Some(vec![0, 2]));
+
+        let diagnostics = context.take_diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].location(), ["list", "item 1"]);
+        assert_eq!(diagnostics[1].location(), ["list", "item 3"]);
+        assert!(matches!(
+            diagnostics[0].kind(),
+            ErrorKind::MalformedOneNoteData(_)
+        ));
+    }
+
+    #[test]
+    fn test_collect_parallel_keeps_order() {
+        let context = Context::new(true, ParseLimits::default());
+        let values = context
+            .collect_parallel("item", (0..100).collect(), |value, ctx| {
+                ctx.record_unknown_jcid(value % 3);
+                parse(value)
+            })
+            .unwrap();
+
+        assert_eq!(values, (0..100).step_by(2).collect::<Vec<_>>());
+
+        let diagnostics = context.take_diagnostics();
+        assert_eq!(diagnostics.len(), 50);
+        assert_eq!(diagnostics[0].location(), ["item 1"]);
+        assert_eq!(diagnostics[49].location(), ["item 99"]);
+
+        let unknown_jcids = context.take_unknown_jcids();
+        assert_eq!(unknown_jcids.values().sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        fn nest(context: &Context, level: u32) -> Result<u32> {
+            if level == 0 {
+                return Ok(0);
+            }
+
+            context.nested(|| nest(context, level - 1).map(|depth| depth + 1))
+        }
+
+        let context = Context::new(false, ParseLimits::default().with_max_depth(3));
+
+        assert_eq!(nest(&context, 3).unwrap(), 3);
+        assert!(matches!(
+            nest(&context, 4).unwrap_err().kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::Depth,
+                ..
+            }
+        ));
+    }
+}
@@ -1,9 +1,12 @@
 use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property::file_type::FileType;
-use crate::one::property_set::{embedded_file_container, embedded_file_node};
+use crate::one::property_set::{embedded_file_container, embedded_file_node, picture_container};
 use crate::onenote::note_tag::{NoteTag, parse_note_tags};
 use crate::onestore::object_space::ObjectSpace;
+use crate::shared::file_data::FileData;
+use crate::shared::media::{ContentType, recording_duration};
+use std::time::Duration;
 
 /// An embedded file.
 ///
@@ -14,7 +17,15 @@ use crate::onestore::object_space::ObjectSpace;
 pub struct EmbeddedFile {
     pub(crate) filename: String,
     pub(crate) file_type: FileType,
-    pub(crate) data: Vec<u8>,
+    pub(crate) data: FileData,
+    pub(crate) source_path: Option<String>,
+    pub(crate) recording_duration: Option<Duration>,
+
+    pub(crate) preview_data: Option<FileData>,
+    pub(crate) preview_extension: Option<String>,
+
+    pub(crate) text: Option<String>,
+    pub(crate) text_language_code: Option<u32>,
 
     pub(crate) layout_max_width: Option<f32>,
     pub(crate) layout_max_height: Option<f32>,
@@ -49,6 +60,55 @@ impl EmbeddedFile {
         &self.data
     }
 
+    /// The file's content type as determined from its binary data.
+    ///
+    /// Returns `None` if the format isn't recognized.
+    pub fn content_type(&self) -> Option<ContentType> {
+        ContentType::detect(&self.data)
+    }
+
+    /// Whether the file name's extension doesn't match the file's actual content type.
+    pub fn has_content_type_mismatch(&self) -> bool {
+        self.content_type()
+            .map(|content_type| !content_type.matches_name(&self.filename))
+            .unwrap_or_default()
+    }
+
+    /// The original path of the file that was embedded.
+    pub fn source_path(&self) -> Option<&str> {
+        self.source_path.as_deref()
+    }
+
+    /// The play duration of an audio or video recording.
+    ///
+    /// The duration is read from the recording's media header and is only available
+    /// for [`FileType::Audio`] and [`FileType::Video`] files in a supported format
+    /// (ASF/Windows Media, WAV and MP3). The file node's `RecordingDuration`
+    /// property isn't parsed, so the duration is sniffed from the file's data.
+    pub fn recording_duration(&self) -> Option<Duration> {
+        self.recording_duration
+    }
+
+    /// The binary data of the embedded file's preview image (if present).
+    pub fn preview_data(&self) -> Option<&[u8]> {
+        self.preview_data.as_deref()
+    }
+
+    /// The file extension of the embedded file's preview image (if present).
+    pub fn preview_extension(&self) -> Option<&str> {
+        self.preview_extension.as_deref()
+    }
+
+    /// The text for the embedded file (e.g. the transcript of a recording).
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// The text's language code (MS-LCID)
+    pub fn text_language_code(&self) -> Option<u32> {
+        self.text_language_code
+    }
+
     /// The max width of the embedded file's icon in half-inch increments.
     ///
     /// See [\[MS-ONE\] 2.3.21].
@@ -103,10 +163,40 @@ pub(crate) fn parse_embedded_file(file_id: ExGuid, space: &ObjectSpace) -> Resul
     })?;
     let container = embedded_file_container::parse(container_object)?;
 
+    let data = container.into_value();
+
+    let recording_duration = match node.file_type {
+        FileType::Audio | FileType::Video => recording_duration(&data),
+        FileType::Unknown => None,
+    };
+
+    let preview = node
+        .picture_container
+        .map(|container_object_id| {
+            space.get_object(container_object_id).ok_or_else(|| {
+                ErrorKind::MalformedOneNoteData("embedded file preview container is missing".into())
+            })
+        })
+        .transpose()?
+        .map(picture_container::parse)
+        .transpose()?;
+
+    let (preview_data, preview_extension) = if let Some(preview) = preview {
+        (Some(preview.data), preview.extension)
+    } else {
+        (None, None)
+    };
+
     let file = EmbeddedFile {
         filename: node.embedded_file_name,
         file_type: node.file_type,
-        data: container.into_value(),
+        data,
+        source_path: node.source_path,
+        recording_duration,
+        preview_data,
+        preview_extension,
+        text: node.text,
+        text_language_code: node.text_language_code,
         layout_max_width: node.layout_max_width,
         layout_max_height: node.layout_max_height,
         offset_horizontal: node.offset_from_parent_horiz,
@@ -1,10 +1,13 @@
 use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property::layout_alignment::LayoutAlignment;
-use crate::one::property_set::{image_node, picture_container};
+use crate::one::property_set::{PropertySetId, image_node, picture_container, xps_container};
 use crate::onenote::iframe::{IFrame, parse_iframe};
 use crate::onenote::note_tag::{NoteTag, parse_note_tags};
+use crate::onestore::object::Object;
 use crate::onestore::object_space::ObjectSpace;
+use crate::shared::file_data::FileData;
+use crate::shared::media::ContentType;
 
 /// An embedded image.
 ///
@@ -13,7 +16,7 @@ use crate::onestore::object_space::ObjectSpace;
 /// [\[MS-ONE\] 2.2.24]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/b7bb4d1a-2a57-4819-9eb4-5a2ce8cf210f
 #[derive(Clone, PartialEq, PartialOrd, Debug)]
 pub struct Image {
-    pub(crate) data: Option<Vec<u8>>,
+    pub(crate) data: Option<FileData>,
     pub(crate) extension: Option<String>,
 
     pub(crate) layout_max_width: Option<f32>,
@@ -45,6 +48,8 @@ pub struct Image {
     pub(crate) note_tags: Vec<NoteTag>,
 
     pub(crate) embeds: Vec<IFrame>,
+
+    pub(crate) printout: Option<Box<Printout>>,
 }
 
 impl Image {
@@ -60,6 +65,56 @@ impl Image {
         self.extension.as_deref()
     }
 
+    /// The image's content type as determined from its binary data.
+    ///
+    /// Returns `None` if there is no image data or the format isn't recognized.
+    pub fn content_type(&self) -> Option<ContentType> {
+        self.data.as_deref().and_then(ContentType::detect)
+    }
+
+    /// Whether the image's extension (or file name) doesn't match its actual content type.
+    pub fn has_content_type_mismatch(&self) -> bool {
+        let declared = self.extension.as_deref().or(self.image_filename.as_deref());
+
+        match (self.content_type(), declared) {
+            (Some(content_type), Some(declared)) => !content_type.matches_name(declared),
+            _ => false,
+        }
+    }
+
+    /// Whether the image is an EMF or WMF metafile.
+    pub fn is_metafile(&self) -> bool {
+        matches!(
+            self.content_type(),
+            Some(ContentType::Emf | ContentType::Wmf)
+        )
+    }
+
+    /// Convert a metafile image to an SVG document.
+    ///
+    /// Returns `None` if there is no image data or the image isn't an EMF or
+    /// WMF metafile.
+    #[cfg(feature = "metafile")]
+    pub fn to_svg(&self) -> Result<Option<String>> {
+        match self.data.as_deref() {
+            Some(data) if self.is_metafile() => crate::metafile::to_svg(data).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Rasterize a metafile image to a PNG image.
+    ///
+    /// The `scale` factor is applied to the metafile's natural size at 96 DPI.
+    /// Returns `None` if there is no image data or the image isn't an EMF or
+    /// WMF metafile.
+    #[cfg(feature = "metafile-png")]
+    pub fn to_png(&self, scale: f32) -> Result<Option<Vec<u8>>> {
+        match self.data.as_deref() {
+            Some(data) if self.is_metafile() => crate::metafile::to_png(data, scale).map(Some),
+            _ => Ok(None),
+        }
+    }
+
     /// The maximum width to display the image in half-inch increments.
     ///
     /// See [\[MS-ONE\] 2.3.21].
@@ -194,6 +249,51 @@ impl Image {
     pub fn embeds(&self) -> &[IFrame] {
         &self.embeds
     }
+
+    /// The document this image was printed from (if present).
+    ///
+    /// Images created using OneNote's "Insert as printout" feature keep a
+    /// reference to the original document.
+    pub fn printout(&self) -> Option<&Printout> {
+        self.printout.as_deref()
+    }
+}
+
+/// The source document of a printout image.
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct Printout {
+    pub(crate) data: FileData,
+    pub(crate) extension: Option<String>,
+    pub(crate) page_number: Option<u32>,
+}
+
+impl Printout {
+    /// The source document's binary data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The source document's file extension.
+    pub fn extension(&self) -> Option<&str> {
+        self.extension.as_deref()
+    }
+
+    /// The source document's content type as determined from its binary data.
+    pub fn content_type(&self) -> Option<ContentType> {
+        ContentType::detect(&self.data)
+    }
+
+    /// The page number of the document page that is displayed by the image.
+    ///
+    /// This is the page number as displayed to the user, not a zero-based
+    /// page index.
+    ///
+    /// See [\[MS-ONE\] 2.3.95].
+    ///
+    /// [\[MS-ONE\] 2.3.95]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/acf52570-aa45-45ef-a62e-38f4a5844d5e
+    pub fn page_number(&self) -> Option<u32> {
+        self.page_number
+    }
 }
 
 pub(crate) fn parse_image(image_id: ExGuid, space: &ObjectSpace) -> Result<Image> {
@@ -210,7 +310,7 @@ pub(crate) fn parse_image(image_id: ExGuid, space: &ObjectSpace) -> Result<Image
                 .ok_or_else(|| ErrorKind::MalformedOneNoteData("image container is missing".into()))
         })
         .transpose()?
-        .map(|container_object| picture_container::parse(container_object))
+        .map(picture_container::parse)
         .transpose()?;
 
     let (data, extension) = if let Some(data) = container_data {
@@ -219,6 +319,17 @@ pub(crate) fn parse_image(image_id: ExGuid, space: &ObjectSpace) -> Result<Image
         (None, None)
     };
 
+    let printout = find_xps_container(node_object, space)
+        .map(xps_container::parse)
+        .transpose()?
+        .map(|container| {
+            Box::new(Printout {
+                data: container.data,
+                extension: container.extension,
+                page_number: node.displayed_page_number,
+            })
+        });
+
     let embed = node
         .iframe
         .into_iter()
@@ -245,7 +356,114 @@ pub(crate) fn parse_image(image_id: ExGuid, space: &ObjectSpace) -> Result<Image
         is_background: node.is_background,
         note_tags: parse_note_tags(node.note_tags, space)?,
         embeds: embed,
+        printout,
     };
 
     Ok(image)
 }
+
+/// Find the XPS container referenced by an image node.
+///
+/// The property referencing the container isn't documented, so we look at all
+/// objects referenced by the image node instead.
+fn find_xps_container<'a>(node: &Object, space: &'a ObjectSpace) -> Option<&'a Object<'a>> {
+    node.props()
+        .object_ids()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, id)| node.mapping().get_object(index, *id))
+        .filter_map(|id| space.get_object(id))
+        .find(|object| object.id() == PropertySetId::XpsContainer.as_jcid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_image;
+    use crate::fsshttpb::writer::Value;
+    use crate::one::property::PropertyType;
+    use crate::one::property_set::PropertySetId;
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, id, with_space};
+
+    #[test]
+    fn test_parse_image_without_last_modified() {
+        let space = SpaceBuilder::new(1)
+            .object(
+                ObjectBuilder::new(id(10), PropertySetId::ImageNode)
+                    .object_ref(PropertyType::PictureContainer, id(11))
+                    .prop(PropertyType::PictureWidth, Value::U32(2.5f32.to_bits())),
+            )
+            .object(
+                ObjectBuilder::new(id(11), PropertySetId::PictureContainer)
+                    .string(PropertyType::PictureFileExtension, ".png")
+                    .file_data(b"\x89PNG"),
+            );
+
+        let image = with_space(space, |space| parse_image(id(10), space)).unwrap();
+
+        assert_eq!(image.data(), Some(&b"\x89PNG"[..]));
+        assert_eq!(image.extension(), Some(".png"));
+        assert_eq!(image.picture_width(), Some(2.5));
+    }
+
+    #[test]
+    fn test_parse_image_with_xps_container() {
+        let space = SpaceBuilder::new(1)
+            .object(
+                ObjectBuilder::new(id(10), PropertySetId::ImageNode)
+                    .prop(PropertyType::DisplayedPageNumber, Value::U32(2))
+                    .object_refs(PropertyType::ElementChildNodes, &[id(12)]),
+            )
+            .object(
+                ObjectBuilder::new(id(12), PropertySetId::XpsContainer)
+                    .string(PropertyType::PictureFileExtension, ".pdf")
+                    .file_data(b"%PDF-1.7"),
+            );
+
+        let image = with_space(space, |space| parse_image(id(10), space)).unwrap();
+        let printout = image.printout().unwrap();
+
+        assert_eq!(printout.data(), b"%PDF-1.7");
+        assert_eq!(printout.extension(), Some(".pdf"));
+        assert_eq!(printout.page_number(), Some(2));
+    }
+
+    #[test]
+    fn test_parse_image_without_xps_container() {
+        let space = SpaceBuilder::new(1)
+            .object(
+                ObjectBuilder::new(id(10), PropertySetId::ImageNode)
+                    .object_ref(PropertyType::PictureContainer, id(11))
+                    .prop(PropertyType::DisplayedPageNumber, Value::U32(2)),
+            )
+            .object(
+                ObjectBuilder::new(id(11), PropertySetId::PictureContainer).file_data(b"\x89PNG"),
+            );
+
+        let image = with_space(space, |space| parse_image(id(10), space)).unwrap();
+
+        assert!(image.printout().is_none());
+    }
+
+    #[test]
+    fn test_parse_image_with_several_xps_candidates() {
+        // The first referenced XPS container is used, other objects are skipped.
+        let space = SpaceBuilder::new(1)
+            .object(
+                ObjectBuilder::new(id(10), PropertySetId::ImageNode)
+                    .object_ref(PropertyType::PictureContainer, id(11))
+                    .object_refs(PropertyType::ElementChildNodes, &[id(11), id(12), id(13)]),
+            )
+            .object(
+                ObjectBuilder::new(id(11), PropertySetId::PictureContainer).file_data(b"\x89PNG"),
+            )
+            .object(ObjectBuilder::new(id(12), PropertySetId::XpsContainer).file_data(b"first"))
+            .object(ObjectBuilder::new(id(13), PropertySetId::XpsContainer).file_data(b"second"));
+
+        let image = with_space(space, |space| parse_image(id(10), space)).unwrap();
+        let printout = image.printout().unwrap();
+
+        assert_eq!(printout.data(), b"first");
+        assert_eq!(printout.extension(), None);
+        assert_eq!(printout.page_number(), None);
+    }
+}
@@ -3,6 +3,7 @@ use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property_set::{
     ink_container, ink_data_node, ink_stroke_node, stroke_properties_node,
 };
+use crate::onenote::context::Context;
 use crate::onestore::object_space::ObjectSpace;
 
 /// An ink object.
@@ -164,7 +165,11 @@ impl InkBoundingBox {
     }
 }
 
-pub(crate) fn parse_ink(ink_container_id: ExGuid, space: &ObjectSpace) -> Result<Ink> {
+pub(crate) fn parse_ink(
+    ink_container_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+) -> Result<Ink> {
     let container_object = space
         .get_object(ink_container_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("ink container is missing".into()))?;
@@ -187,6 +192,7 @@ pub(crate) fn parse_ink(ink_container_id: ExGuid, space: &ObjectSpace) -> Result
         space,
         container.ink_scaling_x,
         container.ink_scaling_y,
+        ctx,
     )?;
 
     Ok(Ink {
@@ -202,18 +208,16 @@ pub(crate) fn parse_ink_data(
     space: &ObjectSpace,
     scale_x: Option<f32>,
     scale_y: Option<f32>,
+    ctx: &Context,
 ) -> Result<(Vec<InkStroke>, Option<InkBoundingBox>)> {
     let ink_data_object = space
         .get_object(ink_data_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("ink data node is missing".into()))?;
     let ink_data = ink_data_node::parse(ink_data_object)?;
 
-    let strokes = ink_data
-        .strokes
-        .iter()
-        .copied()
-        .map(|ink_stroke_id| parse_ink_stroke(ink_stroke_id, space, scale_x, scale_y))
-        .collect::<Result<_>>()?;
+    let strokes = ctx.collect("stroke", ink_data.strokes, |ink_stroke_id| {
+        parse_ink_stroke(ink_stroke_id, space, scale_x, scale_y)
+    })?;
 
     let scale_x = scale_x.unwrap_or(1.0);
     let scale_y = scale_y.unwrap_or(1.0);
@@ -304,3 +308,64 @@ fn parse_ink_path(
 
     Ok(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_ink_stroke;
+    use crate::fsshttpb::writer::Value;
+    use crate::one::property::PropertyType;
+    use crate::one::property_set::PropertySetId;
+    use crate::shared::guid::Guid;
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, id, with_space};
+
+    fn dimension(id: Guid, data: &mut Vec<u8>) {
+        data.extend_from_slice(&id.0.to_bytes_le());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&1000u32.to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+    }
+
+    #[test]
+    fn test_parse_ink_stroke_with_odd_dimensions() {
+        // Y before X, an additional pressure dimension and trailing data
+        let mut dimensions = vec![];
+        dimension(
+            guid!("b53f9f75-04e0-4498-a7ee-c30dbb5a9011"),
+            &mut dimensions,
+        );
+        dimension(
+            guid!("598a6a8f-52c0-4ba0-93af-af357411a561"),
+            &mut dimensions,
+        );
+        dimension(
+            guid!("7307502d-f9f4-4e18-b3f2-2ce1b1a3610c"),
+            &mut dimensions,
+        );
+        dimensions.extend_from_slice(&[0xFF; 16]);
+
+        // Multi-byte encoded path: y = [10, 20], x = [1, 2], pressure = [5, 5]
+        let path = vec![6 << 1, 20, 40, 2, 4, 10, 10];
+
+        let space = SpaceBuilder::new(1)
+            .object(
+                ObjectBuilder::new(id(10), PropertySetId::InkStrokeNode)
+                    .prop(PropertyType::InkPath, Value::Vec(path))
+                    .prop(PropertyType::InkBias, Value::U8(0))
+                    .object_ref(PropertyType::InkStrokeProperties, id(11)),
+            )
+            .object(
+                ObjectBuilder::new(id(11), PropertySetId::StrokePropertiesNode)
+                    .prop(PropertyType::InkWidth, Value::U32(1f32.to_bits()))
+                    .prop(PropertyType::InkHeight, Value::U32(1f32.to_bits()))
+                    .prop(PropertyType::InkDimensions, Value::Vec(dimensions)),
+            );
+
+        let stroke = with_space(space, |space| {
+            parse_ink_stroke(id(10), space, Some(2.0), None)
+        })
+        .unwrap();
+
+        let points: Vec<_> = stroke.path().iter().map(|p| (p.x(), p.y())).collect();
+        assert_eq!(points, [(2.0, 10.0), (4.0, 20.0)]);
+    }
+}
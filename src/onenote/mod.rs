@@ -1,16 +1,25 @@
+use crate::cab;
+use crate::cache::{CachedSection, PageCache, ParseCache};
 use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::packaging::OneStorePackaging;
-use crate::onenote::notebook::Notebook;
+use crate::limits::{Budget, ParseLimits};
+use crate::onenote::context::Context;
+use crate::onenote::notebook::{Notebook, Toc, TocEntry};
 use crate::onenote::section::{Section, SectionEntry, SectionGroup};
-use crate::onestore::parse_store;
+use crate::onenote::section_package::SectionPackage;
+use crate::onenote::source::{FileBuffer, LocalSource, MemorySource, Source};
+use crate::onestore::version::StoreVersion;
+use crate::onestore::{parse_store, parse_store_with};
+use crate::progress::{CancellationToken, Monitor, Phase, Progress};
 use crate::reader::Reader;
+use bytes::Bytes;
 use sanitise_file_name::sanitise;
 use std::ffi::OsStr;
-use std::fs::File;
-use std::io::{BufReader, Read};
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
 pub(crate) mod content;
+pub(crate) mod context;
 pub(crate) mod embedded_file;
 pub(crate) mod iframe;
 pub(crate) mod image;
@@ -23,29 +32,102 @@ pub(crate) mod outline;
 pub(crate) mod page;
 pub(crate) mod page_content;
 pub(crate) mod page_series;
+pub(crate) mod page_tree;
 pub(crate) mod rich_text;
 pub(crate) mod section;
+pub(crate) mod section_package;
+pub(crate) mod source;
 pub(crate) mod table;
+pub(crate) mod unknown;
 
 /// The OneNote file parser.
 ///
 /// Use [`Parser::parse_notebook`] to load a notebook from a `.onetoc2` file or
-/// [`Parser::parse_section`] to load a single `.one` section. These methods
+/// [`Parser::parse_section`] to load a single `.one` section. Notebooks that
+/// were exported as OneNote packages can be loaded using
+/// [`Parser::parse_onepkg`]. These methods
 /// expect OneDrive downloads (FSSHTTP packaging) and will return an error if the
 /// input is not the expected file type.
 ///
 /// # Thread safety
 ///
-/// The parser is stateless and can be shared across threads.
-pub struct Parser;
+/// The parser only holds its configuration and can be shared across threads.
+pub struct Parser {
+    lenient: bool,
+    limits: ParseLimits,
+    monitor: Monitor,
+    cache: Option<ParseCache>,
+}
 
 impl Parser {
     /// Create a new OneNote file parser.
     ///
-    /// The parser holds no state; reuse a single instance across multiple
-    /// parses if desired.
+    /// The parser holds no state besides its configuration; reuse a single
+    /// instance across multiple parses if desired.
     pub fn new() -> Parser {
-        Parser {}
+        Parser {
+            lenient: false,
+            limits: ParseLimits::default(),
+            monitor: Monitor::default(),
+            cache: None,
+        }
+    }
+
+    /// Enable or disable lenient parsing.
+    ///
+    /// By default the parser fails on the first error it encounters. In lenient
+    /// mode, objects that fail to parse (e.g. pages, outline elements, rich text
+    /// embedded objects or notebook sections) are skipped instead and the error
+    /// is recorded as a [`Diagnostic`]. Use [`Section::diagnostics`] and
+    /// [`Notebook::diagnostics`] to inspect the skipped errors.
+    ///
+    /// Errors in the file structure itself (e.g. a corrupt file header) can't
+    /// be recovered from and are still returned when parsing a section.
+    ///
+    /// [`Diagnostic`]: crate::errors::Diagnostic
+    pub fn with_lenient(mut self, lenient: bool) -> Parser {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Set the resource limits for parsing.
+    ///
+    /// The limits protect against untrusted files that would exhaust memory or
+    /// the stack. Files exceeding the limits fail to parse with
+    /// [`ErrorKind::LimitExceeded`]. [`ParseLimits::default`] is used unless
+    /// other limits are set.
+    pub fn with_limits(mut self, limits: ParseLimits) -> Parser {
+        self.limits = limits;
+        self
+    }
+
+    /// Report the parsing progress to `observer`.
+    ///
+    /// The observer is called at the start of each [`Phase`] and whenever an
+    /// item of the phase has been completed. With the `parallel` feature the
+    /// observer may be called from multiple threads at once and the updates of
+    /// different files may interleave.
+    pub fn with_progress(mut self, observer: impl Fn(&Progress) + Send + Sync + 'static) -> Parser {
+        self.monitor = self.monitor.with_observer(Arc::new(observer));
+        self
+    }
+
+    /// Cancel parsing when `token` is cancelled.
+    ///
+    /// The token is checked before each section and page. Parsing then fails
+    /// with [`ErrorKind::Cancelled`], even in lenient mode.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Parser {
+        self.monitor = self.monitor.with_cancellation(token);
+        self
+    }
+
+    /// Reuse previously parsed sections and pages from `cache`.
+    ///
+    /// Sections and pages that haven't changed since they were added to the
+    /// cache are not parsed again. See [`ParseCache`] for details.
+    pub fn with_cache(mut self, cache: ParseCache) -> Parser {
+        self.cache = Some(cache);
+        self
     }
 
     /// Parse a OneNote notebook.
@@ -53,48 +135,58 @@ impl Parser {
     /// The `path` argument must point to a `.onetoc2` file. This will parse the
     /// table of contents of the notebook as well as all contained
     /// sections from the folder that the table of contents file is in.
+    /// Entries whose files don't exist are returned as
+    /// [`SectionEntry::Missing`].
     ///
     /// Returns [`ErrorKind::NotATocFile`] if the file is not a notebook table of
     /// contents.
     pub fn parse_notebook(&self, path: &Path) -> Result<Notebook> {
-        let file = File::open(path)?;
-        let data = Parser::read(file)?;
-        let packaging = OneStorePackaging::parse(&mut Reader::new(data.as_slice()))?;
-        let store = parse_store(&packaging)?;
+        self.parse_notebook_from(&LocalSource, path)
+    }
 
-        if store.schema_guid() != guid!("E4DBFD38-E5C7-408B-A8A1-0E7B421E1F5F") {
-            return Err(ErrorKind::NotATocFile {
-                file: path.to_string_lossy().to_string(),
-            }
-            .into());
+    /// Parse a OneNote package.
+    ///
+    /// The `path` argument must point to a `.onepkg` file as created by the
+    /// OneNote desktop client's "Export as OneNote Package" function. The
+    /// package is extracted in memory and the notebook is parsed from the
+    /// `.onetoc2` file at the package's root.
+    ///
+    /// Returns [`ErrorKind::MalformedCabinetData`] if the package can't be
+    /// extracted and [`ErrorKind::TocFileMissing`] if it doesn't contain a
+    /// notebook table of contents.
+    pub fn parse_onepkg(&self, path: &Path) -> Result<Notebook> {
+        let data = LocalSource.read(path, self.limits.max_input_size())?;
+
+        self.parse_onepkg_buffer(&data, path)
+    }
+
+    /// Parse a OneNote package buffer.
+    ///
+    /// The `data` argument must contain a `.onepkg` file (see
+    /// [`Parser::parse_onepkg`]). The `file_name` is used in error messages.
+    pub fn parse_onepkg_buffer(&self, data: &[u8], file_name: &Path) -> Result<Notebook> {
+        self.limits
+            .check_input_size(data.len())
+            .map_err(|err| err.with_file(file_name))?;
+
+        let budget = Budget::new(self.limits);
+        let mut source = MemorySource::new();
+        for file in cab::extract(data, &budget).map_err(|err| err.with_file(file_name))? {
+            let path: PathBuf = file.name.split('\\').collect();
+            source.insert(path, file.data);
         }
 
-        let base_dir = path.parent().ok_or_else(|| ErrorKind::InvalidPath {
-            message: "path has no parent directory".into(),
-        })?;
-        let (entries, color) = notebook::parse_toc(store.data_root())?;
-        let entries = entries
-            .iter()
-            .map(|name| resolve_entry_path(base_dir, name))
-            .collect::<Result<Vec<_>>>()?;
-        let sections = entries
-            .into_iter()
-            .filter(|p| p.exists())
-            .filter(|p| !p.ends_with("OneNote_RecycleBin"))
-            .map(|path| {
-                if path.is_file() {
-                    self.parse_section(&path).map(SectionEntry::Section)
-                } else {
-                    self.parse_section_group(&path)
-                        .map(SectionEntry::SectionGroup)
-                }
-            })
-            .collect::<Result<_>>()?;
+        let toc = source
+            .paths()
+            .filter(|path| path.extension() == Some(OsStr::new("onetoc2")))
+            .filter(|path| !path.starts_with("OneNote_RecycleBin"))
+            .min_by_key(|path| path.components().count())
+            .map(|path| path.to_path_buf())
+            .ok_or_else(|| ErrorKind::TocFileMissing {
+                dir: file_name.to_string_lossy().into_owned(),
+            })?;
 
-        Ok(Notebook {
-            entries: sections,
-            color,
-        })
+        self.parse_notebook_from(&source, &toc)
     }
 
     /// Parse a OneNote section buffer.
@@ -105,17 +197,66 @@ impl Parser {
     /// Returns [`ErrorKind::NotASectionFile`] if the buffer does not contain a
     /// section file.
     pub fn parse_section_buffer(&self, data: &[u8], file_name: &Path) -> Result<Section> {
-        let packaging = OneStorePackaging::parse(&mut Reader::new(data))?;
-        let store = parse_store(&packaging)?;
+        self.parse_section_data(
+            data,
+            None,
+            file_name,
+            file_name.to_string_lossy().into_owned(),
+        )
+        .map_err(|err| err.with_file(file_name))
+    }
 
-        if store.schema_guid() != guid!("1F937CB4-B26F-445F-B9F8-17E20160E461") {
-            return Err(ErrorKind::NotASectionFile {
-                file: file_name.to_string_lossy().into_owned(),
-            }
-            .into());
-        }
+    /// Read a OneNote section buffer for applying incremental updates.
+    ///
+    /// The `data` argument must contain a OneNote section. The `file_name` is
+    /// used to populate section metadata and error messages. See
+    /// [`SectionPackage`] for details.
+    pub fn read_section_package(&self, data: &[u8], file_name: &Path) -> Result<SectionPackage> {
+        let packaging = self
+            .parse_packaging(data, None, file_name)
+            .map_err(|err| err.with_file(file_name))?;
+
+        Ok(SectionPackage {
+            packaging,
+            path: file_name.to_path_buf(),
+            updates: 0,
+        })
+    }
 
-        section::parse_section(store, file_name.to_string_lossy().into_owned())
+    /// Apply an incremental update to a section package.
+    ///
+    /// The `update` argument must contain a FSSHTTPB data element package,
+    /// e.g. extracted from a captured file synchronization response. Data
+    /// elements of the update replace data elements with the same ID and the
+    /// update's storage index entries are added to the section's storage index.
+    ///
+    /// The package is left unchanged if the update fails to parse.
+    pub fn apply_section_update(&self, package: &mut SectionPackage, update: &[u8]) -> Result<()> {
+        self.limits
+            .check_input_size(update.len())
+            .map_err(|err| err.with_file(&package.path))?;
+
+        let budget = Budget::new(self.limits);
+        let mut reader = Reader::with_budget(update, &budget);
+        package
+            .packaging
+            .apply_update(&mut reader)
+            .map_err(|err| err.with_offset(reader.offset()))
+            .map_err(|err| err.with_file(&package.path))?;
+        package.updates += 1;
+
+        Ok(())
+    }
+
+    /// Parse the section of a section package.
+    ///
+    /// Returns [`ErrorKind::NotASectionFile`] if the package does not contain a
+    /// section.
+    pub fn parse_section_package(&self, package: &SectionPackage) -> Result<Section> {
+        let file_name = package.path.to_string_lossy().into_owned();
+
+        self.parse_section_packaging(&package.packaging, &package.path, file_name)
+            .map_err(|err| err.with_file(&package.path))
     }
 
     /// Parse a OneNote section file.
@@ -126,11 +267,304 @@ impl Parser {
     /// Returns [`ErrorKind::NotASectionFile`] if the file does not contain a
     /// section.
     pub fn parse_section(&self, path: &Path) -> Result<Section> {
-        let file = File::open(path)?;
-        let data = Parser::read(file)?;
-        let packaging = OneStorePackaging::parse(&mut Reader::new(data.as_slice()))?;
+        self.parse_section_from(&LocalSource, path)
+    }
+
+    /// Parse a OneNote notebook from a file source.
+    ///
+    /// This works like [`Parser::parse_notebook`] but reads the table of
+    /// contents and all section files from the given source, e.g. an in-memory
+    /// [`MemorySource`] or a ZIP archive. The `path` argument must point to a
+    /// `.onetoc2` file within the source.
+    ///
+    /// Returns [`ErrorKind::NotATocFile`] if the file is not a notebook table of
+    /// contents.
+    pub fn parse_notebook_from(&self, source: &dyn Source, path: &Path) -> Result<Notebook> {
+        self.parse_notebook_in(source, path, 0)
+    }
+
+    /// Parse a notebook or section group at the given nesting depth.
+    fn parse_notebook_in(
+        &self,
+        source: &dyn Source,
+        path: &Path,
+        depth: usize,
+    ) -> Result<Notebook> {
+        let ctx = Context::new(self.lenient, self.limits);
+        let toc = self
+            .parse_toc_from(source, path, &ctx)
+            .map_err(|err| err.with_file(path))?;
+
+        let base_dir = path.parent().ok_or_else(|| ErrorKind::InvalidPath {
+            message: "path has no parent directory".into(),
+        })?;
+
+        let sections = self.parse_entries(source, path, base_dir, &toc.entries, depth, &ctx)?;
+        let orphaned_sections = find_orphaned_sections(source, base_dir, &toc)?;
+
+        Ok(Notebook {
+            entries: sections,
+            color: toc.color,
+            orphaned_sections,
+            diagnostics: ctx.take_diagnostics(),
+        })
+    }
+
+    fn parse_toc_from(&self, source: &dyn Source, path: &Path, ctx: &Context) -> Result<Toc> {
+        let data = source.read(path, self.limits.max_input_size())?;
+        self.limits.check_input_size(data.len())?;
+
+        let budget = Budget::new(self.limits);
+        let mut reader = Reader::with_budget(data.as_slice(), &budget);
+        let packaging = OneStorePackaging::parse(&mut reader)
+            .map_err(|err| err.with_offset(reader.offset()))?;
         let store = parse_store(&packaging)?;
 
+        if store.schema_guid() != guid!("E4DBFD38-E5C7-408B-A8A1-0E7B421E1F5F") {
+            return Err(ErrorKind::NotATocFile {
+                file: path.to_string_lossy().to_string(),
+            }
+            .into());
+        }
+
+        notebook::parse_toc(store.data_root(), ctx)
+    }
+
+    /// Parse the sections and section groups of a table of contents.
+    #[cfg(not(feature = "parallel"))]
+    fn parse_entries(
+        &self,
+        source: &dyn Source,
+        toc: &Path,
+        base_dir: &Path,
+        entries: &[TocEntry],
+        depth: usize,
+        ctx: &Context,
+    ) -> Result<Vec<SectionEntry>> {
+        let progress = self.monitor.counter(Phase::Sections, toc, entries.len());
+
+        let mut sections = vec![];
+        for toc_entry in entries {
+            progress.check()?;
+
+            let entry = ctx.recover(
+                || entry_segment(toc_entry),
+                || {
+                    self.read_entry(source, base_dir, toc_entry, depth)?
+                        .map(|entry| self.parse_toc_entry(entry))
+                        .transpose()
+                },
+            )?;
+            progress.complete();
+
+            sections.extend(entry.flatten());
+        }
+
+        Ok(sections)
+    }
+
+    /// Parse the sections and section groups of a table of contents.
+    ///
+    /// The section files are read on the current thread as the source doesn't
+    /// have to be thread-safe. They are then parsed on the thread pool while
+    /// the remaining entries are read. The results are collected in the
+    /// order of the table of contents.
+    #[cfg(feature = "parallel")]
+    fn parse_entries(
+        &self,
+        source: &dyn Source,
+        toc: &Path,
+        base_dir: &Path,
+        entries: &[TocEntry],
+        depth: usize,
+        ctx: &Context,
+    ) -> Result<Vec<SectionEntry>> {
+        let progress = self.monitor.counter(Phase::Sections, toc, entries.len());
+
+        let mut results: Vec<Result<Option<SectionEntry>>> =
+            entries.iter().map(|_| Ok(None)).collect();
+
+        rayon::in_place_scope(|scope| -> Result<()> {
+            let progress = &progress;
+
+            for (toc_entry, result) in entries.iter().zip(results.iter_mut()) {
+                progress.check()?;
+
+                match self.read_entry(source, base_dir, toc_entry, depth) {
+                    Ok(Some(entry @ ReadEntry::Section { .. })) => {
+                        scope.spawn(move |_| {
+                            *result = progress
+                                .check()
+                                .and_then(|_| self.parse_toc_entry(entry).map(Some));
+                            progress.complete();
+                        });
+                    }
+                    entry => {
+                        *result = entry.and_then(|entry| {
+                            entry.map(|entry| self.parse_toc_entry(entry)).transpose()
+                        });
+                        progress.complete();
+                    }
+                }
+            }
+
+            Ok(())
+        })?;
+
+        let mut sections = vec![];
+        for (toc_entry, result) in entries.iter().zip(results) {
+            let entry = ctx.recover(|| entry_segment(toc_entry), || result)?;
+
+            sections.extend(entry.flatten());
+        }
+
+        Ok(sections)
+    }
+
+    /// Read a table of contents entry.
+    ///
+    /// Section files are only read, section groups are parsed completely.
+    /// The recycle bin is skipped.
+    fn read_entry(
+        &self,
+        source: &dyn Source,
+        base_dir: &Path,
+        entry: &TocEntry,
+        depth: usize,
+    ) -> Result<Option<ReadEntry>> {
+        let path = resolve_entry_path(source, base_dir, entry.file_name())?;
+        if path.ends_with("OneNote_RecycleBin") {
+            return Ok(None);
+        }
+
+        if !source.exists(&path) {
+            return Ok(Some(ReadEntry::Missing(entry.clone())));
+        }
+
+        if source.is_file(&path) {
+            let data = source
+                .read_buffer(&path, self.limits.max_input_size())
+                .map_err(|err| err.with_file(&path))?;
+
+            Ok(Some(ReadEntry::Section {
+                path,
+                data,
+                entry: entry.clone(),
+            }))
+        } else {
+            self.parse_section_group_from(source, &path, entry, depth + 1)
+                .map(|group| Some(ReadEntry::SectionGroup(group)))
+        }
+    }
+
+    fn parse_toc_entry(&self, entry: ReadEntry) -> Result<SectionEntry> {
+        match entry {
+            ReadEntry::Section { path, data, entry } => {
+                let mut section = self.parse_section_file(&data, &path)?;
+                section.toc_entry = Some(entry);
+
+                Ok(SectionEntry::Section(section))
+            }
+            ReadEntry::SectionGroup(group) => Ok(SectionEntry::SectionGroup(group)),
+            ReadEntry::Missing(entry) => Ok(SectionEntry::Missing(entry)),
+        }
+    }
+
+    /// Parse a OneNote section file from a file source.
+    ///
+    /// This works like [`Parser::parse_section`] but reads the section file
+    /// from the given source.
+    ///
+    /// Returns [`ErrorKind::NotASectionFile`] if the file does not contain a
+    /// section.
+    pub fn parse_section_from(&self, source: &dyn Source, path: &Path) -> Result<Section> {
+        let data = source
+            .read_buffer(path, self.limits.max_input_size())
+            .map_err(|err| err.with_file(path))?;
+
+        self.parse_section_file(&data, path)
+    }
+
+    fn parse_section_file(&self, data: &FileBuffer, path: &Path) -> Result<Section> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| ErrorKind::InvalidPath {
+                message: "path has no file name".into(),
+            })?
+            .to_string_lossy()
+            .to_string();
+
+        self.parse_section_data(data, data.shared(), path, file_name)
+            .map_err(|err| err.with_file(path))
+    }
+
+    /// Parse a section from its data.
+    ///
+    /// If the data is part of a `shared` buffer, binary data is shared with
+    /// the buffer instead of being copied.
+    fn parse_section_data(
+        &self,
+        data: &[u8],
+        shared: Option<&Bytes>,
+        path: &Path,
+        file_name: String,
+    ) -> Result<Section> {
+        let packaging = self.parse_packaging(data, shared, path)?;
+
+        self.parse_section_packaging(&packaging, path, file_name)
+    }
+
+    /// Parse the FSSHTTPB packaging of a file.
+    fn parse_packaging(
+        &self,
+        data: &[u8],
+        shared: Option<&Bytes>,
+        path: &Path,
+    ) -> Result<OneStorePackaging> {
+        self.limits.check_input_size(data.len())?;
+        self.monitor.check()?;
+
+        let budget = Budget::new(self.limits);
+        let mut reader = Reader::with_budget(data, &budget);
+        if let Some(buffer) = shared {
+            reader = reader.sharing(buffer);
+        }
+
+        self.monitor.report(Phase::Packaging, path, 0, data.len());
+        let packaging = OneStorePackaging::parse(&mut reader)
+            .map_err(|err| err.with_offset(reader.offset()))?;
+        self.monitor
+            .report(Phase::Packaging, path, data.len(), data.len());
+
+        Ok(packaging)
+    }
+
+    /// Parse a section from its FSSHTTPB packaging.
+    fn parse_section_packaging(
+        &self,
+        packaging: &OneStorePackaging,
+        path: &Path,
+        file_name: String,
+    ) -> Result<Section> {
+        let version = match &self.cache {
+            Some(_) => Some(StoreVersion::parse(packaging)?),
+            None => None,
+        };
+        let previous = self.cache.as_ref().and_then(|cache| cache.section(path));
+
+        let pages = match (&version, &previous) {
+            (Some(version), Some(previous)) if version.is_unchanged(&previous.version) => {
+                return Ok(previous.section.clone());
+            }
+            (Some(version), Some(previous)) => PageCache::new(previous.unchanged_pages(version)),
+            (Some(_), None) => PageCache::new(Default::default()),
+            (None, _) => PageCache::disabled(),
+        };
+
+        let store = parse_store_with(packaging, &pages.reusable_spaces(), |completed, total| {
+            self.monitor.report(Phase::Store, path, completed, total)
+        })?;
+
         if store.schema_guid() != guid!("1F937CB4-B26F-445F-B9F8-17E20160E461") {
             return Err(ErrorKind::NotASectionFile {
                 file: path.to_string_lossy().to_string(),
@@ -138,18 +572,31 @@ impl Parser {
             .into());
         }
 
-        section::parse_section(
-            store,
-            path.file_name()
-                .ok_or_else(|| ErrorKind::InvalidPath {
-                    message: "path has no file name".into(),
-                })?
-                .to_string_lossy()
-                .to_string(),
-        )
+        let ctx = Context::new(self.lenient, self.limits);
+        let progress = |total| self.monitor.counter(Phase::Pages, path, total);
+        let section = section::parse_section(store, file_name, progress, &pages, &ctx)?;
+
+        if let (Some(cache), Some(version)) = (&self.cache, version) {
+            let cached = CachedSection {
+                version,
+                section: section.clone(),
+                pages: pages.into_pages(),
+            };
+            cache.insert(path, cached);
+        }
+
+        Ok(section)
     }
 
-    fn parse_section_group(&self, path: &Path) -> Result<SectionGroup> {
+    fn parse_section_group_from(
+        &self,
+        source: &dyn Source,
+        path: &Path,
+        toc_entry: &TocEntry,
+        depth: usize,
+    ) -> Result<SectionGroup> {
+        self.limits.check_depth(depth)?;
+
         let display_name = path
             .file_name()
             .ok_or_else(|| ErrorKind::InvalidPath {
@@ -158,20 +605,22 @@ impl Parser {
             .to_string_lossy()
             .to_string();
 
-        for entry in path.read_dir()? {
-            let entry = entry?;
+        for entry in source.read_dir(path)? {
             let is_toc = entry
-                .path()
                 .extension()
                 .map(|ext| ext == OsStr::new("onetoc2"))
                 .unwrap_or_default();
 
             if is_toc {
                 return self
-                    .parse_notebook(&entry.path())
+                    .parse_notebook_in(source, &entry, depth)
                     .map(|group| SectionGroup {
                         display_name,
                         entries: group.entries,
+                        color: group.color,
+                        toc_entry: toc_entry.clone(),
+                        orphaned_sections: group.orphaned_sections,
+                        diagnostics: group.diagnostics,
                     });
             }
         }
@@ -181,19 +630,49 @@ impl Parser {
         }
         .into())
     }
+}
 
-    fn read(file: File) -> Result<Vec<u8>> {
-        let size = file.metadata()?.len();
-        let mut data = Vec::with_capacity(size as usize);
+/// A table of contents entry that has been read from the source.
+enum ReadEntry {
+    Section {
+        path: PathBuf,
+        data: FileBuffer,
+        entry: TocEntry,
+    },
+    SectionGroup(SectionGroup),
+    Missing(TocEntry),
+}
 
-        let mut buf = BufReader::new(file);
-        buf.read_to_end(&mut data)?;
+/// Describe the location of a table of contents entry.
+fn entry_segment(entry: &TocEntry) -> String {
+    let name = entry.file_name();
 
-        Ok(data)
+    match name.strip_suffix(".one") {
+        Some(name) => format!("section {name:?}"),
+        None => format!("section group {name:?}"),
     }
 }
 
-fn resolve_entry_path(base_dir: &Path, entry: &str) -> Result<PathBuf> {
+/// Find the section files in `dir` that the table of contents doesn't list.
+fn find_orphaned_sections(source: &dyn Source, dir: &Path, toc: &Toc) -> Result<Vec<PathBuf>> {
+    let files = if dir.as_os_str().is_empty() {
+        source.read_dir(Path::new("."))
+    } else {
+        source.read_dir(dir)
+    };
+
+    let mut sections: Vec<_> = files
+        .map_err(|err| err.with_file(dir))?
+        .into_iter()
+        .filter(|path| path.extension() == Some(OsStr::new("one")))
+        .filter(|path| source.is_file(path) && !toc.lists(path))
+        .collect();
+    sections.sort();
+
+    Ok(sections)
+}
+
+fn resolve_entry_path(source: &dyn Source, base_dir: &Path, entry: &str) -> Result<PathBuf> {
     let entry_path = Path::new(entry);
     if entry_path.is_absolute() {
         return Err(ErrorKind::InvalidPath {
@@ -237,17 +716,18 @@ fn resolve_entry_path(base_dir: &Path, entry: &str) -> Result<PathBuf> {
     }
 
     let candidate = base_dir.join(&sanitized);
-    if candidate.exists() {
-        let base_canon = base_dir
-            .canonicalize()
+    if source.exists(&candidate) {
+        let base_canon = source
+            .canonicalize(base_dir)
             .map_err(|err| ErrorKind::InvalidPath {
                 message: format!("failed to resolve base directory: {err}").into(),
             })?;
-        let candidate_canon = candidate
-            .canonicalize()
-            .map_err(|err| ErrorKind::InvalidPath {
-                message: format!("failed to resolve entry path: {err}").into(),
-            })?;
+        let candidate_canon =
+            source
+                .canonicalize(&candidate)
+                .map_err(|err| ErrorKind::InvalidPath {
+                    message: format!("failed to resolve entry path: {err}").into(),
+                })?;
         if !candidate_canon.starts_with(&base_canon) {
             return Err(ErrorKind::InvalidPath {
                 message: "section entry escapes base directory".into(),
@@ -267,16 +747,493 @@ impl Default for Parser {
 
 #[cfg(test)]
 mod tests {
-    use super::resolve_entry_path;
+    use super::{Parser, resolve_entry_path};
+    use crate::cab::tests::build_cabinet;
+    use crate::cache::ParseCache;
+    use crate::errors::{ErrorKind, Limit};
+    use crate::limits::ParseLimits;
+    use crate::onenote::page::Page;
+    use crate::onenote::page_tree::PageTreeNode;
+    use crate::onenote::section::SectionEntry;
+    use crate::onenote::source::{LocalSource, MemorySource};
+    use crate::progress::{CancellationToken, Phase};
+    use crate::test_support::{
+        SpaceBuilder, StoreBuilder, section_root_with_series, subpage_space,
+    };
+    use std::fs;
     use std::path::Path;
+    use std::ptr;
+    use std::sync::{Arc, Mutex};
     use tempfile::tempdir;
 
+    fn sample_package(compress: bool) -> Vec<u8> {
+        let names = [
+            "Open Notebook.onetoc2",
+            "New Section 1 2.one",
+            "New Section 2.one",
+            "New Section 3.one",
+        ];
+        let files: Vec<_> = names
+            .iter()
+            .map(|name| {
+                let data = fs::read(Path::new("tests/samples/non-legacy").join(name)).unwrap();
+                (*name, data)
+            })
+            .collect();
+        let files: Vec<_> = files
+            .iter()
+            .map(|(name, data)| (*name, data.as_slice()))
+            .collect();
+
+        build_cabinet(&files, compress)
+    }
+
+    #[test]
+    fn test_parse_onepkg_buffer() {
+        let parser = Parser::new();
+        let expected = parser
+            .parse_notebook(Path::new("tests/samples/non-legacy/Open Notebook.onetoc2"))
+            .unwrap();
+
+        for compress in [false, true] {
+            let notebook = parser
+                .parse_onepkg_buffer(&sample_package(compress), Path::new("Notebook.onepkg"))
+                .unwrap();
+
+            assert_eq!(format!("{notebook:?}"), format!("{expected:?}"));
+        }
+    }
+
+    #[test]
+    fn test_parse_onepkg_buffer_without_toc() {
+        let package = build_cabinet(&[("New Section.one", b"")], false);
+        let err = Parser::new()
+            .parse_onepkg_buffer(&package, Path::new("Notebook.onepkg"))
+            .unwrap_err();
+
+        assert!(format!("{err}").contains("Table of contents file is missing"));
+    }
+
+    fn sample_source(prefix: &Path) -> MemorySource {
+        fn add_dir(source: &mut MemorySource, dir: &Path, prefix: &Path) {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                let target = prefix.join(path.file_name().unwrap());
+                if path.is_dir() {
+                    add_dir(source, &path, &target);
+                } else {
+                    source.insert(target, fs::read(&path).unwrap());
+                }
+            }
+        }
+
+        let mut source = MemorySource::new();
+        add_dir(&mut source, Path::new("tests/samples"), prefix);
+
+        source
+    }
+
+    #[test]
+    fn test_parse_notebook_from_memory() {
+        let parser = Parser::new();
+        let expected = parser
+            .parse_notebook(Path::new("tests/samples/Open Notebook.onetoc2"))
+            .unwrap();
+
+        let source = sample_source(Path::new("Notebook"));
+        let notebook = parser
+            .parse_notebook_from(&source, Path::new("Notebook/Open Notebook.onetoc2"))
+            .unwrap();
+
+        assert_eq!(
+            format!("{:?}", notebook.entries()),
+            format!("{:?}", expected.entries())
+        );
+        assert_eq!(
+            notebook.orphaned_sections(),
+            [Path::new("Notebook/Schnelle Notizen.one")]
+        );
+        assert_eq!(
+            expected.orphaned_sections(),
+            [Path::new("tests/samples/Schnelle Notizen.one")]
+        );
+    }
+
+    #[test]
+    fn test_parse_notebook_toc() {
+        let notebook = Parser::new()
+            .parse_notebook(Path::new("tests/samples/non-legacy/Open Notebook.onetoc2"))
+            .unwrap();
+
+        let entries: Vec<_> = notebook
+            .entries()
+            .iter()
+            .map(|entry| match entry {
+                SectionEntry::Section(section) => {
+                    let entry = section.toc_entry().unwrap();
+                    (entry.file_name(), entry.ordering_id(), false)
+                }
+                SectionEntry::SectionGroup(group) => {
+                    let entry = group.toc_entry();
+                    (entry.file_name(), entry.ordering_id(), false)
+                }
+                SectionEntry::Missing(entry) => (entry.file_name(), entry.ordering_id(), true),
+            })
+            .collect();
+
+        assert_eq!(
+            entries,
+            [
+                ("New Section 1 2.one", 0, false),
+                ("New Section 2.one", 2, false),
+                ("New Section Group", 4, true),
+                ("New Section 3.one", 3, false),
+            ]
+        );
+        assert!(notebook.orphaned_sections().is_empty());
+
+        // "New Section 1 2.one" is listed twice
+        let diagnostics = notebook.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location(), ["table of contents"]);
+        assert!(matches!(
+            diagnostics[0].kind(),
+            ErrorKind::MalformedOneNoteData(message)
+                if message.contains("New Section 1 2.one") && message.contains("0, 1")
+        ));
+
+        let SectionEntry::Section(section) = &notebook.entries()[0] else {
+            panic!("expected a section");
+        };
+        let toc_color = section.toc_entry().unwrap().color().unwrap();
+        assert_eq!(
+            (toc_color.r(), toc_color.g(), toc_color.b()),
+            (138, 168, 228)
+        );
+    }
+
+    #[test]
+    fn test_parse_section_group_toc() {
+        let mut source = MemorySource::new();
+        for name in ["Open Notebook.onetoc2", "New Section 3.one"] {
+            let data = fs::read(Path::new("tests/samples/non-legacy").join(name)).unwrap();
+            source.insert(name, data);
+        }
+        for name in ["Open Notebook.onetoc2", "New Section 1.one"] {
+            let data = fs::read(Path::new("tests/samples/New Section Group").join(name)).unwrap();
+            source.insert(Path::new("New Section Group").join(name), data);
+        }
+        source.insert("New Section Group/Unlisted.one", vec![]);
+
+        let notebook = Parser::new()
+            .parse_notebook_from(&source, Path::new("Open Notebook.onetoc2"))
+            .unwrap();
+
+        let SectionEntry::SectionGroup(group) = &notebook.entries()[2] else {
+            panic!("expected a section group");
+        };
+        assert_eq!(group.display_name(), "New Section Group");
+        assert_eq!(group.toc_entry().ordering_id(), 4);
+        assert_eq!(
+            group.orphaned_sections(),
+            [Path::new("New Section Group/Unlisted.one")]
+        );
+        assert!(matches!(
+            &group.entries()[1],
+            SectionEntry::Missing(entry) if entry.file_name() == "New Section 2.one"
+        ));
+    }
+
+    #[test]
+    fn test_error_context() {
+        let path = Path::new("tests/samples/non-legacy/New Section 2.one");
+        let data = fs::read(path).unwrap();
+
+        let err = Parser::new()
+            .parse_section_buffer(&data[..data.len() / 2], path)
+            .unwrap_err();
+
+        assert_eq!(err.file(), Some(path));
+        assert!(err.offset().is_some());
+        assert!(err.data_element().is_some());
+        assert!(format!("{err}").contains("New Section 2.one at offset 0x"));
+    }
+
+    #[test]
+    fn test_parse_section_limits() {
+        let path = Path::new("tests/samples/non-legacy/New Section 2.one");
+        let data = fs::read(path).unwrap();
+
+        let parse = |limits: ParseLimits| {
+            Parser::new()
+                .with_limits(limits)
+                .parse_section_buffer(&data, path)
+        };
+
+        assert!(parse(ParseLimits::unlimited()).is_ok());
+
+        let limits = [
+            (
+                ParseLimits::default().with_max_input_size(100),
+                Limit::InputSize,
+            ),
+            (
+                ParseLimits::default().with_max_objects(10),
+                Limit::ObjectCount,
+            ),
+            (
+                ParseLimits::default().with_max_allocation(16),
+                Limit::Allocation,
+            ),
+            (ParseLimits::default().with_max_depth(0), Limit::Depth),
+        ];
+        for (limits, expected) in limits {
+            let err = parse(limits).unwrap_err();
+            assert!(
+                matches!(err.kind(), ErrorKind::LimitExceeded { limit, .. } if *limit == expected),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_notebook_lenient() {
+        let mut source = MemorySource::new();
+        for name in [
+            "Open Notebook.onetoc2",
+            "New Section 1 2.one",
+            "New Section 3.one",
+        ] {
+            let data = fs::read(Path::new("tests/samples/non-legacy").join(name)).unwrap();
+            source.insert(name, data);
+        }
+        source.insert("New Section 2.one", b"not a section".to_vec());
+
+        let path = Path::new("Open Notebook.onetoc2");
+        let err = Parser::new()
+            .parse_notebook_from(&source, path)
+            .unwrap_err();
+        assert_eq!(err.file(), Some(Path::new("New Section 2.one")));
+        assert_eq!(err.location(), ["section \"New Section 2\""]);
+
+        let notebook = Parser::new()
+            .with_lenient(true)
+            .parse_notebook_from(&source, path)
+            .unwrap();
+        assert_eq!(notebook.entries().len(), 3);
+        assert!(matches!(
+            &notebook.entries()[1],
+            SectionEntry::Missing(entry) if entry.file_name() == "New Section Group"
+        ));
+
+        let diagnostics = notebook.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].location(), ["table of contents"]);
+        assert_eq!(diagnostics[1].location(), ["section \"New Section 2\""]);
+    }
+
+    #[test]
+    fn test_parse_notebook_progress() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let parser = Parser::new().with_progress({
+            let events = events.clone();
+            move |progress| {
+                events.lock().unwrap().push((
+                    progress.phase(),
+                    progress.file().to_path_buf(),
+                    progress.completed(),
+                    progress.total(),
+                ))
+            }
+        });
+
+        let toc = Path::new("tests/samples/non-legacy/Open Notebook.onetoc2");
+        parser.parse_notebook(toc).unwrap();
+
+        let events = events.lock().unwrap();
+        let sections: Vec<_> = events
+            .iter()
+            .filter(|(phase, ..)| *phase == Phase::Sections)
+            .map(|(_, file, completed, total)| {
+                assert_eq!(file, toc);
+                (*completed, *total)
+            })
+            .collect();
+        let (_, total) = sections[0];
+        assert_eq!(sections.len() as u64, total + 1);
+        assert_eq!(sections.last(), Some(&(total, total)));
+
+        let section = Path::new("tests/samples/non-legacy/New Section 2.one");
+        for phase in [Phase::Packaging, Phase::Store, Phase::Pages] {
+            let updates: Vec<_> = events
+                .iter()
+                .filter(|(p, file, ..)| *p == phase && file == section)
+                .collect();
+
+            let (_, _, completed, total) = updates.first().unwrap();
+            assert_eq!(*completed, 0, "{phase:?}");
+            assert!(*total > 0, "{phase:?}");
+            assert!(
+                updates
+                    .iter()
+                    .any(|(_, _, completed, _)| completed == total),
+                "{phase:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_section_page_tree() {
+        fn flatten<'a>(nodes: &[PageTreeNode<'a>], pages: &mut Vec<&'a Page>) {
+            for node in nodes {
+                pages.push(node.page());
+                flatten(node.children(), pages);
+            }
+        }
+
+        let section = Parser::new()
+            .parse_section(Path::new("tests/samples/non-legacy/New Section 1 2.one"))
+            .unwrap();
+
+        let mut pages = vec![];
+        flatten(&section.page_tree(), &mut pages);
+
+        let expected: Vec<_> = section
+            .page_series()
+            .iter()
+            .flat_map(|series| series.pages())
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(pages.len(), expected.len());
+        assert!(pages.iter().zip(expected).all(|(a, b)| ptr::eq(*a, b)));
+    }
+
+    #[test]
+    fn test_section_page_tree_per_series() {
+        let first = [subpage_space(20, 2, 1.0, 0), subpage_space(21, 3, 2.0, 1)];
+        let second = [subpage_space(22, 4, 3.0, 1), subpage_space(23, 5, 4.0, 0)];
+        let first_ids: Vec<_> = first.iter().map(SpaceBuilder::cell_id).collect();
+        let second_ids: Vec<_> = second.iter().map(SpaceBuilder::cell_id).collect();
+
+        let data = first
+            .into_iter()
+            .chain(second)
+            .fold(
+                StoreBuilder::section(section_root_with_series(1, &[&first_ids, &second_ids])),
+                StoreBuilder::space,
+            )
+            .build();
+
+        let section = Parser::new()
+            .parse_section_buffer(&data, Path::new("Synthetic.one"))
+            .unwrap();
+        let tree = section.page_tree();
+
+        // The subpage that starts the second series isn't nested below the
+        // last page of the first series.
+        let roots: Vec<_> = tree
+            .iter()
+            .map(|node| (node.page().height(), node.children().len()))
+            .collect();
+        assert_eq!(roots, [(Some(1.0), 1), (Some(3.0), 0), (Some(4.0), 0)]);
+    }
+
+    #[test]
+    fn test_parse_notebook_cached() {
+        let cache = ParseCache::new();
+        let parser = Parser::new().with_cache(cache.clone());
+        let path = Path::new("tests/samples/non-legacy/Open Notebook.onetoc2");
+
+        let section = Path::new("tests/samples/non-legacy/New Section 2.one");
+
+        let expected = Parser::new().parse_notebook(path).unwrap();
+        let notebook = parser.parse_notebook(path).unwrap();
+        assert_eq!(format!("{notebook:?}"), format!("{expected:?}"));
+        let first = cache.section(section).unwrap();
+
+        let notebook = parser.parse_notebook(path).unwrap();
+        assert_eq!(format!("{notebook:?}"), format!("{expected:?}"));
+        assert!(Arc::ptr_eq(&first, &cache.section(section).unwrap()));
+
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_notebook_cancelled() {
+        let token = CancellationToken::new();
+        let parser = Parser::new()
+            .with_lenient(true)
+            .with_cancellation(token.clone())
+            .with_progress(move |progress| {
+                if progress.phase() == Phase::Pages && progress.completed() > 0 {
+                    token.cancel();
+                }
+            });
+
+        let err = parser
+            .parse_notebook(Path::new("tests/samples/non-legacy/Open Notebook.onetoc2"))
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Cancelled), "{err}");
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn test_parse_notebook_from_zip() {
+        use crate::onenote::source::{Source, ZipSource};
+        use std::io::{Cursor, Write};
+        use zip::write::{SimpleFileOptions, ZipWriter};
+
+        let source = sample_source(Path::new("Notebook"));
+
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        for path in source.paths() {
+            let name = path.to_str().unwrap().replace('\\', "/");
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer
+                .write_all(&source.read(path, u64::MAX).unwrap())
+                .unwrap();
+        }
+        writer
+            .start_file("../outside.one", SimpleFileOptions::default())
+            .unwrap();
+        let archive = writer.finish().unwrap();
+
+        let zip = ZipSource::new(archive).unwrap();
+        assert!(zip.paths().all(|path| path.starts_with("Notebook")));
+
+        let parser = Parser::new();
+        let notebook = parser
+            .parse_notebook_from(&zip, Path::new("Notebook/Open Notebook.onetoc2"))
+            .unwrap();
+        let expected = parser
+            .parse_notebook_from(&source, Path::new("Notebook/Open Notebook.onetoc2"))
+            .unwrap();
+
+        assert_eq!(format!("{notebook:?}"), format!("{expected:?}"));
+    }
+
+    #[test]
+    fn test_resolve_entry_path_in_memory() {
+        let mut source = MemorySource::new();
+        source.insert("Notebook/Section.one", vec![]);
+        source.insert("Other/Secret.one", vec![]);
+
+        let base = Path::new("Notebook");
+        let resolved = resolve_entry_path(&source, base, "Section.one").unwrap();
+        assert_eq!(resolved, Path::new("Notebook/Section.one"));
+
+        let err = resolve_entry_path(&source, base, "../Other/Secret.one").unwrap_err();
+        assert!(format!("{err}").contains("invalid path components"));
+    }
+
     #[test]
     fn test_resolve_entry_path_rejects_traversal() {
         let dir = tempdir().unwrap();
         let base = dir.path();
 
-        let err = resolve_entry_path(base, "../secret.one").unwrap_err();
+        let err = resolve_entry_path(&LocalSource, base, "../secret.one").unwrap_err();
         let err = format!("{err}");
         assert!(err.contains("invalid path components"));
     }
@@ -291,7 +1248,7 @@ mod tests {
         } else {
             "/etc/passwd"
         };
-        let err = resolve_entry_path(base, candidate).unwrap_err();
+        let err = resolve_entry_path(&LocalSource, base, candidate).unwrap_err();
         let err = format!("{err}");
         assert!(err.contains("relative path"));
     }
@@ -301,7 +1258,7 @@ mod tests {
         let dir = tempdir().unwrap();
         let base = dir.path();
 
-        let resolved = resolve_entry_path(base, "Section 1.one").unwrap();
+        let resolved = resolve_entry_path(&LocalSource, base, "Section 1.one").unwrap();
         assert_eq!(resolved, Path::new(base).join("Section 1.one"));
     }
 }
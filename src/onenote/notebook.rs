@@ -1,20 +1,28 @@
-use crate::errors::{ErrorKind, Result};
+use crate::errors::{Diagnostic, ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property_set::toc_container;
-use crate::onenote::section::SectionEntry;
+use crate::onenote::context::Context;
+use crate::onenote::section::{SectionEntry, collect_diagnostics, collect_unknown_jcids};
 use crate::onestore::object_space::ObjectSpace;
 use crate::property::common::Color;
 use itertools::Itertools;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// A OneNote notebook.
 #[derive(Clone, Debug)]
 pub struct Notebook {
     pub(crate) entries: Vec<SectionEntry>,
     pub(crate) color: Option<Color>,
+    pub(crate) orphaned_sections: Vec<PathBuf>,
+    pub(crate) diagnostics: Vec<Diagnostic>,
 }
 
 impl Notebook {
     /// The section entries of this notebook.
+    ///
+    /// The entries are in the order of the notebook's table of contents. Use
+    /// [`TocEntry::ordering_id`] to sort them in the order that OneNote shows.
     pub fn entries(&self) -> &[SectionEntry] {
         &self.entries
     }
@@ -23,59 +31,341 @@ impl Notebook {
     pub fn color(&self) -> Option<Color> {
         self.color
     }
+
+    /// The section files in the notebook's directory that are not listed in
+    /// its table of contents.
+    ///
+    /// OneNote doesn't show these sections. They are not parsed.
+    pub fn orphaned_sections(&self) -> &[PathBuf] {
+        &self.orphaned_sections
+    }
+
+    /// The errors that were skipped when parsing the notebook in lenient mode.
+    ///
+    /// This includes the diagnostics of all sections and section groups within
+    /// the notebook. Use [`Parser::with_lenient`] to enable lenient mode.
+    ///
+    /// Problems that don't prevent parsing, like a file that is listed more
+    /// than once in the table of contents, are reported outside of lenient
+    /// mode too.
+    ///
+    /// [`Parser::with_lenient`]: crate::Parser::with_lenient
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        collect_diagnostics(&self.diagnostics, &self.entries)
+    }
+
+    /// The JCIDs of the content the parser doesn't support in all sections
+    /// of the notebook.
+    ///
+    /// See [`Section::unknown_jcids`].
+    ///
+    /// [`Section::unknown_jcids`]: crate::section::Section::unknown_jcids
+    pub fn unknown_jcids(&self) -> BTreeMap<u32, usize> {
+        collect_unknown_jcids(&self.entries)
+    }
 }
 
-struct TocEntry {
-    entries: Vec<(u32, String)>,
+/// An entry in a notebook's table of contents.
+///
+/// See [\[MS-ONE\] 2.2.15].
+///
+/// [\[MS-ONE\] 2.2.15]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/6c1dd264-850b-4e46-af62-50b4dba49b62
+#[derive(Clone, Debug, PartialEq)]
+pub struct TocEntry {
+    file_name: String,
+    ordering_id: u32,
     color: Option<Color>,
 }
 
-pub(crate) fn parse_toc(space: &ObjectSpace) -> Result<(Vec<String>, Option<Color>)> {
+impl TocEntry {
+    /// The name of the section file or section group directory.
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// The entry's position in the table of contents.
+    ///
+    /// OneNote shows the entries sorted by their ordering ID. The IDs don't
+    /// have to be consecutive.
+    pub fn ordering_id(&self) -> u32 {
+        self.ordering_id
+    }
+
+    /// The color of the entry in the table of contents.
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+}
+
+/// A parsed table of contents.
+pub(crate) struct Toc {
+    pub(crate) entries: Vec<TocEntry>,
+    pub(crate) color: Option<Color>,
+}
+
+impl Toc {
+    /// Whether the table of contents lists the file at `path`.
+    pub(crate) fn lists(&self, path: &Path) -> bool {
+        path.file_name().is_some_and(|name| {
+            self.entries
+                .iter()
+                .any(|entry| name == Path::new(&entry.file_name).as_os_str())
+        })
+    }
+}
+
+/// Parse a notebook's table of contents.
+///
+/// The entries are kept in the order in which they appear in the table of
+/// contents. Containers without a file name are flattened into their parent.
+/// Files that are listed more than once are only returned for their first
+/// entry and reported as a diagnostic that lists all of their entries.
+pub(crate) fn parse_toc(space: &ObjectSpace, ctx: &Context) -> Result<Toc> {
     let content_id = space
         .content_root()
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("notebook has no content root".into()))?;
 
-    let entry = parse_toc_entry(content_id, space)?;
-    let toc = entry
-        .entries
-        .into_iter()
-        .sorted_by_key(|(ordering_id, _)| *ordering_id)
-        .dedup_by(|(_, a), (_, b)| a == b)
-        .map(|(_, name)| name)
-        .collect();
+    let content = space.get_object(content_id).ok_or_else(|| {
+        ErrorKind::MalformedOneNoteData("notebook content root is missing".into())
+    })?;
+
+    let toc = toc_container::parse(content)?;
+    let mut visited = HashSet::from([content_id]);
+    let entries = parse_toc_children(toc.children, space, ctx, &mut visited)?;
 
-    Ok((toc, entry.color))
+    Ok(Toc {
+        entries: remove_duplicates(entries, ctx),
+        color: toc.color,
+    })
 }
 
-fn parse_toc_entry(content_id: ExGuid, space: &ObjectSpace) -> Result<TocEntry> {
+fn parse_toc_children(
+    children: Vec<ExGuid>,
+    space: &ObjectSpace,
+    ctx: &Context,
+    visited: &mut HashSet<ExGuid>,
+) -> Result<Vec<TocEntry>> {
+    let entries = ctx.collect("table of contents entry", children, |content_id| {
+        parse_toc_entry(content_id, space, ctx, visited)
+    })?;
+
+    Ok(entries.into_iter().flatten().collect())
+}
+
+fn parse_toc_entry(
+    content_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+    visited: &mut HashSet<ExGuid>,
+) -> Result<Vec<TocEntry>> {
+    // Each entry may only be referenced once, which also rules out cycles
+    if !visited.insert(content_id) {
+        return Err(ErrorKind::MalformedOneNoteData(
+            "table of contents entry is referenced more than once".into(),
+        )
+        .into());
+    }
+
     let content = space.get_object(content_id).ok_or_else(|| {
-        ErrorKind::MalformedOneNoteData("notebook content root is missing".into())
+        ErrorKind::MalformedOneNoteData("table of contents entry is missing".into())
     })?;
 
     let toc = toc_container::parse(content)?;
 
-    if let Some(name) = toc.filename {
-        let ordering_id = toc
-            .ordering_id
-            .ok_or_else(|| ErrorKind::MalformedOneNoteData("section has no order id".into()))?;
+    let Some(file_name) = toc.filename else {
+        return ctx.nested(|| parse_toc_children(toc.children, space, ctx, visited));
+    };
 
-        Ok(TocEntry {
-            entries: vec![(ordering_id, name)],
-            color: toc.color,
-        })
-    } else {
-        let children = toc
-            .children
-            .into_iter()
-            .map(|content_id| parse_toc_entry(content_id, space))
-            .collect::<Result<Vec<_>>>()?
-            .into_iter()
-            .flat_map(|entry| entry.entries)
+    let ordering_id = toc
+        .ordering_id
+        .ok_or_else(|| ErrorKind::MalformedOneNoteData("section has no order id".into()))?;
+
+    Ok(vec![TocEntry {
+        file_name,
+        ordering_id,
+        color: toc.color,
+    }])
+}
+
+/// Keep the first entry of each file and report files that are listed more
+/// than once.
+fn remove_duplicates(entries: Vec<TocEntry>, ctx: &Context) -> Vec<TocEntry> {
+    let mut unique: Vec<TocEntry> = vec![];
+    let mut duplicates: Vec<(String, Vec<u32>)> = vec![];
+
+    for entry in entries {
+        let Some(first) = unique.iter().find(|e| e.file_name == entry.file_name) else {
+            unique.push(entry);
+            continue;
+        };
+
+        match duplicates
+            .iter_mut()
+            .find(|(name, _)| *name == entry.file_name)
+        {
+            Some((_, ordering_ids)) => ordering_ids.push(entry.ordering_id),
+            None => duplicates.push((entry.file_name, vec![first.ordering_id, entry.ordering_id])),
+        }
+    }
+
+    for (file_name, ordering_ids) in duplicates {
+        let ordering_ids = ordering_ids.iter().map(u32::to_string).join(", ");
+
+        ctx.report(
+            "table of contents".to_string(),
+            ErrorKind::MalformedOneNoteData(
+                format!("{file_name} is listed more than once (ordering IDs {ordering_ids})")
+                    .into(),
+            )
+            .into(),
+        );
+    }
+
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_toc;
+    use crate::errors::{ErrorKind, Limit};
+    use crate::fsshttpb::writer::Value;
+    use crate::limits::ParseLimits;
+    use crate::one::property::PropertyType;
+    use crate::one::property_set::PropertySetId;
+    use crate::onenote::context::Context;
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, id, with_space};
+
+    fn toc_container(value: u32, children: &[u32]) -> ObjectBuilder {
+        let children: Vec<_> = children.iter().map(|child| id(*child)).collect();
+
+        ObjectBuilder::new(id(value), PropertySetId::TocContainer)
+            .object_refs(PropertyType::TocChildren, &children)
+    }
+
+    fn toc_entry(value: u32, file_name: &str, ordering_id: u32) -> ObjectBuilder {
+        toc_container(value, &[])
+            .string(PropertyType::FolderChildFilename, file_name)
+            .prop(
+                PropertyType::NotebookElementOrderingId,
+                Value::U32(ordering_id),
+            )
+    }
+
+    #[test]
+    fn test_parse_toc_order() {
+        let space = SpaceBuilder::new(1)
+            .content_root(toc_container(1, &[2, 3, 4]))
+            .object(toc_entry(2, "B.one", 1))
+            .object(toc_entry(3, "A.one", 0))
+            .object(toc_entry(4, "C.one", 2));
+        let ctx = Context::new(false, ParseLimits::default());
+
+        let toc = with_space(space, |space| parse_toc(space, &ctx).unwrap());
+
+        let entries: Vec<_> = toc
+            .entries
+            .iter()
+            .map(|entry| (entry.file_name(), entry.ordering_id()))
             .collect();
+        assert_eq!(entries, [("B.one", 1), ("A.one", 0), ("C.one", 2)]);
+        assert!(ctx.take_diagnostics().is_empty());
+    }
 
-        Ok(TocEntry {
-            entries: children,
-            color: toc.color,
-        })
+    #[test]
+    fn test_parse_toc_nested() {
+        let space = SpaceBuilder::new(1)
+            .content_root(toc_container(1, &[2, 3]))
+            .object(toc_entry(2, "A.one", 0))
+            .object(toc_container(3, &[4, 5]))
+            .object(toc_entry(4, "B.one", 1))
+            .object(toc_entry(5, "C.one", 2));
+        let ctx = Context::new(false, ParseLimits::default());
+
+        let toc = with_space(space, |space| parse_toc(space, &ctx).unwrap());
+
+        let names: Vec<_> = toc.entries.iter().map(|entry| entry.file_name()).collect();
+        assert_eq!(names, ["A.one", "B.one", "C.one"]);
+        assert!(ctx.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_parse_toc_duplicates() {
+        let space = SpaceBuilder::new(1)
+            .content_root(toc_container(1, &[2, 3, 4, 5]))
+            .object(toc_entry(2, "A.one", 3))
+            .object(toc_entry(3, "B.one", 1))
+            .object(toc_entry(4, "A.one", 0))
+            .object(toc_entry(5, "A.one", 2));
+        let ctx = Context::new(false, ParseLimits::default());
+
+        let toc = with_space(space, |space| parse_toc(space, &ctx).unwrap());
+
+        let entries: Vec<_> = toc
+            .entries
+            .iter()
+            .map(|entry| (entry.file_name(), entry.ordering_id()))
+            .collect();
+        assert_eq!(entries, [("A.one", 3), ("B.one", 1)]);
+
+        let diagnostics = ctx.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location(), ["table of contents"]);
+        assert!(matches!(
+            diagnostics[0].kind(),
+            ErrorKind::MalformedOneNoteData(message)
+                if message.contains("A.one") && message.contains("3, 0, 2")
+        ));
+    }
+
+    #[test]
+    fn test_parse_toc_cycle() {
+        let space = SpaceBuilder::new(1)
+            .content_root(toc_container(1, &[2]))
+            .object(toc_container(2, &[1]));
+        let ctx = Context::new(false, ParseLimits::default());
+
+        let err = with_space(space, |space| parse_toc(space, &ctx).err().unwrap());
+
+        assert!(matches!(err.kind(), ErrorKind::MalformedOneNoteData(_)));
+    }
+
+    #[test]
+    fn test_parse_toc_cycle_lenient() {
+        let space = SpaceBuilder::new(1)
+            .content_root(toc_container(1, &[2, 3]))
+            .object(toc_container(2, &[1]))
+            .object(toc_entry(3, "A.one", 0));
+        let ctx = Context::new(true, ParseLimits::default());
+
+        let toc = with_space(space, |space| parse_toc(space, &ctx).unwrap());
+
+        assert_eq!(toc.entries.len(), 1);
+        assert_eq!(toc.entries[0].file_name(), "A.one");
+
+        let diagnostics = ctx.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].location(),
+            ["table of contents entry 0", "table of contents entry 0"]
+        );
+    }
+
+    #[test]
+    fn test_parse_toc_depth() {
+        let space = (2..=5).fold(
+            SpaceBuilder::new(1).content_root(toc_container(1, &[2])),
+            |space, value| space.object(toc_container(value, &[value + 1])),
+        );
+        let ctx = Context::new(false, ParseLimits::default().with_max_depth(3));
+
+        let err = with_space(space, |space| parse_toc(space, &ctx).err().unwrap());
+
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::Depth,
+                ..
+            }
+        ));
     }
 }
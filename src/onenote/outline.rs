@@ -3,6 +3,7 @@ use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property::layout_alignment::LayoutAlignment;
 use crate::one::property_set::{PropertySetId, outline_element_node, outline_group, outline_node};
 use crate::onenote::content::{Content, parse_content};
+use crate::onenote::context::Context;
 use crate::onenote::list::{List, parse_list};
 use crate::onestore::object_space::ObjectSpace;
 
@@ -256,17 +257,19 @@ impl OutlineElement {
     }
 }
 
-pub(crate) fn parse_outline(outline_id: ExGuid, space: &ObjectSpace) -> Result<Outline> {
+pub(crate) fn parse_outline(
+    outline_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+) -> Result<Outline> {
     let outline_object = space
         .get_object(outline_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("outline node is missing".into()))?;
     let data = outline_node::parse(outline_object)?;
 
-    let items = data
-        .children
-        .into_iter()
-        .map(|item_id| parse_outline_item(item_id, space))
-        .collect::<Result<_>>()?;
+    let items = ctx.collect("item", data.children, |item_id| {
+        parse_outline_item(item_id, space, ctx)
+    })?;
 
     let outline = Outline {
         items,
@@ -287,7 +290,7 @@ pub(crate) fn parse_outline(outline_id: ExGuid, space: &ObjectSpace) -> Result<O
     Ok(outline)
 }
 
-fn parse_outline_item(item_id: ExGuid, space: &ObjectSpace) -> Result<OutlineItem> {
+fn parse_outline_item(item_id: ExGuid, space: &ObjectSpace, ctx: &Context) -> Result<OutlineItem> {
     let content_type = space
         .get_object(item_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("outline item is missing".into()))?
@@ -299,32 +302,34 @@ fn parse_outline_item(item_id: ExGuid, space: &ObjectSpace) -> Result<OutlineIte
     })?;
 
     let item = match id {
-        PropertySetId::OutlineGroup => OutlineItem::Group(parse_outline_group(item_id, space)?),
-        PropertySetId::OutlineElementNode => {
-            OutlineItem::Element(parse_outline_element(item_id, space)?)
-        }
-        _ => {
-            return Err(ErrorKind::MalformedOneNoteData(
-                format!("invalid outline item type: {:?}", id).into(),
-            )
-            .into());
-        }
+        PropertySetId::OutlineGroup => ctx
+            .nested(|| parse_outline_group(item_id, space, ctx))
+            .map(OutlineItem::Group),
+        PropertySetId::OutlineElementNode => ctx
+            .nested(|| parse_outline_element(item_id, space, ctx))
+            .map(OutlineItem::Element),
+        _ => Err(ErrorKind::MalformedOneNoteData(
+            format!("invalid outline item type: {:?}", id).into(),
+        )
+        .into()),
     };
 
-    Ok(item)
+    item.map_err(|err| err.with_jcid(content_type.0))
 }
 
-fn parse_outline_group(group_id: ExGuid, space: &ObjectSpace) -> Result<OutlineGroup> {
+fn parse_outline_group(
+    group_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+) -> Result<OutlineGroup> {
     let group_object = space
         .get_object(group_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("outline group is missing".into()))?;
     let data = outline_group::parse(group_object)?;
 
-    let outlines = data
-        .children
-        .into_iter()
-        .map(|item_id| parse_outline_item(item_id, space))
-        .collect::<Result<_>>()?;
+    let outlines = ctx.collect("item", data.children, |item_id| {
+        parse_outline_item(item_id, space, ctx)
+    })?;
 
     let group = OutlineGroup {
         child_level: data.child_level,
@@ -337,29 +342,24 @@ fn parse_outline_group(group_id: ExGuid, space: &ObjectSpace) -> Result<OutlineG
 pub(crate) fn parse_outline_element(
     element_id: ExGuid,
     space: &ObjectSpace,
+    ctx: &Context,
 ) -> Result<OutlineElement> {
     let element_object = space
         .get_object(element_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("outline element is missing".into()))?;
     let data = outline_element_node::parse(element_object)?;
 
-    let children = data
-        .children
-        .into_iter()
-        .map(|item_id| parse_outline_item(item_id, space))
-        .collect::<Result<_>>()?;
-
-    let contents = data
-        .contents
-        .into_iter()
-        .map(|content_id| parse_content(content_id, space))
-        .collect::<Result<_>>()?;
-
-    let list_contents = data
-        .list_contents
-        .into_iter()
-        .map(|list_id| parse_list(list_id, space))
-        .collect::<Result<_>>()?;
+    let children = ctx.collect("item", data.children, |item_id| {
+        parse_outline_item(item_id, space, ctx)
+    })?;
+
+    let contents = ctx.collect("content", data.contents, |content_id| {
+        parse_content(content_id, space, ctx)
+    })?;
+
+    let list_contents = ctx.collect("list", data.list_contents, |list_id| {
+        parse_list(list_id, space)
+    })?;
 
     let element = OutlineElement {
         child_level: data.child_level,
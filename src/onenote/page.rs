@@ -2,9 +2,11 @@ use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property::layout_alignment::LayoutAlignment;
 use crate::one::property_set::{page_manifest_node, page_metadata, page_node, title_node};
+use crate::onenote::context::Context;
 use crate::onenote::outline::{Outline, parse_outline};
 use crate::onenote::page_content::{PageContent, parse_page_content};
 use crate::onestore::object_space::ObjectSpace;
+use crate::shared::guid::Guid;
 
 /// A page.
 ///
@@ -14,10 +16,12 @@ use crate::onestore::object_space::ObjectSpace;
 /// [\[MS-ONE\] 2.2.19]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/e381b7c7-b434-43a2-ba23-0d08bafd281a
 #[derive(Clone, Debug)]
 pub struct Page {
+    pub(crate) id: Guid,
     title: Option<Title>,
     level: i32,
     author: Option<String>,
     height: Option<f32>,
+    is_conflict_page: bool,
     contents: Vec<PageContent>,
 }
 
@@ -54,6 +58,15 @@ impl Page {
         self.height
     }
 
+    /// Whether this is a conflict page.
+    ///
+    /// OneNote creates conflict pages for changes that couldn't be merged
+    /// into another page, e.g. when the page was edited on two devices at the
+    /// same time.
+    pub fn is_conflict_page(&self) -> bool {
+        self.is_conflict_page
+    }
+
     /// The page contents.
     pub fn contents(&self) -> &[PageContent] {
         &self.contents
@@ -145,43 +158,41 @@ impl Title {
     }
 }
 
-pub(crate) fn parse_page(page_space: &ObjectSpace) -> Result<Page> {
+pub(crate) fn parse_page(page_space: &ObjectSpace, ctx: &Context) -> Result<Page> {
     let metadata = parse_metadata(page_space)?;
     let manifest = parse_manifest(page_space)?;
 
     let data = parse_data(manifest, page_space)?;
 
-    let title = data
-        .title
-        .map(|id| parse_title(id, page_space))
-        .transpose()?;
+    let title = match data.title {
+        Some(id) => ctx.recover(|| "title".to_string(), || parse_title(id, page_space, ctx))?,
+        None => None,
+    };
     let level = metadata.page_level;
 
-    let contents = data
-        .content
-        .into_iter()
-        .map(|content_id| parse_page_content(content_id, page_space))
-        .collect::<Result<_>>()?;
+    let contents = ctx.collect("content", data.content, |content_id| {
+        parse_page_content(content_id, page_space, ctx)
+    })?;
 
     Ok(Page {
+        id: metadata.entity_guid,
         title,
         level,
         author: data.author.map(|author| author.into_value()),
         height: data.page_height,
+        is_conflict_page: data.is_conflict_page,
         contents,
     })
 }
 
-fn parse_title(title_id: ExGuid, space: &ObjectSpace) -> Result<Title> {
+fn parse_title(title_id: ExGuid, space: &ObjectSpace, ctx: &Context) -> Result<Title> {
     let title_object = space
         .get_object(title_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("title object is missing".into()))?;
     let title = title_node::parse(title_object)?;
-    let contents = title
-        .children
-        .into_iter()
-        .map(|outline_id| parse_outline(outline_id, space))
-        .collect::<Result<_>>()?;
+    let contents = ctx.collect("outline", title.children, |outline_id| {
+        parse_outline(outline_id, space, ctx)
+    })?;
 
     Ok(Title {
         contents,
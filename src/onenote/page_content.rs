@@ -1,10 +1,12 @@
 use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property_set::PropertySetId;
+use crate::onenote::context::Context;
 use crate::onenote::embedded_file::{EmbeddedFile, parse_embedded_file};
 use crate::onenote::image::{Image, parse_image};
 use crate::onenote::ink::{Ink, parse_ink};
 use crate::onenote::outline::{Outline, parse_outline};
+use crate::onenote::unknown::{UnknownContent, parse_unknown};
 use crate::onestore::object_space::ObjectSpace;
 
 /// The contents of a page.
@@ -18,8 +20,8 @@ pub enum PageContent {
     EmbeddedFile(EmbeddedFile),
     /// An ink drawing.
     Ink(Ink),
-    /// Content of unknown type.
-    Unknown,
+    /// Content of a type the parser doesn't support.
+    Unknown(UnknownContent),
 }
 
 impl PageContent {
@@ -58,28 +60,39 @@ impl PageContent {
             None
         }
     }
+
+    /// Return the preserved data if it's content of an unsupported type.
+    pub fn unknown(&self) -> Option<&UnknownContent> {
+        if let PageContent::Unknown(unknown) = self {
+            Some(unknown)
+        } else {
+            None
+        }
+    }
 }
 
-pub(crate) fn parse_page_content(content_id: ExGuid, space: &ObjectSpace) -> Result<PageContent> {
-    let content_type = space
+pub(crate) fn parse_page_content(
+    content_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+) -> Result<PageContent> {
+    let object = space
         .get_object(content_id)
-        .ok_or_else(|| ErrorKind::MalformedOneNoteData("page content is missing".into()))?
-        .id();
-    let id = PropertySetId::from_jcid(content_type).ok_or_else(|| {
-        ErrorKind::MalformedOneNoteData(
-            format!("invalid property set id: {:?}", content_type).into(),
-        )
-    })?;
+        .ok_or_else(|| ErrorKind::MalformedOneNoteData("page content is missing".into()))?;
 
-    let content = match id {
-        PropertySetId::ImageNode => PageContent::Image(parse_image(content_id, space)?),
-        PropertySetId::EmbeddedFileNode => {
-            PageContent::EmbeddedFile(parse_embedded_file(content_id, space)?)
+    let content = match PropertySetId::from_jcid(object.id()) {
+        Some(PropertySetId::ImageNode) => parse_image(content_id, space).map(PageContent::Image),
+        Some(PropertySetId::EmbeddedFileNode) => {
+            parse_embedded_file(content_id, space).map(PageContent::EmbeddedFile)
+        }
+        Some(PropertySetId::OutlineNode) => {
+            parse_outline(content_id, space, ctx).map(PageContent::Outline)
+        }
+        Some(PropertySetId::InkContainer) => {
+            parse_ink(content_id, space, ctx).map(PageContent::Ink)
         }
-        PropertySetId::OutlineNode => PageContent::Outline(parse_outline(content_id, space)?),
-        PropertySetId::InkContainer => PageContent::Ink(parse_ink(content_id, space)?),
-        _ => PageContent::Unknown,
+        _ => Ok(PageContent::Unknown(parse_unknown(content_id, object, ctx))),
     };
 
-    Ok(content)
+    content.map_err(|err| err.with_jcid(object.id().0))
 }
@@ -1,8 +1,11 @@
+use crate::cache::PageCache;
 use crate::errors::{ErrorKind, Result};
 use crate::fsshttpb::data::exguid::ExGuid;
 use crate::one::property_set::page_series_node;
+use crate::onenote::context::Context;
 use crate::onenote::page::{Page, parse_page};
 use crate::onestore::OneStore;
+use crate::progress::Counter;
 
 /// A series of page.
 ///
@@ -22,23 +25,48 @@ impl PageSeries {
     }
 }
 
-pub(crate) fn parse_page_series(id: ExGuid, store: &OneStore) -> Result<PageSeries> {
+pub(crate) fn parse_page_series(
+    id: ExGuid,
+    store: &OneStore,
+    progress: &Counter,
+    cache: &PageCache,
+    ctx: &Context,
+) -> Result<PageSeries> {
+    let data = parse_data(id, store)?;
+
+    let pages = ctx.collect_parallel("page", data.page_spaces, |page_space_id, ctx| {
+        progress.check()?;
+
+        let page = cache.page(page_space_id, ctx, |ctx| {
+            let page_space = store
+                .object_space(page_space_id)
+                .ok_or_else(|| ErrorKind::MalformedOneNoteData("page space is missing".into()))?;
+
+            parse_page(page_space, ctx)
+        });
+        progress.complete();
+
+        page
+    })?;
+
+    Ok(PageSeries { pages })
+}
+
+/// Count the pages of the page series.
+///
+/// Page series that fail to parse are not counted.
+pub(crate) fn count_pages(ids: &[ExGuid], store: &OneStore) -> usize {
+    ids.iter()
+        .filter_map(|id| parse_data(*id, store).ok())
+        .map(|data| data.page_spaces.len())
+        .sum()
+}
+
+fn parse_data(id: ExGuid, store: &OneStore) -> Result<page_series_node::Data> {
     let object = store
         .data_root()
         .get_object(id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("page series object is missing".into()))?;
-    let data = page_series_node::parse(object)?;
 
-    let pages = data
-        .page_spaces
-        .into_iter()
-        .map(|page_space_id| {
-            store
-                .object_space(page_space_id)
-                .ok_or_else(|| ErrorKind::MalformedOneNoteData("page space is missing".into()))
-        })
-        .map(|page_space| parse_page(page_space?))
-        .collect::<Result<_>>()?;
-
-    Ok(PageSeries { pages })
+    page_series_node::parse(object)
 }
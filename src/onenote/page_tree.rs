@@ -0,0 +1,108 @@
+use crate::onenote::page::Page;
+use std::mem;
+
+/// A page and its subpages in a section's page tree.
+///
+/// See [`Section::page_tree()`].
+///
+/// [`Section::page_tree()`]: crate::section::Section::page_tree
+#[derive(Clone, Debug)]
+pub struct PageTreeNode<'a> {
+    page: &'a Page,
+    children: Vec<PageTreeNode<'a>>,
+}
+
+impl<'a> PageTreeNode<'a> {
+    /// The page.
+    pub fn page(&self) -> &'a Page {
+        self.page
+    }
+
+    /// The page's subpages in section order.
+    pub fn children(&self) -> &[PageTreeNode<'a>] {
+        &self.children
+    }
+}
+
+/// Nest the pages under their parent pages.
+///
+/// A page's parent is the closest preceding page with a lower level. Pages
+/// without such a page are roots, so a page that skips levels is nested
+/// directly below the previous shallower page.
+pub(crate) fn build_page_tree<'a>(
+    pages: impl IntoIterator<Item = &'a Page>,
+) -> Vec<PageTreeNode<'a>> {
+    let pages: Vec<_> = pages.into_iter().collect();
+    let parents = find_parents(pages.iter().map(|page| page.level()));
+
+    // Children always follow their parent, so building the nodes in reverse
+    // order completes each node's children before the node itself.
+    let mut children: Vec<Vec<PageTreeNode>> = vec![vec![]; pages.len()];
+    let mut roots = vec![];
+
+    for (index, page) in pages.into_iter().enumerate().rev() {
+        let mut node_children = mem::take(&mut children[index]);
+        node_children.reverse();
+
+        let node = PageTreeNode {
+            page,
+            children: node_children,
+        };
+
+        match parents[index] {
+            Some(parent) => children[parent].push(node),
+            None => roots.push(node),
+        }
+    }
+
+    roots.reverse();
+
+    roots
+}
+
+/// Find the index of each page's parent page from the page levels.
+fn find_parents(levels: impl Iterator<Item = i32>) -> Vec<Option<usize>> {
+    let mut ancestors: Vec<(i32, usize)> = vec![];
+
+    levels
+        .enumerate()
+        .map(|(index, level)| {
+            while ancestors.last().is_some_and(|(parent, _)| *parent >= level) {
+                ancestors.pop();
+            }
+
+            let parent = ancestors.last().map(|(_, parent)| *parent);
+            ancestors.push((level, index));
+
+            parent
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_parents;
+
+    fn parents(levels: &[i32]) -> Vec<Option<usize>> {
+        find_parents(levels.iter().copied())
+    }
+
+    #[test]
+    fn test_find_parents() {
+        assert_eq!(
+            parents(&[1, 2, 3, 2, 1, 2]),
+            [None, Some(0), Some(1), Some(0), None, Some(4)]
+        );
+        assert_eq!(parents(&[0, 0]), [None, None]);
+        assert_eq!(parents(&[]), []);
+    }
+
+    #[test]
+    fn test_find_parents_malformed_levels() {
+        // Skipped levels nest below the previous shallower page.
+        assert_eq!(parents(&[1, 3, 2, 3]), [None, Some(0), Some(0), Some(2)]);
+
+        // Leading subpages and levels below the first page are roots.
+        assert_eq!(parents(&[2, 3, 1, -1]), [None, Some(0), None, None]);
+    }
+}
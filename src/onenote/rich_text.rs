@@ -8,6 +8,7 @@ use crate::one::property_set::{
     embedded_ink_container, math_inline_object, paragraph_style_object, rich_text_node,
     text_run_data,
 };
+use crate::onenote::context::Context;
 use crate::onenote::ink::{Ink, InkBoundingBox, parse_ink_data};
 use crate::onenote::math_inline_object::{MathInlineObject, parse_math_inline_object};
 use crate::onenote::note_tag::{NoteTag, parse_note_tags};
@@ -65,7 +66,40 @@ impl RichText {
         &self.text_run_formatting
     }
 
-    /// The character positions where the text runs end.
+    /// The paragraph text without hidden text runs and embedded object placeholders.
+    ///
+    /// This is the text as it is displayed by OneNote. Use [`RichText::text`] to
+    /// get the full paragraph text.
+    pub fn visible_text(&self) -> String {
+        let mut run = 0;
+        let mut position = 0;
+
+        // The run indices count UTF-16 code units
+        self.text
+            .chars()
+            .filter(|c| {
+                let start = position;
+                position += c.len_utf16() as u32;
+
+                while self
+                    .text_run_indices
+                    .get(run)
+                    .is_some_and(|end| start >= *end)
+                {
+                    run += 1;
+                }
+
+                let style = self
+                    .text_run_formatting
+                    .get(run)
+                    .unwrap_or(&self.paragraph_style);
+
+                !style.hidden && !style.text_run_is_embedded_object
+            })
+            .collect()
+    }
+
+    /// The positions where the text runs end, counted in UTF-16 code units.
     ///
     /// See [\[MS-ONE\] 2.3.76].
     ///
@@ -228,6 +262,10 @@ pub struct ParagraphStyling {
     pub(crate) language_code: Option<u32>,
     pub(crate) math_formatting: bool,
     pub(crate) hyperlink: bool,
+    pub(crate) hyperlink_protected: bool,
+    pub(crate) hidden: bool,
+    pub(crate) text_run_is_embedded_object: bool,
+    pub(crate) text_run_object_type: Option<u32>,
 }
 
 impl ParagraphStyling {
@@ -366,13 +404,37 @@ impl ParagraphStyling {
     pub fn hyperlink(&self) -> bool {
         self.hyperlink
     }
+
+    /// Whether the hyperlink is protected from being edited.
+    pub fn hyperlink_protected(&self) -> bool {
+        self.hyperlink_protected
+    }
+
+    /// Whether the text is hidden and not displayed by OneNote.
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Whether the text run is a placeholder for an embedded object.
+    pub fn text_run_is_embedded_object(&self) -> bool {
+        self.text_run_is_embedded_object
+    }
+
+    /// The type of the object embedded in the text run (if present).
+    pub fn text_run_object_type(&self) -> Option<u32> {
+        self.text_run_object_type
+    }
 }
 
 // Embedded object types
 const INK_SPACE_BLOB: u32 = 0x00020026;
 const INK_END_OF_LINE_BLOB: u32 = 0x00020027;
 
-pub(crate) fn parse_rich_text(content_id: ExGuid, space: &ObjectSpace) -> Result<RichText> {
+pub(crate) fn parse_rich_text(
+    content_id: ExGuid,
+    space: &ObjectSpace,
+    ctx: &Context,
+) -> Result<RichText> {
     let object = space
         .get_object(content_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("rich text content is missing".into()))?;
@@ -438,37 +500,39 @@ pub(crate) fn parse_rich_text(content_id: ExGuid, space: &ObjectSpace) -> Result
 
     let mut objects_without_ref = 0;
 
-    let embedded_objects: Vec<_> = objects
-        .into_iter()
-        .enumerate()
-        .map(|(i, (object_type, embedded_data))| match object_type {
-            Some(INK_END_OF_LINE_BLOB) => {
-                objects_without_ref += 1;
-                Ok(Some(EmbeddedObject::InkLineBreak))
-            }
-            Some(INK_SPACE_BLOB) => {
-                objects_without_ref += 1;
-                parse_embedded_ink_space(embedded_data)
-                    .map(|space| Some(EmbeddedObject::InkSpace(space)))
-            }
-            None => {
-                if !data.text_run_data_object.is_empty() {
-                    return parse_embedded_ink_data(
-                        data.text_run_data_object[i - objects_without_ref],
-                        space,
-                        embedded_data,
-                    )
-                    .map(|container| Some(EmbeddedObject::Ink(container)));
+    let embedded_objects: Vec<_> = ctx
+        .collect(
+            "embedded object",
+            objects.into_iter().enumerate(),
+            |(i, (object_type, embedded_data))| match object_type {
+                Some(INK_END_OF_LINE_BLOB) => {
+                    objects_without_ref += 1;
+                    Ok(Some(EmbeddedObject::InkLineBreak))
                 }
-
-                Ok(None)
-            }
-            Some(v) => Err(ErrorKind::MalformedOneNoteFileData(
-                format!("unknown embedded object type: {:x}", v).into(),
-            )
-            .into()),
-        })
-        .collect::<Result<Vec<_>>>()?
+                Some(INK_SPACE_BLOB) => {
+                    objects_without_ref += 1;
+                    parse_embedded_ink_space(embedded_data)
+                        .map(|space| Some(EmbeddedObject::InkSpace(space)))
+                }
+                None => {
+                    if !data.text_run_data_object.is_empty() {
+                        return parse_embedded_ink_data(
+                            data.text_run_data_object[i - objects_without_ref],
+                            space,
+                            embedded_data,
+                            ctx,
+                        )
+                        .map(|container| Some(EmbeddedObject::Ink(container)));
+                    }
+
+                    Ok(None)
+                }
+                Some(v) => Err(ErrorKind::MalformedOneNoteFileData(
+                    format!("unknown embedded object type: {:x}", v).into(),
+                )
+                .into()),
+            },
+        )?
         .into_iter()
         .flatten()
         .collect_vec();
@@ -505,8 +569,9 @@ fn parse_embedded_ink_data(
     embedded_id: ExGuid,
     space: &ObjectSpace,
     data: embedded_ink_container::Data,
+    ctx: &Context,
 ) -> Result<EmbeddedInkContainer> {
-    let (strokes, bb) = parse_ink_data(embedded_id, space, None, None)?;
+    let (strokes, bb) = parse_ink_data(embedded_id, space, None, None, ctx)?;
 
     let display_bb = data
         .start_x
@@ -567,5 +632,100 @@ fn parse_style(data: paragraph_style_object::Data) -> ParagraphStyling {
         language_code: data.language_code,
         math_formatting: data.math_formatting,
         hyperlink: data.hyperlink,
+        hyperlink_protected: data.hyperlink_protected,
+        hidden: data.hidden,
+        text_run_is_embedded_object: data.text_run_is_embedded_object,
+        text_run_object_type: data.text_run_object_type,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParagraphStyling, RichText};
+    use crate::one::property::paragraph_alignment::ParagraphAlignment;
+
+    fn style(hidden: bool, text_run_is_embedded_object: bool) -> ParagraphStyling {
+        ParagraphStyling {
+            charset: None,
+            bold: false,
+            italic: false,
+            underline: false,
+            strikethrough: false,
+            superscript: false,
+            subscript: false,
+            font: None,
+            font_size: None,
+            font_color: None,
+            highlight: None,
+            next_style: None,
+            style_id: None,
+            paragraph_alignment: None,
+            paragraph_space_before: None,
+            paragraph_space_after: None,
+            paragraph_line_spacing_exact: None,
+            language_code: None,
+            math_formatting: false,
+            hyperlink: false,
+            hyperlink_protected: false,
+            hidden,
+            text_run_is_embedded_object,
+            text_run_object_type: None,
+        }
+    }
+
+    fn rich_text(text: &str, formatting: Vec<ParagraphStyling>, indices: Vec<u32>) -> RichText {
+        RichText {
+            text: text.to_string(),
+            text_run_formatting: formatting,
+            text_run_indices: indices,
+            paragraph_style: style(false, false),
+            paragraph_space_before: 0.0,
+            paragraph_space_after: 0.0,
+            paragraph_line_spacing_exact: None,
+            paragraph_alignment: ParagraphAlignment::Left,
+            layout_alignment_in_parent: None,
+            layout_alignment_self: None,
+            note_tags: vec![],
+            embedded_objects: vec![],
+            math_inline_objects: vec![],
+        }
+    }
+
+    #[test]
+    fn test_visible_text_skips_hidden_and_object_runs() {
+        let text = rich_text(
+            "Hällo secret\u{FDEF}world",
+            vec![
+                style(false, false),
+                style(true, false),
+                style(false, true),
+                style(false, false),
+            ],
+            vec![6, 12, 13],
+        );
+
+        assert_eq!(text.visible_text(), "Hällo world");
+    }
+
+    #[test]
+    fn test_visible_text_counts_utf16_units() {
+        // The emoji is a surrogate pair, so it takes up two positions.
+        let text = rich_text(
+            "\u{1F600} ab",
+            vec![style(false, false), style(true, false), style(false, false)],
+            vec![3, 4],
+        );
+
+        assert_eq!(text.visible_text(), "\u{1F600} b");
+    }
+
+    #[test]
+    fn test_visible_text_without_runs() {
+        let text = rich_text("Hello", vec![], vec![]);
+        assert_eq!(text.visible_text(), "Hello");
+
+        let mut text = rich_text("Hello", vec![], vec![]);
+        text.paragraph_style.hidden = true;
+        assert_eq!(text.visible_text(), "");
     }
 }
@@ -1,9 +1,17 @@
-use crate::errors::{ErrorKind, Result};
+use crate::cache::PageCache;
+use crate::errors::{Diagnostic, ErrorKind, Result};
 use crate::one::property::color::Color;
 use crate::one::property_set::{section_metadata_node, section_node};
-use crate::onenote::page_series::{PageSeries, parse_page_series};
+use crate::onenote::context::Context;
+use crate::onenote::notebook::TocEntry;
+use crate::onenote::page_series::{PageSeries, count_pages, parse_page_series};
+use crate::onenote::page_tree::{PageTreeNode, build_page_tree};
 use crate::onestore::OneStore;
 use crate::onestore::object_space::ObjectSpace;
+use crate::progress::Counter;
+use crate::shared::guid::Guid;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 /// An entry in a section list.
 #[allow(missing_docs)]
@@ -11,6 +19,9 @@ use crate::onestore::object_space::ObjectSpace;
 pub enum SectionEntry {
     Section(Section),
     SectionGroup(SectionGroup),
+    /// A table of contents entry whose section file or section group
+    /// directory doesn't exist.
+    Missing(TocEntry),
 }
 
 /// A OneNote section.
@@ -21,9 +32,14 @@ pub enum SectionEntry {
 /// [\[MS-ONE\] 2.2.17]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/6913913f-b7d1-4b29-ab09-231ea3835ac2
 #[derive(Clone, Debug)]
 pub struct Section {
+    pub(crate) id: Guid,
     display_name: String,
     page_series: Vec<PageSeries>,
     color: Option<Color>,
+    schema_revision_in_order_to_read: u32,
+    pub(crate) toc_entry: Option<TocEntry>,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    pub(crate) unknown_jcids: BTreeMap<u32, usize>,
 }
 
 impl Section {
@@ -37,10 +53,60 @@ impl Section {
         &self.page_series
     }
 
+    /// The section's pages with their subpages nested below them.
+    ///
+    /// Pages are nested based on their [`level`] and order in their page
+    /// series. A page that skips levels is nested directly below the previous
+    /// page with a lower level. Pages are never nested below a page of another
+    /// page series.
+    ///
+    /// [`level`]: crate::page::Page::level
+    pub fn page_tree(&self) -> Vec<PageTreeNode<'_>> {
+        self.page_series
+            .iter()
+            .flat_map(|series| build_page_tree(series.pages()))
+            .collect()
+    }
+
     /// The color of the section.
     pub fn color(&self) -> Option<Color> {
         self.color
     }
+
+    /// The minimum schema revision that an application must support to read
+    /// the section.
+    ///
+    /// See [`validation::SUPPORTED_SCHEMA_REVISION`].
+    ///
+    /// [`validation::SUPPORTED_SCHEMA_REVISION`]: crate::validation::SUPPORTED_SCHEMA_REVISION
+    pub fn schema_revision_in_order_to_read(&self) -> u32 {
+        self.schema_revision_in_order_to_read
+    }
+
+    /// The section's entry in the notebook's table of contents.
+    ///
+    /// This is `None` if the section hasn't been parsed as part of a notebook.
+    pub fn toc_entry(&self) -> Option<&TocEntry> {
+        self.toc_entry.as_ref()
+    }
+
+    /// The errors that were skipped when parsing the section in lenient mode.
+    ///
+    /// The diagnostics' locations are relative to the section.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// The JCIDs of the content the parser doesn't support.
+    ///
+    /// Maps each JCID to the number of times it was encountered. The content
+    /// is available as [`Content::Unknown`] or [`PageContent::Unknown`].
+    ///
+    /// [`Content::Unknown`]: crate::contents::Content::Unknown
+    /// [`PageContent::Unknown`]: crate::page::PageContent::Unknown
+    pub fn unknown_jcids(&self) -> &BTreeMap<u32, usize> {
+        &self.unknown_jcids
+    }
 }
 
 /// A group of sections.
@@ -48,6 +114,10 @@ impl Section {
 pub struct SectionGroup {
     pub(crate) display_name: String,
     pub(crate) entries: Vec<SectionEntry>,
+    pub(crate) color: Option<Color>,
+    pub(crate) toc_entry: TocEntry,
+    pub(crate) orphaned_sections: Vec<PathBuf>,
+    pub(crate) diagnostics: Vec<Diagnostic>,
 }
 
 impl SectionGroup {
@@ -60,9 +130,100 @@ impl SectionGroup {
     pub fn entries(&self) -> &[SectionEntry] {
         &self.entries
     }
+
+    /// The color of the group's own table of contents.
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// The group's entry in the parent's table of contents.
+    pub fn toc_entry(&self) -> &TocEntry {
+        &self.toc_entry
+    }
+
+    /// The section files in the group's directory that are not listed in its
+    /// table of contents.
+    ///
+    /// See [`Notebook::orphaned_sections`].
+    ///
+    /// [`Notebook::orphaned_sections`]: crate::notebook::Notebook::orphaned_sections
+    pub fn orphaned_sections(&self) -> &[PathBuf] {
+        &self.orphaned_sections
+    }
+
+    /// The errors that were skipped when parsing the section group in lenient mode.
+    ///
+    /// This includes the diagnostics of all sections within the group. The
+    /// diagnostics' locations are relative to the section group.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        collect_diagnostics(&self.diagnostics, &self.entries)
+    }
+
+    /// The JCIDs of the content the parser doesn't support in all sections
+    /// within the group.
+    ///
+    /// See [`Section::unknown_jcids`].
+    pub fn unknown_jcids(&self) -> BTreeMap<u32, usize> {
+        collect_unknown_jcids(&self.entries)
+    }
+}
+
+/// Collect the diagnostics of a notebook or section group and its entries.
+pub(crate) fn collect_diagnostics(own: &[Diagnostic], entries: &[SectionEntry]) -> Vec<Diagnostic> {
+    let mut diagnostics = own.to_vec();
+
+    for entry in entries {
+        let (segment, entry_diagnostics) = match entry {
+            SectionEntry::Section(section) => (
+                format!("section {:?}", section.display_name),
+                section.diagnostics.clone(),
+            ),
+            SectionEntry::SectionGroup(group) => (
+                format!("section group {:?}", group.display_name),
+                group.diagnostics(),
+            ),
+            SectionEntry::Missing(_) => continue,
+        };
+
+        diagnostics.extend(entry_diagnostics.into_iter().map(|mut diagnostic| {
+            diagnostic.location.insert(0, segment.clone());
+            diagnostic
+        }));
+    }
+
+    diagnostics
 }
 
-pub(crate) fn parse_section(store: OneStore, filename: String) -> Result<Section> {
+/// Sum up the unsupported JCIDs of the sections in a notebook or section group.
+pub(crate) fn collect_unknown_jcids(entries: &[SectionEntry]) -> BTreeMap<u32, usize> {
+    let mut jcids = BTreeMap::new();
+
+    for entry in entries {
+        let entry_jcids = match entry {
+            SectionEntry::Section(section) => section.unknown_jcids.clone(),
+            SectionEntry::SectionGroup(group) => group.unknown_jcids(),
+            SectionEntry::Missing(_) => continue,
+        };
+
+        for (jcid, count) in entry_jcids {
+            *jcids.entry(jcid).or_default() += count;
+        }
+    }
+
+    jcids
+}
+
+/// Parse a section from its store.
+///
+/// `progress` creates the page counter once the number of pages is known.
+/// Pages are reused from `cache` if possible.
+pub(crate) fn parse_section<'a>(
+    store: OneStore,
+    filename: String,
+    progress: impl FnOnce(usize) -> Counter<'a>,
+    cache: &PageCache,
+    ctx: &Context,
+) -> Result<Section> {
     let metadata = parse_metadata(store.data_root())?;
     let content = parse_content(store.data_root())?;
 
@@ -72,16 +233,21 @@ pub(crate) fn parse_section(store: OneStore, filename: String) -> Result<Section
         .trim_end_matches(".one")
         .to_string();
 
-    let page_series = content
-        .page_series
-        .into_iter()
-        .map(|page_series_id| parse_page_series(page_series_id, &store))
-        .collect::<Result<_>>()?;
+    let pages = progress(count_pages(&content.page_series, &store));
+
+    let page_series = ctx.collect("page series", content.page_series, |page_series_id| {
+        parse_page_series(page_series_id, &store, &pages, cache, ctx)
+    })?;
 
     Ok(Section {
+        id: content.entity_guid,
         display_name,
         page_series,
         color: metadata.color,
+        schema_revision_in_order_to_read: metadata.schema_revision_in_order_to_read,
+        toc_entry: None,
+        diagnostics: ctx.take_diagnostics(),
+        unknown_jcids: ctx.take_unknown_jcids(),
     })
 }
 
@@ -0,0 +1,112 @@
+use crate::fsshttpb::packaging::OneStorePackaging;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A section file that incremental updates can be applied to.
+///
+/// OneNote clients synchronize sections using the FSSHTTP protocol. The server
+/// sends the changes as FSSHTTPB data element packages with new revisions,
+/// object groups and storage index entries. Instead of downloading the section
+/// again, these packages can be applied to a section that has been read before
+/// using [`Parser::apply_section_update`]. [`Parser::parse_section_package`]
+/// then parses the updated section.
+///
+/// Use [`Parser::read_section_package`] to read a section package.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12].
+///
+/// [`Parser::apply_section_update`]: crate::Parser::apply_section_update
+/// [`Parser::parse_section_package`]: crate::Parser::parse_section_package
+/// [`Parser::read_section_package`]: crate::Parser::read_section_package
+/// [\[MS-FSSHTTPB\] 2.2.1.12]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/99a25464-99b5-4262-a964-baabed2170eb
+pub struct SectionPackage {
+    pub(crate) packaging: OneStorePackaging,
+    pub(crate) path: PathBuf,
+    pub(crate) updates: usize,
+}
+
+impl SectionPackage {
+    /// The file name that was used to read the section.
+    pub fn file_name(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of updates that have been applied.
+    pub fn updates(&self) -> usize {
+        self.updates
+    }
+}
+
+impl fmt::Debug for SectionPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SectionPackage")
+            .field("path", &self.path)
+            .field("updates", &self.updates)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Parser;
+    use crate::onenote::section::Section;
+    use crate::test_support::{
+        SpaceBuilder, build_update, page_space, section_root, section_store,
+    };
+    use std::path::Path;
+
+    fn heights(section: &Section) -> Vec<Option<f32>> {
+        section.page_series()[0]
+            .pages()
+            .iter()
+            .map(|page| page.height())
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_section_update() {
+        let parser = Parser::new();
+        let data = section_store(vec![page_space(20, 1, 1.0), page_space(21, 1, 2.0)]);
+
+        let mut package = parser
+            .read_section_package(&data, Path::new("Synthetic.one"))
+            .unwrap();
+        let section = parser.parse_section_package(&package).unwrap();
+        assert_eq!(heights(&section), [Some(1.0), Some(2.0)]);
+
+        // Edit a page.
+        let update = build_update(1, &[page_space(21, 2, 3.0)]);
+        parser.apply_section_update(&mut package, &update).unwrap();
+
+        let section = parser.parse_section_package(&package).unwrap();
+        assert_eq!(heights(&section), [Some(1.0), Some(3.0)]);
+
+        // Add a page.
+        let pages = [20, 21, 22].map(|value| SpaceBuilder::new(value).cell_id());
+        let update = build_update(2, &[section_root(2, &pages), page_space(22, 1, 4.0)]);
+        parser.apply_section_update(&mut package, &update).unwrap();
+
+        let section = parser.parse_section_package(&package).unwrap();
+        assert_eq!(heights(&section), [Some(1.0), Some(3.0), Some(4.0)]);
+        assert_eq!(package.updates(), 2);
+    }
+
+    #[test]
+    fn test_apply_invalid_section_update() {
+        let parser = Parser::new();
+        let data = section_store(vec![page_space(20, 1, 1.0)]);
+        let mut package = parser
+            .read_section_package(&data, Path::new("Synthetic.one"))
+            .unwrap();
+
+        let update = build_update(1, &[page_space(20, 2, 2.0)]);
+        let err = parser
+            .apply_section_update(&mut package, &update[..update.len() / 2])
+            .unwrap_err();
+        assert_eq!(err.file(), Some(Path::new("Synthetic.one")));
+        assert_eq!(package.updates(), 0);
+
+        let section = parser.parse_section_package(&package).unwrap();
+        assert_eq!(heights(&section), [Some(1.0)]);
+    }
+}
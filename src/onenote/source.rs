@@ -0,0 +1,487 @@
+use crate::errors::{ErrorKind, Limit, Result};
+use bytes::Bytes;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::ops::Deref;
+use std::path::{Component, Path, PathBuf};
+#[cfg(feature = "zip")]
+use std::{io::Seek, sync::Mutex};
+
+/// A source of notebook files.
+///
+/// The parser uses this to read the table of contents, section files and section
+/// group folders of a notebook (see [`Parser::parse_notebook_from`]). Implement
+/// this trait to parse notebooks from other storage systems.
+///
+/// Paths passed to the methods of this trait are built from the path of the
+/// table of contents file and the (validated) entries of the table of
+/// contents.
+///
+/// [`Parser::parse_notebook_from`]: crate::Parser::parse_notebook_from
+pub trait Source {
+    /// Read the contents of a file.
+    ///
+    /// Fails with [`ErrorKind::LimitExceeded`] if the file is larger than
+    /// `max_size` bytes. Implementations must not read more than `max_size + 1`
+    /// bytes before failing (see [`ParseLimits::max_input_size`]).
+    ///
+    /// [`ErrorKind::LimitExceeded`]: crate::errors::ErrorKind::LimitExceeded
+    /// [`ParseLimits::max_input_size`]: crate::ParseLimits::max_input_size
+    fn read(&self, path: &Path, max_size: u64) -> Result<Vec<u8>>;
+
+    /// Check whether the path points to a file.
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// Check whether the path points to a directory.
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// List the paths of all entries of a directory.
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>>;
+
+    /// Resolve the path to its canonical form.
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
+
+    /// Check whether the path points to an existing file or directory.
+    fn exists(&self, path: &Path) -> bool {
+        self.is_file(path) || self.is_dir(path)
+    }
+
+    /// Read the contents of a section file.
+    ///
+    /// Sources can override this to return a buffer that the parsed data can
+    /// borrow from, e.g. a memory-mapped file. The default implementation
+    /// calls [`Source::read`].
+    fn read_buffer(&self, path: &Path, max_size: u64) -> Result<FileBuffer> {
+        self.read(path, max_size).map(FileBuffer::from)
+    }
+}
+
+/// The contents of a file that has been read from a [`Source`].
+///
+/// The buffer either owns the file's contents or, with the `mmap` feature,
+/// refers to a memory-mapped file. Binary data (e.g. images and embedded
+/// files) parsed from a memory-mapped file refers to the mapping instead of
+/// being copied.
+pub struct FileBuffer {
+    inner: Buffer,
+}
+
+enum Buffer {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(Bytes),
+}
+
+impl FileBuffer {
+    /// Memory-map a file.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the buffer or any data
+    /// parsed from it is alive. See [`memmap2::Mmap`].
+    #[cfg(feature = "mmap")]
+    pub unsafe fn map(file: &File) -> Result<FileBuffer> {
+        let mmap = unsafe { memmap2::Mmap::map(file)? };
+
+        Ok(FileBuffer {
+            inner: Buffer::Mapped(Bytes::from_owner(mmap)),
+        })
+    }
+
+    /// The buffer that parsed data can share instead of copying it.
+    pub(crate) fn shared(&self) -> Option<&Bytes> {
+        match &self.inner {
+            Buffer::Owned(_) => None,
+            #[cfg(feature = "mmap")]
+            Buffer::Mapped(data) => Some(data),
+        }
+    }
+}
+
+impl From<Vec<u8>> for FileBuffer {
+    fn from(data: Vec<u8>) -> Self {
+        FileBuffer {
+            inner: Buffer::Owned(data),
+        }
+    }
+}
+
+impl Deref for FileBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.inner {
+            Buffer::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            Buffer::Mapped(data) => data,
+        }
+    }
+}
+
+impl fmt::Debug for FileBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FileBuffer({} bytes)", self.len())
+    }
+}
+
+/// Files from the local filesystem.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LocalSource;
+
+impl Source for LocalSource {
+    fn read(&self, path: &Path, max_size: u64) -> Result<Vec<u8>> {
+        let file = File::open(path)?;
+        check_size(file.metadata()?.len(), max_size)?;
+
+        read_limited(BufReader::new(file), max_size)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        path.read_dir()?.map(|entry| Ok(entry?.path())).collect()
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        Ok(path.canonicalize()?)
+    }
+}
+
+/// Memory-mapped files from the local filesystem.
+///
+/// Section files are memory-mapped instead of being read into memory. This
+/// keeps the memory usage low when parsing large files because only the
+/// parsed data is kept in memory (see [`FileBuffer`]).
+#[cfg(feature = "mmap")]
+#[derive(Debug, Copy, Clone)]
+pub struct MmapSource {
+    _private: (),
+}
+
+#[cfg(feature = "mmap")]
+impl MmapSource {
+    /// Create a source that memory-maps section files.
+    ///
+    /// # Safety
+    ///
+    /// Section files must not be modified or truncated while the parsed
+    /// sections are alive. See [`FileBuffer::map`].
+    pub unsafe fn new() -> MmapSource {
+        MmapSource { _private: () }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Source for MmapSource {
+    fn read(&self, path: &Path, max_size: u64) -> Result<Vec<u8>> {
+        LocalSource.read(path, max_size)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        LocalSource.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        LocalSource.is_dir(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        LocalSource.read_dir(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        LocalSource.canonicalize(path)
+    }
+
+    fn read_buffer(&self, path: &Path, max_size: u64) -> Result<FileBuffer> {
+        let file = File::open(path)?;
+        check_size(file.metadata()?.len(), max_size)?;
+
+        // SAFETY: Guaranteed by the caller of `MmapSource::new`.
+        unsafe { FileBuffer::map(&file) }
+    }
+}
+
+/// Files that are stored in memory.
+///
+/// Directories are implied by the paths of the files they contain.
+#[derive(Debug, Default, Clone)]
+pub struct MemorySource {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl MemorySource {
+    /// Create an empty in-memory source.
+    pub fn new() -> MemorySource {
+        MemorySource::default()
+    }
+
+    /// Add a file with the given path.
+    pub fn insert(&mut self, path: impl AsRef<Path>, data: Vec<u8>) {
+        self.files.insert(normalize(path.as_ref()), data);
+    }
+
+    /// Iterate over the paths of all files.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(|path| path.as_path())
+    }
+}
+
+impl Source for MemorySource {
+    fn read(&self, path: &Path, max_size: u64) -> Result<Vec<u8>> {
+        let data = self
+            .files
+            .get(&normalize(path))
+            .ok_or_else(|| not_found("file", path))?;
+        check_size(data.len() as u64, max_size)?;
+
+        Ok(data.clone())
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        is_dir(self.files.keys(), path)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        read_dir(self.files.keys(), path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        Ok(normalize(path))
+    }
+}
+
+/// Files from a ZIP archive.
+///
+/// This can be used to parse notebooks from the ZIP archives created by
+/// OneDrive's download function without extracting them first. Files are
+/// decompressed when they're read.
+#[cfg(feature = "zip")]
+pub struct ZipSource<R> {
+    archive: Mutex<zip::ZipArchive<R>>,
+    files: BTreeMap<PathBuf, usize>,
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> ZipSource<R> {
+    /// Open a ZIP archive.
+    ///
+    /// Entries with unsafe paths (e.g. absolute paths or paths that contain
+    /// `..` components) are ignored.
+    pub fn new(reader: R) -> Result<ZipSource<R>> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+
+        let mut files = BTreeMap::new();
+        for index in 0..archive.len() {
+            let entry = archive.by_index_raw(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+
+            if let Some(path) = entry.enclosed_name() {
+                files.insert(normalize(&path), index);
+            }
+        }
+
+        Ok(ZipSource {
+            archive: Mutex::new(archive),
+            files,
+        })
+    }
+
+    /// Iterate over the paths of all files.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(|path| path.as_path())
+    }
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> Source for ZipSource<R> {
+    fn read(&self, path: &Path, max_size: u64) -> Result<Vec<u8>> {
+        let index = *self
+            .files
+            .get(&normalize(path))
+            .ok_or_else(|| not_found("file", path))?;
+
+        let mut archive = self
+            .archive
+            .lock()
+            .map_err(|_| std::io::Error::other("ZIP archive lock is poisoned"))?;
+        let file = archive.by_index(index)?;
+        check_size(file.size(), max_size)?;
+
+        // The declared size isn't trusted, so the decompressed data is limited as well
+        read_limited(file, max_size)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        is_dir(self.files.keys(), path)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        read_dir(self.files.keys(), path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        Ok(normalize(path))
+    }
+}
+
+/// Check whether a directory is implied by the (sorted) file paths.
+fn is_dir<'a>(mut files: impl Iterator<Item = &'a PathBuf>, path: &Path) -> bool {
+    let path = normalize(path);
+
+    files.any(|file| file != &path && file.starts_with(&path))
+}
+
+/// List the entries of a directory that is implied by the (sorted) file paths.
+fn read_dir<'a>(files: impl Iterator<Item = &'a PathBuf>, path: &Path) -> Result<Vec<PathBuf>> {
+    let path = normalize(path);
+
+    let mut entries: Vec<_> = files
+        .filter(|file| *file != &path)
+        .filter_map(|file| file.strip_prefix(&path).ok())
+        .filter_map(|relative| relative.components().next())
+        .map(|name| path.join(name))
+        .collect();
+    entries.dedup();
+
+    if entries.is_empty() {
+        return Err(not_found("directory", &path));
+    }
+
+    Ok(entries)
+}
+
+/// Read all data but fail as soon as it's larger than `max_size` bytes.
+fn read_limited(reader: impl Read, max_size: u64) -> Result<Vec<u8>> {
+    let mut data = vec![];
+    reader
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut data)?;
+    check_size(data.len() as u64, max_size)?;
+
+    Ok(data)
+}
+
+fn check_size(size: u64, max_size: u64) -> Result<()> {
+    if size > max_size {
+        return Err(ErrorKind::LimitExceeded {
+            limit: Limit::InputSize,
+            max: max_size,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+fn not_found(kind: &str, path: &Path) -> crate::errors::Error {
+    ErrorKind::IO {
+        err: std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{kind} not found: {}", path.display()),
+        ),
+    }
+    .into()
+}
+
+/// Normalize a path lexically by removing `.` and resolving `..` components.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemorySource, Source, read_limited};
+    use crate::errors::{ErrorKind, Limit};
+    use std::path::{Path, PathBuf};
+
+    fn is_input_size_error(err: crate::errors::Error) -> bool {
+        matches!(
+            err.kind(),
+            ErrorKind::LimitExceeded {
+                limit: Limit::InputSize,
+                ..
+            }
+        )
+    }
+
+    #[test]
+    fn test_memory_source() {
+        let mut source = MemorySource::new();
+        source.insert("Notebook.onetoc2", vec![1]);
+        source.insert("Group/Notebook.onetoc2", vec![2]);
+        source.insert("Group/Section.one", vec![3]);
+
+        assert!(source.is_file(Path::new("./Group/Section.one")));
+        assert!(source.is_dir(Path::new("Group")));
+        assert!(!source.is_dir(Path::new("Group/Section.one")));
+        assert!(!source.exists(Path::new("Missing.one")));
+
+        assert_eq!(
+            source
+                .read(Path::new("Group/../Notebook.onetoc2"), u64::MAX)
+                .unwrap(),
+            [1]
+        );
+        assert!(source.read(Path::new("Missing.one"), u64::MAX).is_err());
+
+        assert_eq!(
+            source.read_dir(Path::new("")).unwrap(),
+            [PathBuf::from("Group"), PathBuf::from("Notebook.onetoc2")]
+        );
+        assert_eq!(
+            source.read_dir(Path::new("Group")).unwrap(),
+            [
+                PathBuf::from("Group/Notebook.onetoc2"),
+                PathBuf::from("Group/Section.one")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_limit() {
+        let mut source = MemorySource::new();
+        source.insert("Section.one", vec![0; 10]);
+
+        assert!(source.read(Path::new("Section.one"), 10).is_ok());
+        assert!(is_input_size_error(
+            source.read(Path::new("Section.one"), 9).unwrap_err()
+        ));
+
+        assert_eq!(read_limited(&[1, 2, 3][..], 3).unwrap(), [1, 2, 3]);
+
+        // An endless stream fails instead of exhausting the memory
+        assert!(is_input_size_error(
+            read_limited(std::io::repeat(0), 100).unwrap_err()
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use crate::one::property::color::Color;
 use crate::one::property::layout_alignment::LayoutAlignment;
 use crate::one::property::outline_indent_distance::OutlineIndentDistance;
 use crate::one::property_set::{table_cell_node, table_node, table_row_node};
+use crate::onenote::context::Context;
 use crate::onenote::note_tag::{NoteTag, parse_note_tags};
 use crate::onenote::outline::{OutlineElement, parse_outline_element};
 use crate::onestore::object_space::ObjectSpace;
@@ -164,17 +165,13 @@ impl TableCell {
     }
 }
 
-pub(crate) fn parse_table(table_id: ExGuid, space: &ObjectSpace) -> Result<Table> {
+pub(crate) fn parse_table(table_id: ExGuid, space: &ObjectSpace, ctx: &Context) -> Result<Table> {
     let table_object = space
         .get_object(table_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("table object is missing".into()))?;
     let data = table_node::parse(table_object)?;
 
-    let contents = data
-        .rows
-        .into_iter()
-        .map(|row_id| parse_row(row_id, space))
-        .collect::<Result<_>>()?;
+    let contents = ctx.collect("row", data.rows, |row_id| parse_row(row_id, space, ctx))?;
 
     let table = Table {
         rows: data.row_count,
@@ -191,34 +188,30 @@ pub(crate) fn parse_table(table_id: ExGuid, space: &ObjectSpace) -> Result<Table
     Ok(table)
 }
 
-fn parse_row(row_id: ExGuid, space: &ObjectSpace) -> Result<TableRow> {
+fn parse_row(row_id: ExGuid, space: &ObjectSpace, ctx: &Context) -> Result<TableRow> {
     let row_object = space
         .get_object(row_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("row object is missing".into()))?;
     let data = table_row_node::parse(row_object)?;
 
-    let contents = data
-        .cells
-        .into_iter()
-        .map(|cell_id| parse_cell(cell_id, space))
-        .collect::<Result<_>>()?;
+    let contents = ctx.collect("cell", data.cells, |cell_id| {
+        parse_cell(cell_id, space, ctx)
+    })?;
 
     let row = TableRow { contents };
 
     Ok(row)
 }
 
-fn parse_cell(cell_id: ExGuid, space: &ObjectSpace) -> Result<TableCell> {
+fn parse_cell(cell_id: ExGuid, space: &ObjectSpace, ctx: &Context) -> Result<TableCell> {
     let cell_object = space
         .get_object(cell_id)
         .ok_or_else(|| ErrorKind::MalformedOneNoteData("cell object is missing".into()))?;
     let data = table_cell_node::parse(cell_object)?;
 
-    let contents = data
-        .contents
-        .into_iter()
-        .map(|element_id| parse_outline_element(element_id, space))
-        .collect::<Result<_>>()?;
+    let contents = ctx.collect("element", data.contents, |element_id| {
+        ctx.nested(|| parse_outline_element(element_id, space, ctx))
+    })?;
 
     let cell = TableCell {
         contents,
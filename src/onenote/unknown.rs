@@ -0,0 +1,204 @@
+use crate::fsshttpb::data::exguid::ExGuid;
+use crate::one::property::PropertyType;
+use crate::one::property::simple;
+use crate::onenote::context::Context;
+use crate::onestore::object::Object;
+use crate::onestore::types::prop_set::PropertySet;
+use crate::onestore::types::property::PropertyValue;
+use std::collections::BTreeMap;
+
+/// Content of a type that the parser doesn't support.
+///
+/// The object's properties are preserved without interpreting them. This allows
+/// detecting content types that have been added in newer OneNote versions.
+#[derive(Clone, Debug)]
+pub struct UnknownContent {
+    pub(crate) jcid: u32,
+    pub(crate) object_id: String,
+    pub(crate) offset_horizontal: Option<f32>,
+    pub(crate) offset_vertical: Option<f32>,
+    pub(crate) properties: BTreeMap<u32, RawPropertyValue>,
+}
+
+impl UnknownContent {
+    /// The JCID that identifies the object's type.
+    ///
+    /// See [\[MS-ONESTORE\] 2.6.14].
+    ///
+    /// [\[MS-ONESTORE\] 2.6.14]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/388c266c-08e4-4ea4-af0e-5e2c5d1b995c
+    pub fn jcid(&self) -> u32 {
+        self.jcid
+    }
+
+    /// The ID of the object within its object space.
+    ///
+    /// The ID is formatted as `{GUID},n`.
+    pub fn object_id(&self) -> &str {
+        &self.object_id
+    }
+
+    /// The horizontal offset from the parent in half-inch increments.
+    ///
+    /// See [\[MS-ONE\] 2.3.18].
+    ///
+    /// [\[MS-ONE\] 2.3.18]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/5fb9e84a-c9e9-4537-ab14-e5512f24669a
+    pub fn offset_horizontal(&self) -> Option<f32> {
+        self.offset_horizontal
+    }
+
+    /// The vertical offset from the parent in half-inch increments.
+    ///
+    /// See [\[MS-ONE\] 2.3.19].
+    ///
+    /// [\[MS-ONE\] 2.3.19]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-one/5c4992ba-1db5-43e9-83dd-7299c562104d
+    pub fn offset_vertical(&self) -> Option<f32> {
+        self.offset_vertical
+    }
+
+    /// The object's properties by their property ID.
+    ///
+    /// The property IDs include the property type. See [\[MS-ONESTORE\] 2.6.6].
+    ///
+    /// [\[MS-ONESTORE\] 2.6.6]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/17d8c39e-6cc2-4fcd-8d10-aee950fd0ab2
+    pub fn properties(&self) -> &BTreeMap<u32, RawPropertyValue> {
+        &self.properties
+    }
+}
+
+/// An uninterpreted property value.
+///
+/// References to other objects only contain the number of references.
+///
+/// See [\[MS-ONESTORE\] 2.6.6].
+///
+/// [\[MS-ONESTORE\] 2.6.6]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/17d8c39e-6cc2-4fcd-8d10-aee950fd0ab2
+#[derive(Clone, Debug, PartialEq)]
+pub enum RawPropertyValue {
+    /// A property without data.
+    Empty,
+    /// A boolean.
+    Bool(bool),
+    /// An 8 bit integer.
+    U8(u8),
+    /// A 16 bit integer.
+    U16(u16),
+    /// A 32 bit integer.
+    U32(u32),
+    /// A 64 bit integer.
+    U64(u64),
+    /// A byte array.
+    Bytes(Vec<u8>),
+    /// A single object reference.
+    ObjectId,
+    /// An array of object references.
+    ObjectIds(u32),
+    /// A single object space reference.
+    ObjectSpaceId,
+    /// An array of object space references.
+    ObjectSpaceIds(u32),
+    /// A single context reference.
+    ContextId,
+    /// An array of context references.
+    ContextIds(u32),
+    /// An array of property sets with the given property ID.
+    PropertyValues(u32, Vec<BTreeMap<u32, RawPropertyValue>>),
+    /// A nested property set.
+    PropertySet(BTreeMap<u32, RawPropertyValue>),
+}
+
+impl RawPropertyValue {
+    fn from_value(value: &PropertyValue) -> RawPropertyValue {
+        match value {
+            PropertyValue::Empty => RawPropertyValue::Empty,
+            PropertyValue::Bool(v) => RawPropertyValue::Bool(*v),
+            PropertyValue::U8(v) => RawPropertyValue::U8(*v),
+            PropertyValue::U16(v) => RawPropertyValue::U16(*v),
+            PropertyValue::U32(v) => RawPropertyValue::U32(*v),
+            PropertyValue::U64(v) => RawPropertyValue::U64(*v),
+            PropertyValue::Vec(v) => RawPropertyValue::Bytes(v.clone()),
+            PropertyValue::ObjectId => RawPropertyValue::ObjectId,
+            PropertyValue::ObjectIds(c) => RawPropertyValue::ObjectIds(*c),
+            PropertyValue::ObjectSpaceId => RawPropertyValue::ObjectSpaceId,
+            PropertyValue::ObjectSpaceIds(c) => RawPropertyValue::ObjectSpaceIds(*c),
+            PropertyValue::ContextId => RawPropertyValue::ContextId,
+            PropertyValue::ContextIds(c) => RawPropertyValue::ContextIds(*c),
+            PropertyValue::PropertyValues(id, sets) => {
+                RawPropertyValue::PropertyValues(id.value(), sets.iter().map(raw_props).collect())
+            }
+            PropertyValue::PropertySet(set) => RawPropertyValue::PropertySet(raw_props(set)),
+        }
+    }
+}
+
+fn raw_props(set: &PropertySet) -> BTreeMap<u32, RawPropertyValue> {
+    set.entries()
+        .into_iter()
+        .map(|(id, value)| (id.value(), RawPropertyValue::from_value(value)))
+        .collect()
+}
+
+pub(crate) fn parse_unknown(object_id: ExGuid, object: &Object, ctx: &Context) -> UnknownContent {
+    let jcid = object.id().0;
+    ctx.record_unknown_jcid(jcid);
+
+    // The layout offsets are optional: a mismatching type is ignored rather
+    // than failing an object we don't understand anyway.
+    let offset_horizontal = simple::parse_f32(PropertyType::OffsetFromParentHoriz, object)
+        .ok()
+        .flatten();
+    let offset_vertical = simple::parse_f32(PropertyType::OffsetFromParentVert, object)
+        .ok()
+        .flatten();
+
+    UnknownContent {
+        jcid,
+        object_id: object_id.to_string(),
+        offset_horizontal,
+        offset_vertical,
+        properties: raw_props(object.props().properties()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawPropertyValue;
+    use crate::fsshttpb::writer::Value;
+    use crate::limits::ParseLimits;
+    use crate::one::property::PropertyType;
+    use crate::onenote::context::Context;
+    use crate::onenote::page_content::{PageContent, parse_page_content};
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, id, with_space};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_parse_unknown_page_content() {
+        let object = ObjectBuilder::with_jcid(id(1), 0x0006_0099)
+            .prop(
+                PropertyType::OffsetFromParentHoriz,
+                Value::U32(1.5f32.to_bits()),
+            )
+            .prop(PropertyType::TextRunIndex, Value::Vec(vec![1, 2]));
+
+        let ctx = Context::new(false, ParseLimits::default());
+        let content = with_space(SpaceBuilder::new(1).content_root(object), |space| {
+            parse_page_content(id(1), space, &ctx).unwrap()
+        });
+
+        let PageContent::Unknown(content) = content else {
+            panic!("expected unknown content");
+        };
+
+        assert_eq!(content.jcid(), 0x0006_0099);
+        assert_eq!(content.object_id(), id(1).to_string());
+        assert_eq!(content.offset_horizontal(), Some(1.5));
+        assert_eq!(content.offset_vertical(), None);
+        assert_eq!(
+            content
+                .properties()
+                .get(&(PropertyType::TextRunIndex as u32)),
+            Some(&RawPropertyValue::Bytes(vec![1, 2]))
+        );
+
+        assert_eq!(ctx.take_unknown_jcids(), BTreeMap::from([(0x0006_0099, 1)]));
+    }
+}
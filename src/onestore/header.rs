@@ -21,6 +21,11 @@ pub(crate) struct StoreHeader {
 }
 
 impl StoreHeader {
+    /// The GUID that identifies the file.
+    pub(crate) fn file_identity(&self) -> Guid {
+        self.file_identity
+    }
+
     pub(crate) fn parse(data: &ObjectGroup) -> Result<StoreHeader> {
         let (_, object_data) = data
             .declarations
@@ -38,7 +43,7 @@ impl StoreHeader {
             .into());
         };
 
-        let prop_set = ObjectPropSet::parse(&mut Reader::new(object_data.as_slice()))?;
+        let prop_set = ObjectPropSet::parse(&mut Reader::new(object_data))?;
 
         let file_identity = prop_set
             .get(PropertyType::FileIdentityGuid)
@@ -77,7 +82,7 @@ impl StoreHeader {
 
     fn parse_guid(value: &PropertyValue) -> Result<Guid> {
         if let PropertyValue::Vec(data) = &value {
-            Ok(Guid::parse(&mut Reader::new(data.as_slice()))?)
+            Ok(Guid::parse(&mut Reader::new(data))?)
         } else {
             Err(ErrorKind::MalformedOneStoreData("property is not a vec".into()).into())
         }
@@ -5,7 +5,7 @@ use crate::fsshttpb::data_element::storage_manifest::StorageManifest;
 use crate::fsshttpb::packaging::OneStorePackaging;
 use crate::onestore::header::StoreHeader;
 use crate::onestore::object_space::ObjectSpace;
-use crate::onestore::revision::Revision;
+use crate::onestore::revision::RevisionCache;
 use crate::shared::guid::Guid;
 use std::collections::{HashMap, HashSet};
 
@@ -16,6 +16,7 @@ pub(crate) mod object_space;
 pub(crate) mod revision;
 mod revision_role;
 pub(crate) mod types;
+pub(crate) mod version;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -38,48 +39,43 @@ impl<'a> OneStore<'a> {
     pub(crate) fn object_space(&'a self, space_id: CellId) -> Option<&'a ObjectSpace<'a>> {
         self.object_spaces.get(&space_id)
     }
+
+    #[cfg_attr(not(feature = "raw"), allow(dead_code))]
+    pub(crate) fn object_spaces(&self) -> impl Iterator<Item = (CellId, &ObjectSpace<'a>)> {
+        self.object_spaces.iter().map(|(id, space)| (*id, space))
+    }
 }
 
 pub(crate) fn parse_store(package: &OneStorePackaging) -> Result<OneStore<'_>> {
-    let mut parsed_object_spaces = HashSet::new();
+    parse_store_with(package, &HashSet::new(), |_, _| {})
+}
 
-    // [ONESTORE] 2.7.1: Parse storage manifest
-    let storage_index = package
-        .data_element_package
-        .find_storage_index_by_id(package.storage_index)
-        .or_else(|| package.data_element_package.find_storage_index())
-        .ok_or_else(|| ErrorKind::MalformedOneStoreData("storage index is missing".into()))?;
-    let storage_manifest = package
-        .data_element_package
-        .find_storage_manifest()
-        .ok_or_else(|| ErrorKind::MalformedOneStoreData("storage manifest is missing".into()))?;
+/// Parse the store without the object spaces in `skip`.
+///
+/// The number of processed cells and the total number of cells are reported
+/// to `progress`. The header and the data root are always parsed.
+pub(crate) fn parse_store_with<'a>(
+    package: &'a OneStorePackaging,
+    skip: &HashSet<CellId>,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<OneStore<'a>> {
+    let mut parsed_object_spaces = skip.clone();
 
-    let header_cell_id = find_header_cell_id(storage_manifest)?;
+    // [ONESTORE] 2.7.1: Parse storage manifest
+    let (storage_index, storage_manifest) = find_storage(package)?;
 
-    let header_cell_mapping_id = storage_index
-        .find_cell_mapping_id(header_cell_id)
-        .ok_or_else(|| {
-            ErrorKind::MalformedOneStoreData("header cell mapping id not found".into())
-        })?;
+    let total = storage_index.cell_mappings.len();
+    progress(0, total);
 
     // [ONESTORE] 2.7.2: Parse header cell
-    let header_cell = package
-        .data_element_package
-        .find_objects(header_cell_mapping_id, storage_index)?
-        .into_iter()
-        .next()
-        .ok_or_else(|| {
-            ErrorKind::MalformedOneStoreData("no header object in header cell".into())
-        })?;
-
-    let header = StoreHeader::parse(header_cell)?;
+    let header = parse_header(package, storage_index, storage_manifest)?;
 
-    parsed_object_spaces.insert(header_cell_id);
+    parsed_object_spaces.insert(find_header_cell_id(storage_manifest)?);
 
-    // Revision cache deduplicates already parsed revisions by cell id.
-    // Object spaces can reference revisions across cells; caching avoids re-parsing and
-    // preserves identity when multiple spaces share the same revision.
-    let mut revision_cache = HashMap::new();
+    // The revision cache deduplicates already parsed revisions and objects. Object spaces
+    // can share revisions and object groups; caching avoids re-parsing them and shares the
+    // parsed objects between all spaces that reference them.
+    let mut revision_cache = RevisionCache::new();
 
     // Parse data root
 
@@ -97,18 +93,14 @@ pub(crate) fn parse_store(package: &OneStorePackaging) -> Result<OneStore<'_>> {
 
     let mut object_spaces = HashMap::new();
 
-    for mapping in storage_index.cell_mappings.values() {
-        if mapping.id.is_nil() {
-            continue;
-        }
-
-        if parsed_object_spaces.contains(&mapping.cell_id) {
-            continue;
+    for (index, mapping) in storage_index.cell_mappings.values().enumerate() {
+        if !mapping.id.is_nil() && !parsed_object_spaces.contains(&mapping.cell_id) {
+            let (id, group) =
+                parse_object_space(mapping.cell_id, storage_index, package, &mut revision_cache)?;
+            object_spaces.insert(id, group);
         }
 
-        let (id, group) =
-            parse_object_space(mapping.cell_id, storage_index, package, &mut revision_cache)?;
-        object_spaces.insert(id, group);
+        progress(index + 1, total);
     }
 
     Ok(OneStore {
@@ -119,11 +111,52 @@ pub(crate) fn parse_store(package: &OneStorePackaging) -> Result<OneStore<'_>> {
     })
 }
 
+/// Find the storage index and storage manifest of the package.
+fn find_storage(package: &OneStorePackaging) -> Result<(&StorageIndex, &StorageManifest)> {
+    let storage_index = package
+        .data_element_package
+        .find_storage_index_by_id(package.storage_index)
+        .or_else(|| package.data_element_package.find_storage_index())
+        .ok_or_else(|| ErrorKind::MalformedOneStoreData("storage index is missing".into()))?;
+    let storage_manifest = package
+        .data_element_package
+        .find_storage_manifest()
+        .ok_or_else(|| ErrorKind::MalformedOneStoreData("storage manifest is missing".into()))?;
+
+    Ok((storage_index, storage_manifest))
+}
+
+/// Parse the header cell.
+fn parse_header(
+    package: &OneStorePackaging,
+    storage_index: &StorageIndex,
+    storage_manifest: &StorageManifest,
+) -> Result<StoreHeader> {
+    let header_cell_id = find_header_cell_id(storage_manifest)?;
+
+    let header_cell_mapping_id = storage_index
+        .find_cell_mapping_id(header_cell_id)
+        .ok_or_else(|| {
+            ErrorKind::MalformedOneStoreData("header cell mapping id not found".into())
+        })?;
+
+    let header_cell = package
+        .data_element_package
+        .find_objects(header_cell_mapping_id, storage_index)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            ErrorKind::MalformedOneStoreData("no header object in header cell".into())
+        })?;
+
+    StoreHeader::parse(header_cell)
+}
+
 fn parse_object_space<'a>(
     cell_id: CellId,
     storage_index: &'a StorageIndex,
     package: &'a OneStorePackaging,
-    revision_cache: &mut HashMap<CellId, Revision<'a>>,
+    revision_cache: &mut RevisionCache<'a>,
 ) -> Result<(CellId, ObjectSpace<'a>)> {
     let mapping = storage_index
         .cell_mappings
@@ -7,6 +7,7 @@ use crate::onestore::object_space::GroupData;
 use crate::onestore::types::jcid::JcId;
 use crate::onestore::types::object_prop_set::ObjectPropSet;
 use crate::reader::Reader;
+use bytes::Bytes;
 
 /// A OneNote data object.
 ///
@@ -14,13 +15,13 @@ use crate::reader::Reader;
 ///
 /// [\[MS-ONESTOR\] 2.1.5]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/ce60b62f-82e5-401a-bf2c-3255457732ad
 /// [\[MS-ONESTOR\] 2.7.6]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/b4270940-827e-468b-bf42-2c7afee23740
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(crate) struct Object<'a> {
     pub(crate) context_id: ExGuid,
 
     pub(crate) jc_id: JcId,
     pub(crate) props: ObjectPropSet,
-    pub(crate) file_data: Option<&'a [u8]>,
+    pub(crate) file_data: Option<&'a Bytes>,
     pub(crate) mapping: MappingTable,
 }
 
@@ -44,8 +45,14 @@ impl<'a> Object<'a> {
         &self.props
     }
 
+    #[cfg_attr(not(feature = "raw"), allow(dead_code))]
     pub(crate) fn file_data(&self) -> Option<&[u8]> {
-        self.file_data
+        self.file_data.map(|data| data.as_ref())
+    }
+
+    /// The file data as a buffer that shares the data with the file.
+    pub(crate) fn shared_file_data(&self) -> Option<Bytes> {
+        self.file_data.cloned()
     }
 
     pub(crate) fn mapping(&self) -> &MappingTable {
@@ -77,7 +84,7 @@ impl<'a> Object<'a> {
             .into());
         };
 
-        let jc_id = JcId::parse(&mut Reader::new(metadata.as_slice()))?;
+        let jc_id = JcId::parse(&mut Reader::new(metadata))?;
 
         // Parse data
 
@@ -91,7 +98,8 @@ impl<'a> Object<'a> {
                 .into());
             };
 
-        let props = ObjectPropSet::parse(&mut Reader::new(data.as_slice()))?;
+        let props =
+            ObjectPropSet::parse(&mut Reader::new(data)).map_err(|err| err.with_jcid(jc_id.0))?;
 
         // Parse file data
 
@@ -5,9 +5,10 @@ use crate::fsshttpb::data_element::object_group::ObjectGroupData;
 use crate::fsshttpb::data_element::storage_index::{StorageIndex, StorageIndexCellMapping};
 use crate::fsshttpb::packaging::OneStorePackaging;
 use crate::onestore::object::Object;
-use crate::onestore::revision::Revision;
+use crate::onestore::revision::RevisionCache;
 use crate::onestore::revision_role::RevisionRole;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 pub(crate) type GroupData<'a> = HashMap<(ExGuid, u64), &'a ObjectGroupData>;
 
@@ -24,12 +25,27 @@ pub(crate) struct ObjectSpace<'a> {
     id: ExGuid,
     context: ExGuid,
     roots: HashMap<RevisionRole, ExGuid>,
-    objects: HashMap<ExGuid, Object<'a>>,
+    objects: HashMap<ExGuid, Arc<Object<'a>>>,
 }
 
 impl<'a, 'b> ObjectSpace<'a> {
+    #[cfg_attr(not(feature = "raw"), allow(dead_code))]
+    pub(crate) fn id(&self) -> ExGuid {
+        self.id
+    }
+
+    #[cfg_attr(not(feature = "raw"), allow(dead_code))]
+    pub(crate) fn context(&self) -> ExGuid {
+        self.context
+    }
+
+    #[cfg_attr(not(feature = "raw"), allow(dead_code))]
+    pub(crate) fn objects(&self) -> impl Iterator<Item = (ExGuid, &Object<'a>)> {
+        self.objects.iter().map(|(id, object)| (*id, &**object))
+    }
+
     pub(crate) fn get_object(&self, id: ExGuid) -> Option<&Object<'_>> {
-        self.objects.get(&id)
+        self.objects.get(&id).map(|object| &**object)
     }
 
     pub(crate) fn content_root(&self) -> Option<ExGuid> {
@@ -44,7 +60,7 @@ impl<'a, 'b> ObjectSpace<'a> {
         mapping: &'a StorageIndexCellMapping,
         storage_index: &'a StorageIndex,
         packaging: &'a OneStorePackaging,
-        revision_cache: &'b mut HashMap<CellId, Revision<'a>>,
+        revision_cache: &'b mut RevisionCache<'a>,
     ) -> Result<(CellId, ObjectSpace<'a>)> {
         let cell_id = mapping.cell_id;
 
@@ -80,21 +96,24 @@ impl<'a, 'b> ObjectSpace<'a> {
         let mut objects = HashMap::new();
         let mut roots = HashMap::new();
 
+        let mut visited = HashSet::new();
         let mut rev_id = Some(revision_manifest_id);
 
         while let Some(revision_manifest_id) = rev_id {
-            let base_rev_id = Revision::parse(
-                revision_manifest_id,
-                context_id,
-                object_space_id,
-                storage_index,
-                packaging,
-                revision_cache,
-                &mut objects,
-                &mut roots,
-            )?;
-
-            rev_id = base_rev_id;
+            if !visited.insert(revision_manifest_id) {
+                return Err(ErrorKind::MalformedOneStoreData(
+                    "revision chain contains a cycle".into(),
+                )
+                .into());
+            }
+
+            let revision =
+                revision_cache.revision(revision_manifest_id, storage_index, packaging)?;
+
+            roots.extend(revision.roots().iter().copied());
+            revision_cache.add_objects(&revision, cell_id, packaging, &mut objects)?;
+
+            rev_id = revision.base_rev();
         }
 
         let space = ObjectSpace {
@@ -7,30 +7,50 @@ use crate::onestore::object::Object;
 use crate::onestore::object_space::GroupData;
 use crate::onestore::revision_role::RevisionRole;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
 
 /// A OneNote file revision.
 ///
+/// A revision only stores references to its object groups; the objects themselves are
+/// stored in the [`RevisionCache`] so they can be shared with other revisions.
+///
 /// See [\[MS-ONESTOR\] 2.1.8]
 ///
 /// [\[MS-ONESTOR\] 2.1.8]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/a8ca2a90-d92a-4cf7-bf68-ed18ae476a11
-#[derive(Debug, Clone)]
-pub(crate) struct Revision<'a> {
-    objects: HashMap<ExGuid, Object<'a>>,
-    roots: HashMap<RevisionRole, ExGuid>,
+#[derive(Debug)]
+pub(crate) struct Revision {
+    base_rev: Option<ExGuid>,
+    roots: Vec<(RevisionRole, ExGuid)>,
+    group_references: Vec<ExGuid>,
+}
+
+/// Already parsed revisions and objects.
+///
+/// Revisions are cached by their revision manifest ID. Objects are cached by the object space
+/// and object group they were parsed from, so an object is parsed at most once and shared by
+/// all revisions that reference its object group.
+#[derive(Debug, Default)]
+pub(crate) struct RevisionCache<'a> {
+    revisions: HashMap<ExGuid, Arc<Revision>>,
+    objects: HashMap<(CellId, ExGuid, ExGuid), Arc<Object<'a>>>,
 }
 
-impl<'a, 'b> Revision<'a> {
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn parse(
+impl Revision {
+    /// The revision manifest ID of the base revision.
+    pub(crate) fn base_rev(&self) -> Option<ExGuid> {
+        self.base_rev
+    }
+
+    pub(crate) fn roots(&self) -> &[(RevisionRole, ExGuid)] {
+        &self.roots
+    }
+
+    fn parse(
         revision_manifest_id: ExGuid,
-        context_id: ExGuid,
-        object_space_id: ExGuid,
-        storage_index: &'a StorageIndex,
-        packaging: &'a OneStorePackaging,
-        revision_cache: &'b mut HashMap<CellId, Revision<'a>>,
-        objects: &'b mut HashMap<ExGuid, Object<'a>>,
-        roots: &'b mut HashMap<RevisionRole, ExGuid>,
-    ) -> Result<Option<ExGuid>> {
+        storage_index: &StorageIndex,
+        packaging: &OneStorePackaging,
+    ) -> Result<Revision> {
         let revision_manifest = packaging
             .data_element_package
             .find_revision_manifest(revision_manifest_id)
@@ -51,68 +71,112 @@ impl<'a, 'b> Revision<'a> {
             })
             .transpose()?;
 
-        if let Some(rev) = revision_cache.get(&CellId(context_id, revision_manifest.rev_id)) {
-            roots.extend(rev.roots.iter());
-            objects.extend(rev.objects.clone());
+        let roots = revision_manifest
+            .root_declare
+            .iter()
+            .map(|root| Ok((RevisionRole::parse(root.root_id)?, root.object_id)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Revision {
+            base_rev,
+            roots,
+            group_references: revision_manifest.group_references.clone(),
+        })
+    }
+}
+
+impl<'a> RevisionCache<'a> {
+    pub(crate) fn new() -> RevisionCache<'a> {
+        RevisionCache::default()
+    }
 
-            return Ok(base_rev);
+    /// Look up or parse the revision with the given revision manifest ID.
+    pub(crate) fn revision(
+        &mut self,
+        revision_manifest_id: ExGuid,
+        storage_index: &StorageIndex,
+        packaging: &OneStorePackaging,
+    ) -> Result<Arc<Revision>> {
+        if let Some(revision) = self.revisions.get(&revision_manifest_id) {
+            return Ok(revision.clone());
         }
 
-        roots.extend(
-            revision_manifest
-                .root_declare
-                .iter()
-                .map(|root| Ok((RevisionRole::parse(root.root_id)?, root.object_id)))
-                .collect::<Result<Vec<_>>>()?,
-        );
+        let revision = Arc::new(Revision::parse(
+            revision_manifest_id,
+            storage_index,
+            packaging,
+        )?);
+        self.revisions
+            .insert(revision_manifest_id, revision.clone());
+
+        Ok(revision)
+    }
 
-        for group_id in revision_manifest.group_references.iter() {
-            Self::parse_group(context_id, *group_id, object_space_id, packaging, objects)?
+    /// Add the revision's objects to `objects`.
+    ///
+    /// Objects that are already present have been overwritten by a newer revision and are
+    /// skipped.
+    pub(crate) fn add_objects(
+        &mut self,
+        revision: &Revision,
+        space_id: CellId,
+        packaging: &'a OneStorePackaging,
+        objects: &mut HashMap<ExGuid, Arc<Object<'a>>>,
+    ) -> Result<()> {
+        for group_id in revision.group_references.iter() {
+            self.add_group(space_id, *group_id, packaging, objects)
+                .map_err(|err| err.with_data_element(*group_id))?
         }
 
-        Ok(base_rev)
+        Ok(())
     }
 
-    fn parse_group(
-        context_id: ExGuid,
+    fn add_group(
+        &mut self,
+        space_id: CellId,
         group_id: ExGuid,
-        object_space_id: ExGuid,
         packaging: &'a OneStorePackaging,
-        objects: &'b mut HashMap<ExGuid, Object<'a>>,
+        objects: &mut HashMap<ExGuid, Arc<Object<'a>>>,
     ) -> Result<()> {
         let group = packaging
             .data_element_package
             .find_object_group(group_id)
             .ok_or_else(|| ErrorKind::MalformedOneStoreData("object group not found".into()))?;
 
-        let object_ids: Vec<_> = group.declarations.iter().map(|o| o.object_id()).collect();
+        let mut group_objects: Option<GroupData> = None;
 
-        let group_objects: GroupData = group
-            .declarations
-            .iter()
-            .zip(group.objects.iter())
-            .map(|(decl, data)| ((decl.object_id(), decl.partition_id()), data))
-            .collect();
+        for decl in group.declarations.iter() {
+            let object_id = decl.object_id();
 
-        for object_id in object_ids {
             if objects.contains_key(&object_id) {
                 continue;
             }
 
-            if group.declarations.len() != group.objects.len() {
-                return Err(ErrorKind::MalformedOneStoreData(
-                    "object declaration/data counts do not match".into(),
-                )
-                .into());
-            }
-
-            let object = Object::parse(
-                object_id,
-                context_id,
-                object_space_id,
-                &group_objects,
-                packaging,
-            )?;
+            let object = match self.objects.entry((space_id, group_id, object_id)) {
+                Entry::Occupied(entry) => entry.get().clone(),
+                Entry::Vacant(entry) => {
+                    if group.declarations.len() != group.objects.len() {
+                        return Err(ErrorKind::MalformedOneStoreData(
+                            "object declaration/data counts do not match".into(),
+                        )
+                        .into());
+                    }
+
+                    let group_objects = group_objects.get_or_insert_with(|| {
+                        group
+                            .declarations
+                            .iter()
+                            .zip(group.objects.iter())
+                            .map(|(decl, data)| ((decl.object_id(), decl.partition_id()), data))
+                            .collect()
+                    });
+
+                    let object =
+                        Object::parse(object_id, space_id.0, space_id.1, group_objects, packaging)?;
+
+                    entry.insert(Arc::new(object)).clone()
+                }
+            };
 
             objects.insert(object_id, object);
         }
@@ -120,3 +184,38 @@ impl<'a, 'b> Revision<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RevisionCache;
+    use crate::fsshttpb::packaging::OneStorePackaging;
+    use crate::one::property_set::PropertySetId;
+    use crate::onestore::object_space::ObjectSpace;
+    use crate::reader::Reader;
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, StoreBuilder, id};
+
+    #[test]
+    fn test_revision_cache_shares_objects() {
+        let space =
+            SpaceBuilder::new(1).content_root(ObjectBuilder::new(id(1), PropertySetId::PageNode));
+        let cell_id = space.cell_id();
+        let data = StoreBuilder::section(space).build();
+
+        let packaging = OneStorePackaging::parse(&mut Reader::new(&data)).unwrap();
+        let storage_index = packaging.data_element_package.find_storage_index().unwrap();
+        let mapping = &storage_index.cell_mappings[&cell_id];
+
+        let mut cache = RevisionCache::new();
+        let (_, first) =
+            ObjectSpace::parse(mapping, storage_index, &packaging, &mut cache).unwrap();
+        let (_, second) =
+            ObjectSpace::parse(mapping, storage_index, &packaging, &mut cache).unwrap();
+
+        assert_eq!(cache.revisions.len(), 1);
+        assert_eq!(cache.objects.len(), 1);
+        assert!(std::ptr::eq(
+            first.get_object(id(1)).unwrap(),
+            second.get_object(id(1)).unwrap()
+        ));
+    }
+}
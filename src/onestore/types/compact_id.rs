@@ -24,6 +24,7 @@ impl CompactId {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::CompactId;
     use crate::reader::Reader;
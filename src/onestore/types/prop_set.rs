@@ -42,6 +42,24 @@ impl PropertySet {
         self.values.values().map(|(_, value)| value)
     }
 
+    /// The property IDs and values in the order they are stored in the file.
+    ///
+    /// The property IDs are restored from the IDs and the types of the values.
+    pub(crate) fn entries(&self) -> Vec<(PropertyId, &PropertyValue)> {
+        let mut entries: Vec<_> = self.values.iter().collect();
+        entries.sort_by_key(|(_, (index, _))| *index);
+
+        entries
+            .into_iter()
+            .map(|(id, (_, value))| {
+                let flag = matches!(value, PropertyValue::Bool(true)) as u32;
+                let id = id | value.prop_type() << 26 | flag << 31;
+
+                (PropertyId::new(id), value)
+            })
+            .collect()
+    }
+
     pub(crate) fn values_with_index(&self) -> impl Iterator<Item = &(usize, PropertyValue)> {
         self.values.values()
     }
@@ -113,6 +113,27 @@ impl PropertyValue {
     //     }
     // }
 
+    /// The property type that is encoded in the property ID.
+    pub(crate) fn prop_type(&self) -> u32 {
+        match self {
+            PropertyValue::Empty => 0x1,
+            PropertyValue::Bool(_) => 0x2,
+            PropertyValue::U8(_) => 0x3,
+            PropertyValue::U16(_) => 0x4,
+            PropertyValue::U32(_) => 0x5,
+            PropertyValue::U64(_) => 0x6,
+            PropertyValue::Vec(_) => 0x7,
+            PropertyValue::ObjectId => 0x8,
+            PropertyValue::ObjectIds(_) => 0x9,
+            PropertyValue::ObjectSpaceId => 0xA,
+            PropertyValue::ObjectSpaceIds(_) => 0xB,
+            PropertyValue::ContextId => 0xC,
+            PropertyValue::ContextIds(_) => 0xD,
+            PropertyValue::PropertyValues(..) => 0x10,
+            PropertyValue::PropertySet(_) => 0x11,
+        }
+    }
+
     pub(crate) fn parse(property_id: PropertyId, reader: Reader) -> Result<PropertyValue> {
         let prop_type = property_id.prop_type();
 
@@ -150,7 +171,7 @@ impl PropertyValue {
 
     fn parse_vec(reader: Reader) -> Result<PropertyValue> {
         let size = reader.get_u32()?;
-        let data = reader.read(size as usize)?.to_vec();
+        let data = reader.read_vec(u64::from(size))?;
 
         Ok(PropertyValue::Vec(data))
     }
@@ -213,6 +234,7 @@ impl fmt::Debug for PropertyId {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use super::{PropertyId, PropertyValue};
     use crate::reader::Reader;
@@ -0,0 +1,118 @@
+use crate::errors::Result;
+use crate::fsshttpb::data::cell_id::CellId;
+use crate::fsshttpb::data::exguid::ExGuid;
+use crate::fsshttpb::packaging::OneStorePackaging;
+use crate::onestore::{find_storage, parse_header};
+use crate::shared::guid::Guid;
+use std::collections::HashMap;
+
+/// The version of a OneStore file and each of its cells.
+///
+/// Versions are compared to find the object spaces that have not changed since
+/// a file was parsed before. A cell is unchanged if it belongs to a file with
+/// the same file identity and has the same cell manifest, current revision and
+/// serial number. Cells without a serial number are always considered changed.
+///
+/// See [\[MS-ONESTORE\] 2.7.2] and [\[MS-FSSHTTPB\] 2.2.1.12.2].
+///
+/// [\[MS-ONESTORE\] 2.7.2]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/07a5dc4d-0d97-4a4c-ab69-aa7957d7115c
+/// [\[MS-FSSHTTPB\] 2.2.1.12.2]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/f5724986-bd0f-488d-9b85-7d5f954d8e9a
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StoreVersion {
+    file_identity: Guid,
+    cells: HashMap<CellId, CellVersion>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct CellVersion {
+    cell_manifest: ExGuid,
+    revision: Option<ExGuid>,
+    serial: Option<(Guid, u64)>,
+}
+
+impl StoreVersion {
+    pub(crate) fn parse(package: &OneStorePackaging) -> Result<StoreVersion> {
+        let (storage_index, storage_manifest) = find_storage(package)?;
+        let header = parse_header(package, storage_index, storage_manifest)?;
+
+        let cells = storage_index
+            .cell_mappings
+            .values()
+            .map(|mapping| {
+                let version = CellVersion {
+                    cell_manifest: mapping.id,
+                    revision: package
+                        .data_element_package
+                        .find_cell_revision_id(mapping.id),
+                    serial: (!mapping.serial.guid.is_nil())
+                        .then_some((mapping.serial.guid, mapping.serial.serial)),
+                };
+
+                (mapping.cell_id, version)
+            })
+            .collect();
+
+        Ok(StoreVersion {
+            file_identity: header.file_identity(),
+            cells,
+        })
+    }
+
+    /// Whether the cell is unchanged since the `previous` version.
+    pub(crate) fn is_cell_unchanged(&self, previous: &StoreVersion, cell_id: CellId) -> bool {
+        if self.file_identity != previous.file_identity {
+            return false;
+        }
+
+        match (self.cells.get(&cell_id), previous.cells.get(&cell_id)) {
+            (Some(current), Some(previous)) => current.serial.is_some() && current == previous,
+            _ => false,
+        }
+    }
+
+    /// Whether all cells are unchanged since the `previous` version.
+    pub(crate) fn is_unchanged(&self, previous: &StoreVersion) -> bool {
+        self.cells.len() == previous.cells.len()
+            && self
+                .cells
+                .keys()
+                .all(|cell_id| self.is_cell_unchanged(previous, *cell_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StoreVersion;
+    use crate::fsshttpb::packaging::OneStorePackaging;
+    use crate::reader::Reader;
+    use crate::test_support::{SpaceBuilder, StoreBuilder};
+
+    fn version(store: StoreBuilder) -> StoreVersion {
+        let data = store.build();
+        let packaging = OneStorePackaging::parse(&mut Reader::new(&data)).unwrap();
+
+        StoreVersion::parse(&packaging).unwrap()
+    }
+
+    #[test]
+    fn test_store_version() {
+        let page = SpaceBuilder::new(20).cell_id();
+        let store = |serial| {
+            StoreBuilder::section(SpaceBuilder::new(1).serial(1))
+                .space(SpaceBuilder::new(20).serial(serial))
+        };
+
+        let previous = version(store(1));
+        assert!(version(store(1)).is_unchanged(&previous));
+        assert!(version(store(1)).is_cell_unchanged(&previous, page));
+
+        let current = version(store(2));
+        assert!(!current.is_unchanged(&previous));
+        assert!(!current.is_cell_unchanged(&previous, page));
+        assert!(current.is_cell_unchanged(&previous, SpaceBuilder::new(1).cell_id()));
+
+        // Cells without serial numbers can't be compared.
+        let unversioned = version(StoreBuilder::section(SpaceBuilder::new(1)));
+        assert!(!unversioned.is_unchanged(&unversioned.clone()));
+    }
+}
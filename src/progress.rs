@@ -0,0 +1,178 @@
+use crate::errors::{ErrorKind, Result};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// A phase of parsing a file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Phase {
+    /// Parsing the FSSHTTPB packaging of a file. The progress is counted in bytes.
+    Packaging,
+    /// Resolving the object spaces of a file. The progress is counted in object spaces.
+    Store,
+    /// Parsing the sections and section groups of a notebook or section group.
+    /// The progress is counted in table of contents entries.
+    Sections,
+    /// Parsing the pages of a section. The progress is counted in pages.
+    Pages,
+}
+
+/// A progress update while parsing.
+///
+/// Every phase starts with an update where [`Progress::completed`] is zero.
+#[derive(Debug, Copy, Clone)]
+pub struct Progress<'a> {
+    pub(crate) phase: Phase,
+    pub(crate) file: &'a Path,
+    pub(crate) completed: u64,
+    pub(crate) total: u64,
+}
+
+impl Progress<'_> {
+    /// The current phase.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// The file that is being parsed.
+    ///
+    /// This is the table of contents file for [`Phase::Sections`] and the
+    /// section file otherwise.
+    pub fn file(&self) -> &Path {
+        self.file
+    }
+
+    /// The number of completed items of the phase.
+    pub fn completed(&self) -> u64 {
+        self.completed
+    }
+
+    /// The total number of items of the phase.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+/// A token to cancel parsing from another thread.
+///
+/// The parser checks the token between sections and pages. Once the token has
+/// been cancelled, parsing fails with [`ErrorKind::Cancelled`], even in
+/// lenient mode.
+///
+/// ```
+/// use onenote_parser::{CancellationToken, Parser};
+///
+/// let token = CancellationToken::new();
+/// let parser = Parser::new().with_cancellation(token.clone());
+///
+/// // E.g. from a GUI thread:
+/// token.cancel();
+/// ```
+///
+/// [`ErrorKind::Cancelled`]: crate::errors::ErrorKind::Cancelled
+#[derive(Debug, Default, Clone)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that hasn't been cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel parsing.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type Observer = dyn Fn(&Progress) + Send + Sync;
+
+/// Reports progress to the observer and checks for cancellation.
+#[derive(Clone, Default)]
+pub(crate) struct Monitor {
+    observer: Option<Arc<Observer>>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Monitor {
+    pub(crate) fn with_observer(mut self, observer: Arc<Observer>) -> Monitor {
+        self.observer = Some(observer);
+        self
+    }
+
+    pub(crate) fn with_cancellation(mut self, token: CancellationToken) -> Monitor {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Fail with [`ErrorKind::Cancelled`] if parsing has been cancelled.
+    pub(crate) fn check(&self) -> Result<()> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(ErrorKind::Cancelled.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn report(&self, phase: Phase, file: &Path, completed: usize, total: usize) {
+        if let Some(observer) = &self.observer {
+            observer(&Progress {
+                phase,
+                file,
+                completed: completed as u64,
+                total: total as u64,
+            });
+        }
+    }
+
+    /// Start a phase whose items can complete in any order.
+    pub(crate) fn counter<'a>(&'a self, phase: Phase, file: &'a Path, total: usize) -> Counter<'a> {
+        self.report(phase, file, 0, total);
+
+        Counter {
+            monitor: self,
+            phase,
+            file,
+            completed: AtomicUsize::new(0),
+            total,
+        }
+    }
+}
+
+impl fmt::Debug for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Monitor")
+            .field("observer", &self.observer.is_some())
+            .field("cancellation", &self.cancellation)
+            .finish()
+    }
+}
+
+/// Counts the completed items of a phase.
+pub(crate) struct Counter<'a> {
+    monitor: &'a Monitor,
+    phase: Phase,
+    file: &'a Path,
+    completed: AtomicUsize,
+    total: usize,
+}
+
+impl Counter<'_> {
+    /// Check for cancellation before parsing the next item.
+    pub(crate) fn check(&self) -> Result<()> {
+        self.monitor.check()
+    }
+
+    /// Record that an item has been completed.
+    pub(crate) fn complete(&self) {
+        let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+        self.monitor
+            .report(self.phase, self.file, completed, self.total);
+    }
+}
@@ -0,0 +1,1053 @@
+//! Read-only access to the low-level file structure.
+//!
+//! This module exposes the FSSHTTPB data elements, the OneStore object spaces
+//! and the objects with their raw property values. It is intended for tooling
+//! and for debugging files that fail to parse or contain content the
+//! high-level API ignores.
+//!
+//! The types in this module mirror the internal parser structures and are not
+//! covered by semantic versioning.
+//!
+//! ```no_run
+//! use onenote_parser::raw::RawFile;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let data = std::fs::read("Section.one")?;
+//! let file = RawFile::parse(&data)?;
+//! let store = file.store()?;
+//!
+//! for (_, object) in store.data_root().objects() {
+//!     println!("0x{:08X}: {} properties", object.jcid(), object.properties().len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::errors::Result;
+use crate::fsshttpb::data::serial_number::SerialNumber;
+use crate::fsshttpb::data_element::storage_index::StorageIndex as StorageIndexData;
+use crate::fsshttpb::data_element::storage_manifest::StorageManifest as StorageManifestData;
+use crate::fsshttpb::packaging::{FileVersions as FileVersionsData, OneStorePackaging};
+use crate::limits::{Budget, ParseLimits};
+use crate::one::property::PropertyType;
+use crate::one::property_set::PropertySetId;
+use crate::onestore::object::Object as ObjectData;
+use crate::onestore::object_space::ObjectSpace as ObjectSpaceData;
+use crate::onestore::types::jcid::JcId;
+use crate::onestore::types::prop_set::PropertySet;
+use crate::onestore::types::property::PropertyValue as PropertyValueData;
+use crate::onestore::{OneStore, parse_store};
+use crate::reader::Reader;
+
+pub use crate::fsshttpb::data::cell_id::CellId;
+pub use crate::fsshttpb::data::exguid::ExGuid;
+pub use crate::shared::guid::Guid;
+
+/// A OneNote file's FSSHTTPB package.
+///
+/// See [\[MS-ONESTORE\] 2.8.1]
+///
+/// [\[MS-ONESTORE\] 2.8.1]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/a2f046ea-109a-49c4-912d-dc2888cf0565
+#[derive(Debug)]
+pub struct RawFile {
+    packaging: OneStorePackaging,
+}
+
+impl RawFile {
+    /// Parse a `.one` or `.onetoc2` file using the default [`ParseLimits`].
+    pub fn parse(data: &[u8]) -> Result<RawFile> {
+        RawFile::parse_with_limits(data, ParseLimits::default())
+    }
+
+    /// Parse a `.one` or `.onetoc2` file using the given resource limits.
+    pub fn parse_with_limits(data: &[u8], limits: ParseLimits) -> Result<RawFile> {
+        limits.check_input_size(data.len())?;
+
+        let budget = Budget::new(limits);
+        let mut reader = Reader::with_budget(data, &budget);
+        let packaging = OneStorePackaging::parse(&mut reader)
+            .map_err(|err| err.with_offset(reader.offset()))?;
+
+        Ok(RawFile { packaging })
+    }
+
+    /// The file type GUID.
+    pub fn file_type(&self) -> Guid {
+        self.packaging.file_type
+    }
+
+    /// The file's identity GUID.
+    pub fn file(&self) -> Guid {
+        self.packaging.file
+    }
+
+    /// The legacy file version GUID.
+    pub fn legacy_file_version(&self) -> Guid {
+        self.packaging.legacy_file_version
+    }
+
+    /// The file format GUID.
+    pub fn file_format(&self) -> Guid {
+        self.packaging.file_format
+    }
+
+    /// The ID of the storage index the package header points to.
+    pub fn storage_index_id(&self) -> ExGuid {
+        self.packaging.storage_index
+    }
+
+    /// The cell schema GUID.
+    pub fn cell_schema(&self) -> Guid {
+        self.packaging.cell_schema
+    }
+
+    /// The format versions from the revision store file header.
+    ///
+    /// Files that only contain a FSSHTTPB package don't have a revision store header.
+    pub fn versions(&self) -> Option<FileVersions> {
+        self.packaging.versions.map(|data| FileVersions { data })
+    }
+
+    /// The package's data elements in the order they are stored in the file.
+    pub fn data_elements(&self) -> Vec<DataElement> {
+        let package = &self.packaging.data_element_package;
+
+        package
+            .spans
+            .iter()
+            .filter_map(|span| {
+                let id = span.id;
+                let kind = if package.storage_indexes.contains_key(&id) {
+                    DataElementKind::StorageIndex
+                } else if package.storage_manifests.contains_key(&id) {
+                    DataElementKind::StorageManifest
+                } else if package.cell_manifests.contains_key(&id) {
+                    DataElementKind::CellManifest
+                } else if package.revision_manifests.contains_key(&id) {
+                    DataElementKind::RevisionManifest
+                } else if package.object_groups.contains_key(&id) {
+                    DataElementKind::ObjectGroup
+                } else if package.data_element_fragments.contains_key(&id) {
+                    DataElementKind::DataElementFragment
+                } else if package.object_data_blobs.contains_key(&id) {
+                    DataElementKind::ObjectDataBlob
+                } else {
+                    return None;
+                };
+
+                Some(DataElement {
+                    id,
+                    kind,
+                    offset: span.offset,
+                    size: span.size,
+                })
+            })
+            .collect()
+    }
+
+    /// The storage index used to resolve cells and revisions.
+    pub fn storage_index(&self) -> Option<StorageIndex<'_>> {
+        let package = &self.packaging.data_element_package;
+
+        package
+            .find_storage_index_by_id(self.packaging.storage_index)
+            .or_else(|| package.find_storage_index())
+            .map(|data| StorageIndex { data })
+    }
+
+    /// The storage manifest declaring the file's root cells.
+    pub fn storage_manifest(&self) -> Option<StorageManifest<'_>> {
+        self.packaging
+            .data_element_package
+            .find_storage_manifest()
+            .map(|data| StorageManifest { data })
+    }
+
+    /// The cell manifests and the revisions they point to.
+    pub fn cell_manifests(&self) -> Vec<CellManifest> {
+        let mut manifests: Vec<_> = self
+            .packaging
+            .data_element_package
+            .cell_manifests
+            .iter()
+            .map(|(id, revision_id)| CellManifest {
+                id: *id,
+                revision_id: *revision_id,
+            })
+            .collect();
+        manifests.sort_by_key(|manifest| sort_key(manifest.id));
+
+        manifests
+    }
+
+    /// The revision manifests.
+    pub fn revision_manifests(&self) -> Vec<RevisionManifest> {
+        let mut manifests: Vec<_> = self
+            .packaging
+            .data_element_package
+            .revision_manifests
+            .iter()
+            .map(|(id, manifest)| RevisionManifest {
+                id: *id,
+                rev_id: manifest.rev_id,
+                base_rev_id: manifest.base_rev_id.as_option(),
+                roots: manifest
+                    .root_declare
+                    .iter()
+                    .map(|root| (root.root_id, root.object_id))
+                    .collect(),
+                group_references: manifest.group_references.clone(),
+            })
+            .collect();
+        manifests.sort_by_key(|manifest| sort_key(manifest.id));
+
+        manifests
+    }
+
+    /// The revision manifests that make up a cell's revision chain.
+    ///
+    /// The chain starts at the cell's current revision and follows the base
+    /// revisions. It ends early at the first revision that can't be resolved.
+    pub fn revision_chain(&self, cell_id: CellId) -> Vec<ExGuid> {
+        let package = &self.packaging.data_element_package;
+        let Some(index) = self.storage_index().map(|index| index.data) else {
+            return vec![];
+        };
+        let Some(mapping) = index.cell_mappings.get(&cell_id) else {
+            return vec![];
+        };
+
+        let mut chain = vec![];
+        let mut next = package
+            .resolve_cell_revision_manifest_id(index, mapping.id)
+            .or_else(|| index.find_revision_mapping_by_serial(&mapping.serial));
+
+        while let Some(id) = next {
+            if chain.contains(&id) {
+                break;
+            }
+            chain.push(id);
+
+            next = package
+                .find_revision_manifest(id)
+                .and_then(|manifest| manifest.base_rev_id.as_option())
+                .and_then(|base_id| package.resolve_revision_manifest_id(index, base_id));
+        }
+
+        chain
+    }
+
+    /// Resolve the file's object spaces and objects.
+    ///
+    /// This fails if the OneStore structure of the file is malformed. The data
+    /// elements can still be inspected in that case.
+    pub fn store(&self) -> Result<Store<'_>> {
+        parse_store(&self.packaging).map(|store| Store { store })
+    }
+}
+
+/// The format versions from a revision store file header.
+///
+/// See [\[MS-ONESTORE\] 2.8.1]
+///
+/// [\[MS-ONESTORE\] 2.8.1]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/a2f046ea-109a-49c4-912d-dc2888cf0565
+#[derive(Debug, Clone, Copy)]
+pub struct FileVersions {
+    data: FileVersionsData,
+}
+
+impl FileVersions {
+    /// The version of the application that last wrote to the file.
+    pub fn last_writer(&self) -> u32 {
+        self.data.last_writer
+    }
+
+    /// The oldest version of an application that wrote to the file.
+    pub fn oldest_writer(&self) -> u32 {
+        self.data.oldest_writer
+    }
+
+    /// The newest version of an application that wrote to the file.
+    pub fn newest_writer(&self) -> u32 {
+        self.data.newest_writer
+    }
+
+    /// The oldest version of an application that can read the file.
+    pub fn oldest_reader(&self) -> u32 {
+        self.data.oldest_reader
+    }
+}
+
+/// The type of a data element.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12.1]
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.12.1]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/f0901ac0-4f26-413f-805b-a6830781f64c
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataElementKind {
+    /// A storage index.
+    StorageIndex,
+    /// A storage manifest.
+    StorageManifest,
+    /// A cell manifest.
+    CellManifest,
+    /// A revision manifest.
+    RevisionManifest,
+    /// An object group.
+    ObjectGroup,
+    /// A data element fragment.
+    DataElementFragment,
+    /// An object data blob.
+    ObjectDataBlob,
+}
+
+/// A data element in the package.
+#[derive(Debug, Clone, Copy)]
+pub struct DataElement {
+    id: ExGuid,
+    kind: DataElementKind,
+    offset: usize,
+    size: usize,
+}
+
+impl DataElement {
+    /// The data element's ID.
+    pub fn id(&self) -> ExGuid {
+        self.id
+    }
+
+    /// The data element's type.
+    pub fn kind(&self) -> DataElementKind {
+        self.kind
+    }
+
+    /// The data element's offset relative to the start of the data element
+    /// package.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The data element's size in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// A storage index.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12.2]
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.12.2]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/f5724986-bd0f-488d-9b85-7d5f954d8e9a
+#[derive(Debug, Clone, Copy)]
+pub struct StorageIndex<'a> {
+    data: &'a StorageIndexData,
+}
+
+impl<'a> StorageIndex<'a> {
+    /// The storage manifest mappings.
+    pub fn manifest_mappings(&self) -> Vec<ManifestMapping> {
+        self.data
+            .manifest_mappings
+            .iter()
+            .map(|mapping| ManifestMapping {
+                manifest_id: mapping.mapping_id,
+                serial: serial(&mapping.serial),
+            })
+            .collect()
+    }
+
+    /// The mappings from cells to their cell manifests.
+    pub fn cell_mappings(&self) -> Vec<CellMapping> {
+        let mut mappings: Vec<_> = self
+            .data
+            .cell_mappings
+            .values()
+            .map(|mapping| CellMapping {
+                cell_id: mapping.cell_id,
+                manifest_id: mapping.id,
+                serial: serial(&mapping.serial),
+            })
+            .collect();
+        mappings.sort_by_key(|mapping| cell_sort_key(mapping.cell_id));
+
+        mappings
+    }
+
+    /// The mappings from revisions to their revision manifests.
+    pub fn revision_mappings(&self) -> Vec<RevisionMapping> {
+        let mut mappings: Vec<_> = self
+            .data
+            .revision_mappings
+            .iter()
+            .map(|(id, mapping)| RevisionMapping {
+                revision_id: *id,
+                manifest_id: mapping.revision_mapping,
+                serial: serial(&mapping.serial),
+            })
+            .collect();
+        mappings.sort_by_key(|mapping| sort_key(mapping.revision_id));
+
+        mappings
+    }
+}
+
+/// A storage index's storage manifest mapping.
+#[derive(Debug, Clone, Copy)]
+pub struct ManifestMapping {
+    manifest_id: ExGuid,
+    serial: Option<(Guid, u64)>,
+}
+
+impl ManifestMapping {
+    /// The ID of the storage manifest.
+    pub fn manifest_id(&self) -> ExGuid {
+        self.manifest_id
+    }
+
+    /// The mapping's serial number.
+    pub fn serial(&self) -> Option<(Guid, u64)> {
+        self.serial
+    }
+}
+
+/// A storage index's cell mapping.
+#[derive(Debug, Clone, Copy)]
+pub struct CellMapping {
+    cell_id: CellId,
+    manifest_id: ExGuid,
+    serial: Option<(Guid, u64)>,
+}
+
+impl CellMapping {
+    /// The cell's ID.
+    ///
+    /// The first part is the context ID, the second part the object space ID.
+    pub fn cell_id(&self) -> CellId {
+        self.cell_id
+    }
+
+    /// The ID of the cell manifest.
+    pub fn manifest_id(&self) -> ExGuid {
+        self.manifest_id
+    }
+
+    /// The mapping's serial number.
+    pub fn serial(&self) -> Option<(Guid, u64)> {
+        self.serial
+    }
+}
+
+/// A storage index's revision mapping.
+#[derive(Debug, Clone, Copy)]
+pub struct RevisionMapping {
+    revision_id: ExGuid,
+    manifest_id: ExGuid,
+    serial: Option<(Guid, u64)>,
+}
+
+impl RevisionMapping {
+    /// The revision's ID.
+    pub fn revision_id(&self) -> ExGuid {
+        self.revision_id
+    }
+
+    /// The ID of the revision manifest.
+    pub fn manifest_id(&self) -> ExGuid {
+        self.manifest_id
+    }
+
+    /// The mapping's serial number.
+    pub fn serial(&self) -> Option<(Guid, u64)> {
+        self.serial
+    }
+}
+
+/// A storage manifest.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12.3]
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.12.3]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/a681199b-45f3-4378-b929-fb13e674ac5c
+#[derive(Debug, Clone, Copy)]
+pub struct StorageManifest<'a> {
+    data: &'a StorageManifestData,
+}
+
+impl<'a> StorageManifest<'a> {
+    /// The schema GUID that identifies the file type (section or table of contents).
+    pub fn schema(&self) -> Guid {
+        self.data.id
+    }
+
+    /// The root IDs and the cells they point to.
+    pub fn roots(&self) -> Vec<(ExGuid, CellId)> {
+        let mut roots: Vec<_> = self
+            .data
+            .roots
+            .iter()
+            .map(|(id, cell)| (*id, *cell))
+            .collect();
+        roots.sort_by_key(|(id, _)| sort_key(*id));
+
+        roots
+    }
+}
+
+/// A cell manifest.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12.4]
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.12.4]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/541f7f92-ee5d-407e-9ece-fb1b35832a10
+#[derive(Debug, Clone, Copy)]
+pub struct CellManifest {
+    id: ExGuid,
+    revision_id: ExGuid,
+}
+
+impl CellManifest {
+    /// The cell manifest's data element ID.
+    pub fn id(&self) -> ExGuid {
+        self.id
+    }
+
+    /// The ID of the cell's current revision.
+    pub fn revision_id(&self) -> ExGuid {
+        self.revision_id
+    }
+}
+
+/// A revision manifest.
+///
+/// See [\[MS-FSSHTTPB\] 2.2.1.12.5]
+///
+/// [\[MS-FSSHTTPB\] 2.2.1.12.5]: https://docs.microsoft.com/en-us/openspecs/sharepoint_protocols/ms-fsshttpb/eb3351db-8626-4804-a35b-f3eeda13c74d
+#[derive(Debug, Clone)]
+pub struct RevisionManifest {
+    id: ExGuid,
+    rev_id: ExGuid,
+    base_rev_id: Option<ExGuid>,
+    roots: Vec<(ExGuid, ExGuid)>,
+    group_references: Vec<ExGuid>,
+}
+
+impl RevisionManifest {
+    /// The revision manifest's data element ID.
+    pub fn id(&self) -> ExGuid {
+        self.id
+    }
+
+    /// The revision's ID.
+    pub fn revision_id(&self) -> ExGuid {
+        self.rev_id
+    }
+
+    /// The ID of the revision this revision is based on.
+    pub fn base_revision_id(&self) -> Option<ExGuid> {
+        self.base_rev_id
+    }
+
+    /// The revision's root roles and the objects they point to.
+    pub fn roots(&self) -> &[(ExGuid, ExGuid)] {
+        &self.roots
+    }
+
+    /// The data element IDs of the revision's object groups.
+    pub fn group_references(&self) -> &[ExGuid] {
+        &self.group_references
+    }
+}
+
+/// A file's resolved OneStore object spaces.
+#[derive(Debug)]
+pub struct Store<'a> {
+    store: OneStore<'a>,
+}
+
+impl<'a> Store<'a> {
+    /// The schema GUID that identifies the file type (section or table of contents).
+    pub fn schema(&self) -> Guid {
+        self.store.schema_guid()
+    }
+
+    /// The data root object space.
+    pub fn data_root(&self) -> ObjectSpace<'_> {
+        ObjectSpace {
+            space: self.store.data_root(),
+        }
+    }
+
+    /// Look up an object space by its cell ID.
+    pub fn object_space(&self, id: CellId) -> Option<ObjectSpace<'_>> {
+        self.store
+            .object_space(id)
+            .map(|space| ObjectSpace { space })
+    }
+
+    /// All object spaces except for the data root, sorted by their cell ID.
+    pub fn object_spaces(&self) -> Vec<(CellId, ObjectSpace<'_>)> {
+        let mut spaces: Vec<_> = self
+            .store
+            .object_spaces()
+            .map(|(id, space)| (id, ObjectSpace { space }))
+            .collect();
+        spaces.sort_by_key(|(id, _)| cell_sort_key(*id));
+
+        spaces
+    }
+}
+
+/// A OneStore object space.
+///
+/// See [\[MS-ONESTOR\] 2.1.4]
+///
+/// [\[MS-ONESTOR\] 2.1.4]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/1329433f-02a5-4e83-ab41-80d57ade38d9
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectSpace<'a> {
+    space: &'a ObjectSpaceData<'a>,
+}
+
+impl<'a> ObjectSpace<'a> {
+    /// The object space's ID.
+    pub fn id(&self) -> ExGuid {
+        self.space.id()
+    }
+
+    /// The object space's context ID.
+    pub fn context(&self) -> ExGuid {
+        self.space.context()
+    }
+
+    /// The ID of the root object of the default content revision.
+    pub fn content_root(&self) -> Option<ExGuid> {
+        self.space.content_root()
+    }
+
+    /// The ID of the root object of the metadata revision.
+    pub fn metadata_root(&self) -> Option<ExGuid> {
+        self.space.metadata_root()
+    }
+
+    /// Look up an object by its ID.
+    pub fn object(&self, id: ExGuid) -> Option<Object<'a>> {
+        self.space.get_object(id).map(|object| Object { object })
+    }
+
+    /// All objects in the object space, sorted by their ID.
+    pub fn objects(&self) -> Vec<(ExGuid, Object<'a>)> {
+        let mut objects: Vec<_> = self
+            .space
+            .objects()
+            .map(|(id, object)| (id, Object { object }))
+            .collect();
+        objects.sort_by_key(|(id, _)| sort_key(*id));
+
+        objects
+    }
+}
+
+/// A OneStore object.
+///
+/// See [\[MS-ONESTOR\] 2.1.5]
+///
+/// [\[MS-ONESTOR\] 2.1.5]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/ce60b62f-82e5-401a-bf2c-3255457732ad
+#[derive(Debug, Clone, Copy)]
+pub struct Object<'a> {
+    object: &'a ObjectData<'a>,
+}
+
+impl<'a> Object<'a> {
+    /// The object's JCID that identifies its type.
+    pub fn jcid(&self) -> u32 {
+        self.object.id().0
+    }
+
+    /// The name of the object's property set if the JCID is known.
+    pub fn jcid_name(&self) -> Option<String> {
+        jcid_name(self.jcid())
+    }
+
+    /// The object's context ID.
+    pub fn context_id(&self) -> ExGuid {
+        self.object.context_id()
+    }
+
+    /// The object's file data.
+    pub fn file_data(&self) -> Option<&'a [u8]> {
+        self.object.file_data()
+    }
+
+    pub(crate) fn data(&self) -> &'a ObjectData<'a> {
+        self.object
+    }
+
+    /// The object's properties in the order they are stored in the file.
+    ///
+    /// The references of each top-level property are resolved using the
+    /// object's mapping table.
+    pub fn properties(&self) -> Vec<Property<'a>> {
+        let props = self.object.props();
+        let mapping = self.object.mapping();
+        let context_base = props.object_ids().len();
+
+        let mut offset = RefCounts::default();
+
+        props
+            .properties()
+            .entries()
+            .into_iter()
+            .map(|(id, value)| {
+                let count = RefCounts::of(value);
+
+                let object_refs = (offset.objects..offset.objects + count.objects)
+                    .map(|index| {
+                        let cid = props.object_ids().get(index)?;
+                        mapping.get_object(index, *cid)
+                    })
+                    .collect();
+                let object_space_refs = (offset.spaces..offset.spaces + count.spaces)
+                    .map(|index| {
+                        let cid = props.object_space_ids().get(index)?;
+                        mapping.get_object_space(index, *cid)
+                    })
+                    .collect();
+                let context_refs = (offset.contexts..offset.contexts + count.contexts)
+                    .map(|index| {
+                        let cid = props.context_ids().get(index)?;
+                        mapping.get_object(context_base + index, *cid)
+                    })
+                    .collect();
+
+                offset.add(count);
+
+                Property {
+                    id: id.value(),
+                    value: PropertyValue::from_data(value),
+                    object_refs,
+                    object_space_refs,
+                    context_refs,
+                }
+            })
+            .collect()
+    }
+}
+
+/// An object property.
+#[derive(Debug, Clone)]
+pub struct Property<'a> {
+    id: u32,
+    value: PropertyValue<'a>,
+    object_refs: Vec<Option<ExGuid>>,
+    object_space_refs: Vec<Option<CellId>>,
+    context_refs: Vec<Option<ExGuid>>,
+}
+
+impl<'a> Property<'a> {
+    /// The property ID including the property type and the boolean flag.
+    ///
+    /// See [\[MS-ONESTORE\] 2.6.6].
+    ///
+    /// [\[MS-ONESTORE\] 2.6.6]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/17d8c39e-6cc2-4fcd-8d10-aee950fd0ab2
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The name of the property if the property ID is known.
+    pub fn name(&self) -> Option<String> {
+        property_name(self.id)
+    }
+
+    /// The property's value.
+    pub fn value(&self) -> &PropertyValue<'a> {
+        &self.value
+    }
+
+    /// The objects referenced by the property, including references in
+    /// nested property sets.
+    ///
+    /// Entries are `None` if the reference can't be resolved. Properties in
+    /// nested property sets don't resolve their references themselves.
+    pub fn object_refs(&self) -> &[Option<ExGuid>] {
+        &self.object_refs
+    }
+
+    /// The object spaces referenced by the property.
+    pub fn object_space_refs(&self) -> &[Option<CellId>] {
+        &self.object_space_refs
+    }
+
+    /// The contexts referenced by the property.
+    pub fn context_refs(&self) -> &[Option<ExGuid>] {
+        &self.context_refs
+    }
+}
+
+/// A property value.
+///
+/// Reference values only contain the number of references. Use
+/// [`Property::object_refs`] and its siblings to resolve them.
+///
+/// See [\[MS-ONESTORE\] 2.6.6].
+///
+/// [\[MS-ONESTORE\] 2.6.6]: https://docs.microsoft.com/en-us/openspecs/office_file_formats/ms-onestore/17d8c39e-6cc2-4fcd-8d10-aee950fd0ab2
+#[derive(Debug, Clone)]
+pub enum PropertyValue<'a> {
+    /// A property without data.
+    Empty,
+    /// A boolean.
+    Bool(bool),
+    /// An 8 bit integer.
+    U8(u8),
+    /// A 16 bit integer.
+    U16(u16),
+    /// A 32 bit integer.
+    U32(u32),
+    /// A 64 bit integer.
+    U64(u64),
+    /// A byte array.
+    Bytes(&'a [u8]),
+    /// A single object reference.
+    ObjectId,
+    /// An array of object references.
+    ObjectIds(u32),
+    /// A single object space reference.
+    ObjectSpaceId,
+    /// An array of object space references.
+    ObjectSpaceIds(u32),
+    /// A single context reference.
+    ContextId,
+    /// An array of context references.
+    ContextIds(u32),
+    /// An array of property sets with the given property ID.
+    PropertyValues(u32, Vec<Vec<Property<'a>>>),
+    /// A nested property set.
+    PropertySet(Vec<Property<'a>>),
+}
+
+impl<'a> PropertyValue<'a> {
+    fn from_data(value: &'a PropertyValueData) -> PropertyValue<'a> {
+        match value {
+            PropertyValueData::Empty => PropertyValue::Empty,
+            PropertyValueData::Bool(v) => PropertyValue::Bool(*v),
+            PropertyValueData::U8(v) => PropertyValue::U8(*v),
+            PropertyValueData::U16(v) => PropertyValue::U16(*v),
+            PropertyValueData::U32(v) => PropertyValue::U32(*v),
+            PropertyValueData::U64(v) => PropertyValue::U64(*v),
+            PropertyValueData::Vec(v) => PropertyValue::Bytes(v),
+            PropertyValueData::ObjectId => PropertyValue::ObjectId,
+            PropertyValueData::ObjectIds(c) => PropertyValue::ObjectIds(*c),
+            PropertyValueData::ObjectSpaceId => PropertyValue::ObjectSpaceId,
+            PropertyValueData::ObjectSpaceIds(c) => PropertyValue::ObjectSpaceIds(*c),
+            PropertyValueData::ContextId => PropertyValue::ContextId,
+            PropertyValueData::ContextIds(c) => PropertyValue::ContextIds(*c),
+            PropertyValueData::PropertyValues(id, sets) => PropertyValue::PropertyValues(
+                id.value(),
+                sets.iter().map(nested_properties).collect(),
+            ),
+            PropertyValueData::PropertySet(set) => {
+                PropertyValue::PropertySet(nested_properties(set))
+            }
+        }
+    }
+
+    /// The property type as stored in bits 26 to 30 of the property ID.
+    pub fn prop_type(&self) -> u32 {
+        match self {
+            PropertyValue::Empty => 0x1,
+            PropertyValue::Bool(_) => 0x2,
+            PropertyValue::U8(_) => 0x3,
+            PropertyValue::U16(_) => 0x4,
+            PropertyValue::U32(_) => 0x5,
+            PropertyValue::U64(_) => 0x6,
+            PropertyValue::Bytes(_) => 0x7,
+            PropertyValue::ObjectId => 0x8,
+            PropertyValue::ObjectIds(_) => 0x9,
+            PropertyValue::ObjectSpaceId => 0xA,
+            PropertyValue::ObjectSpaceIds(_) => 0xB,
+            PropertyValue::ContextId => 0xC,
+            PropertyValue::ContextIds(_) => 0xD,
+            PropertyValue::PropertyValues(..) => 0x10,
+            PropertyValue::PropertySet(_) => 0x11,
+        }
+    }
+}
+
+/// The name of a property set by its JCID.
+pub fn jcid_name(jcid: u32) -> Option<String> {
+    PropertySetId::from_jcid(JcId(jcid)).map(|id| format!("{id:?}"))
+}
+
+/// The name of a property by its property ID.
+pub fn property_name(id: u32) -> Option<String> {
+    PropertyType::from_id(id).map(|prop_type| format!("{prop_type:?}"))
+}
+
+fn nested_properties(set: &PropertySet) -> Vec<Property<'_>> {
+    set.entries()
+        .into_iter()
+        .map(|(id, value)| Property {
+            id: id.value(),
+            value: PropertyValue::from_data(value),
+            object_refs: vec![],
+            object_space_refs: vec![],
+            context_refs: vec![],
+        })
+        .collect()
+}
+
+/// The number of references a property value holds.
+#[derive(Debug, Default, Clone, Copy)]
+struct RefCounts {
+    objects: usize,
+    spaces: usize,
+    contexts: usize,
+}
+
+impl RefCounts {
+    fn of(value: &PropertyValueData) -> RefCounts {
+        match value {
+            PropertyValueData::ObjectId => RefCounts {
+                objects: 1,
+                ..Default::default()
+            },
+            PropertyValueData::ObjectIds(c) => RefCounts {
+                objects: *c as usize,
+                ..Default::default()
+            },
+            PropertyValueData::ObjectSpaceId => RefCounts {
+                spaces: 1,
+                ..Default::default()
+            },
+            PropertyValueData::ObjectSpaceIds(c) => RefCounts {
+                spaces: *c as usize,
+                ..Default::default()
+            },
+            PropertyValueData::ContextId => RefCounts {
+                contexts: 1,
+                ..Default::default()
+            },
+            PropertyValueData::ContextIds(c) => RefCounts {
+                contexts: *c as usize,
+                ..Default::default()
+            },
+            PropertyValueData::PropertyValues(_, sets) => {
+                let mut count = RefCounts::default();
+                for value in sets.iter().flat_map(|set| set.values()) {
+                    count.add(RefCounts::of(value));
+                }
+
+                count
+            }
+            PropertyValueData::PropertySet(set) => {
+                let mut count = RefCounts::default();
+                for value in set.values() {
+                    count.add(RefCounts::of(value));
+                }
+
+                count
+            }
+            _ => RefCounts::default(),
+        }
+    }
+
+    fn add(&mut self, other: RefCounts) {
+        self.objects = self.objects.saturating_add(other.objects);
+        self.spaces = self.spaces.saturating_add(other.spaces);
+        self.contexts = self.contexts.saturating_add(other.contexts);
+    }
+}
+
+fn serial(serial: &SerialNumber) -> Option<(Guid, u64)> {
+    if serial.guid.is_nil() {
+        None
+    } else {
+        Some((serial.guid, serial.serial))
+    }
+}
+
+fn sort_key(id: ExGuid) -> (uuid::Uuid, u32) {
+    (id.guid.0, id.value)
+}
+
+fn cell_sort_key(id: CellId) -> ((uuid::Uuid, u32), (uuid::Uuid, u32)) {
+    (sort_key(id.0), sort_key(id.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataElementKind, PropertyValue, RawFile};
+    use crate::one::property::PropertyType;
+    use crate::one::property_set::PropertySetId;
+    use crate::test_support::{ObjectBuilder, SpaceBuilder, StoreBuilder, id};
+
+    #[test]
+    fn test_raw_file() {
+        let page = SpaceBuilder::new(20);
+        let page_cell = page.cell_id();
+        let data = StoreBuilder::section(
+            SpaceBuilder::new(1)
+                .content_root(
+                    ObjectBuilder::new(id(10), PropertySetId::PageSeriesNode)
+                        .object_refs(PropertyType::ElementChildNodes, &[id(11), id(12)])
+                        .space_refs(PropertyType::ChildGraphSpaceElementNodes, &[page_cell]),
+                )
+                .object(ObjectBuilder::new(id(11), PropertySetId::PageNode))
+                .object(ObjectBuilder::new(id(12), PropertySetId::PageNode).file_data(&[1, 2])),
+        )
+        .space(page)
+        .build();
+
+        let file = RawFile::parse(&data).unwrap();
+
+        let elements = file.data_elements();
+        assert!(
+            elements
+                .iter()
+                .any(|element| element.kind() == DataElementKind::StorageIndex)
+        );
+        assert!(elements.iter().all(|element| element.size() > 0));
+        assert!(
+            file.storage_index()
+                .unwrap()
+                .cell_mappings()
+                .iter()
+                .any(|mapping| mapping.cell_id() == page_cell)
+        );
+        assert_eq!(file.storage_manifest().unwrap().roots().len(), 2);
+
+        assert_eq!(file.revision_chain(page_cell).len(), 1);
+
+        let store = file.store().unwrap();
+        assert!(store.object_space(page_cell).is_some());
+
+        let root = store.data_root();
+        let series = root.object(root.content_root().unwrap()).unwrap();
+        assert_eq!(series.jcid(), PropertySetId::PageSeriesNode as u32);
+        assert_eq!(series.jcid_name().as_deref(), Some("PageSeriesNode"));
+
+        let properties = series.properties();
+        let children = properties
+            .iter()
+            .find(|prop| prop.id() == PropertyType::ElementChildNodes as u32)
+            .unwrap();
+        assert_eq!(children.name().as_deref(), Some("ElementChildNodes"));
+        assert!(matches!(children.value(), PropertyValue::ObjectIds(2)));
+        assert_eq!(children.object_refs(), &[Some(id(11)), Some(id(12))]);
+
+        let pages = properties
+            .iter()
+            .find(|prop| prop.id() == PropertyType::ChildGraphSpaceElementNodes as u32)
+            .unwrap();
+        assert_eq!(pages.object_space_refs(), &[Some(page_cell)]);
+
+        assert_eq!(root.object(id(12)).unwrap().file_data(), Some(&[1, 2][..]));
+    }
+
+    #[test]
+    fn test_raw_file_truncated() {
+        let mut data = StoreBuilder::section(SpaceBuilder::new(1)).build();
+        let file = RawFile::parse(&data).unwrap();
+        assert!(file.store().is_ok());
+
+        data.truncate(data.len() / 2);
+        assert!(RawFile::parse(&data).is_err());
+    }
+}
@@ -1,11 +1,46 @@
 use crate::errors::{ErrorKind, Result};
-use bytes::Buf;
-
-pub(crate) struct Reader<'a>(&'a [u8]);
+use crate::limits::Budget;
+use bytes::{Buf, Bytes};
+
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    len: usize,
+    budget: Option<&'a Budget>,
+    buffer: Option<&'a Bytes>,
+}
 
 impl<'a> Reader<'a> {
     pub(crate) fn new(data: &'a [u8]) -> Reader<'a> {
-        Reader(data)
+        Reader {
+            data,
+            len: data.len(),
+            budget: None,
+            buffer: None,
+        }
+    }
+
+    /// Create a reader that enforces the resource limits of the budget.
+    pub(crate) fn with_budget(data: &'a [u8], budget: &'a Budget) -> Reader<'a> {
+        Reader {
+            data,
+            len: data.len(),
+            budget: Some(budget),
+            buffer: None,
+        }
+    }
+
+    /// Share the byte arrays read by [`Reader::read_bytes`] with the buffer
+    /// instead of copying them.
+    ///
+    /// The reader's data must be part of the buffer.
+    pub(crate) fn sharing(mut self, buffer: &'a Bytes) -> Reader<'a> {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// The number of bytes that have been consumed.
+    pub(crate) fn offset(&self) -> usize {
+        self.len - self.remaining()
     }
 
     pub(crate) fn read(&mut self, cnt: usize) -> Result<&[u8]> {
@@ -13,18 +48,58 @@ impl<'a> Reader<'a> {
             return Err(ErrorKind::UnexpectedEof.into());
         }
 
-        let data = &self.0[0..cnt];
-        self.0.advance(cnt);
+        let data = &self.data[0..cnt];
+        self.data.advance(cnt);
 
         Ok(data)
     }
 
+    /// Read a byte array whose size has been read from the data.
+    pub(crate) fn read_vec(&mut self, size: u64) -> Result<Vec<u8>> {
+        let size = self.check_size(size)?;
+
+        Ok(self.read(size)?.to_vec())
+    }
+
+    /// Read a byte array whose size has been read from the data.
+    ///
+    /// The byte array is shared with the reader's buffer if there is one and
+    /// copied otherwise.
+    pub(crate) fn read_bytes(&mut self, size: u64) -> Result<Bytes> {
+        let size = self.check_size(size)?;
+        let buffer = self.buffer;
+        let data = self.read(size)?;
+
+        Ok(match buffer {
+            Some(buffer) => buffer.slice_ref(data),
+            None => Bytes::copy_from_slice(data),
+        })
+    }
+
+    fn check_size(&self, size: u64) -> Result<usize> {
+        match self.budget {
+            Some(budget) => budget.limits().check_allocation(size),
+            None => Ok(usize::try_from(size).map_err(|_| ErrorKind::UnexpectedEof)?),
+        }
+    }
+
+    /// Record a parsed data element or object in the budget.
+    pub(crate) fn add_object(&self) -> Result<()> {
+        self.budget.map_or(Ok(()), |budget| budget.add_object())
+    }
+
+    /// Record the size of a file data blob in the budget.
+    pub(crate) fn add_blob_bytes(&self, size: usize) -> Result<()> {
+        self.budget
+            .map_or(Ok(()), |budget| budget.add_blob_bytes(size as u64))
+    }
+
     pub(crate) fn bytes(&self) -> &[u8] {
-        self.0.chunk()
+        self.data.chunk()
     }
 
     pub(crate) fn remaining(&self) -> usize {
-        self.0.remaining()
+        self.data.remaining()
     }
 
     pub(crate) fn advance(&mut self, cnt: usize) -> Result<()> {
@@ -32,43 +107,43 @@ impl<'a> Reader<'a> {
             return Err(ErrorKind::UnexpectedEof.into());
         }
 
-        self.0.advance(cnt);
+        self.data.advance(cnt);
 
         Ok(())
     }
 
     pub(crate) fn get_u8(&mut self) -> Result<u8> {
-        self.0
+        self.data
             .try_get_u8()
             .map_err(|_| ErrorKind::UnexpectedEof.into())
     }
 
     pub(crate) fn get_u16(&mut self) -> Result<u16> {
-        self.0
+        self.data
             .try_get_u16_le()
             .map_err(|_| ErrorKind::UnexpectedEof.into())
     }
 
     pub(crate) fn get_u32(&mut self) -> Result<u32> {
-        self.0
+        self.data
             .try_get_u32_le()
             .map_err(|_| ErrorKind::UnexpectedEof.into())
     }
 
     pub(crate) fn get_u64(&mut self) -> Result<u64> {
-        self.0
+        self.data
             .try_get_u64_le()
             .map_err(|_| ErrorKind::UnexpectedEof.into())
     }
 
     pub(crate) fn get_u128(&mut self) -> Result<u128> {
-        self.0
+        self.data
             .try_get_u128_le()
             .map_err(|_| ErrorKind::UnexpectedEof.into())
     }
 
     pub(crate) fn get_f32(&mut self) -> Result<f32> {
-        self.0
+        self.data
             .try_get_f32_le()
             .map_err(|_| ErrorKind::UnexpectedEof.into())
     }
@@ -77,6 +152,9 @@ impl<'a> Reader<'a> {
 #[cfg(test)]
 mod tests {
     use super::Reader;
+    use crate::errors::ErrorKind;
+    use crate::limits::{Budget, ParseLimits};
+    use bytes::Bytes;
 
     #[test]
     fn test_read_and_advance() {
@@ -86,6 +164,7 @@ mod tests {
         assert_eq!(reader.remaining(), 4);
         assert_eq!(reader.read(2).unwrap(), &[1, 2]);
         assert_eq!(reader.remaining(), 2);
+        assert_eq!(reader.offset(), 2);
 
         reader.advance(1).unwrap();
         assert_eq!(reader.remaining(), 1);
@@ -93,6 +172,34 @@ mod tests {
         assert!(reader.get_u8().is_err());
     }
 
+    #[test]
+    fn test_read_vec_with_budget() {
+        let data = [1u8, 2, 3, 4];
+        let budget = Budget::new(ParseLimits::default().with_max_allocation(2));
+        let mut reader = Reader::with_budget(&data, &budget);
+
+        assert_eq!(reader.read_vec(2).unwrap(), [1, 2]);
+        assert!(matches!(
+            reader.read_vec(3).unwrap_err().kind(),
+            ErrorKind::LimitExceeded { .. }
+        ));
+        assert!(Reader::new(&data).read_vec(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_read_bytes_shares_buffer() {
+        let buffer = Bytes::from_static(&[1, 2, 3, 4]);
+        let mut reader = Reader::new(&buffer[1..]).sharing(&buffer);
+
+        let data = reader.read_bytes(2).unwrap();
+        assert_eq!(data, [2, 3][..]);
+        assert_eq!(data.as_ptr(), buffer[1..].as_ptr());
+
+        let copied = Reader::new(&buffer).read_bytes(2).unwrap();
+        assert_eq!(copied, [1, 2][..]);
+        assert_ne!(copied.as_ptr(), buffer.as_ptr());
+    }
+
     #[test]
     fn test_get_numeric_types() {
         let data = [
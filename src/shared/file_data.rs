@@ -0,0 +1,30 @@
+use bytes::Bytes;
+use std::fmt;
+use std::ops::Deref;
+
+/// The binary data of an embedded file or image.
+///
+/// When parsing a memory-mapped file the data refers to the mapping instead of
+/// being copied.
+#[derive(Clone, Default, PartialEq, PartialOrd)]
+pub(crate) struct FileData(Bytes);
+
+impl From<Bytes> for FileData {
+    fn from(data: Bytes) -> Self {
+        FileData(data)
+    }
+}
+
+impl Deref for FileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for FileData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
@@ -0,0 +1,733 @@
+use crate::Reader;
+use crate::errors::Result;
+use crate::reader;
+use crate::shared::guid::Guid;
+use std::path::Path;
+use std::time::Duration;
+
+/// The content type of a file embedded in a OneNote section.
+///
+/// The content type is determined by inspecting the file's data (content sniffing)
+/// and does not rely on the file name or extension stored in the section.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[non_exhaustive]
+pub enum ContentType {
+    /// A PNG image.
+    Png,
+    /// A JPEG image.
+    Jpeg,
+    /// A GIF image.
+    Gif,
+    /// A Windows bitmap image.
+    Bmp,
+    /// A TIFF image.
+    Tiff,
+    /// An Enhanced Metafile (EMF) image.
+    Emf,
+    /// A Windows Metafile (WMF) image.
+    Wmf,
+    /// A PDF document.
+    Pdf,
+    /// An XPS document.
+    Xps,
+    /// A Word document (Office Open XML).
+    Docx,
+    /// An Excel workbook (Office Open XML).
+    Xlsx,
+    /// A PowerPoint presentation (Office Open XML).
+    Pptx,
+    /// A legacy Office document stored as an OLE compound file (e.g. `.doc`, `.xls`).
+    OleCompoundFile,
+    /// A ZIP archive.
+    Zip,
+    /// A Windows Media audio file (ASF container).
+    Wma,
+    /// A Windows Media video file (ASF container).
+    Wmv,
+    /// A WAV audio file.
+    Wav,
+    /// An MP3 audio file.
+    Mp3,
+    /// An MPEG-4 audio file.
+    M4a,
+    /// An MPEG-4 video file.
+    Mp4,
+    /// A QuickTime video file.
+    QuickTime,
+    /// An AVI video file.
+    Avi,
+    /// An Ogg media file.
+    Ogg,
+    /// A Matroska or WebM media file.
+    Matroska,
+}
+
+impl ContentType {
+    /// Determine the content type of a file from its data.
+    ///
+    /// Returns `None` if the content type could not be recognized.
+    pub fn detect(data: &[u8]) -> Option<ContentType> {
+        let content_type = if data.starts_with(b"\x89PNG\r\n\x1A\n") {
+            ContentType::Png
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            ContentType::Jpeg
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            ContentType::Gif
+        } else if is_bmp(data) {
+            ContentType::Bmp
+        } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+            ContentType::Tiff
+        } else if data.starts_with(&[1, 0, 0, 0]) && data.get(40..44) == Some(b" EMF") {
+            ContentType::Emf
+        } else if data.starts_with(&[0xD7, 0xCD, 0xC6, 0x9A])
+            || ((data.starts_with(&[1, 0]) || data.starts_with(&[2, 0]))
+                && data.get(2..4) == Some(&[9, 0]))
+        {
+            ContentType::Wmf
+        } else if data.starts_with(b"%PDF-") {
+            ContentType::Pdf
+        } else if data.starts_with(b"PK\x03\x04") {
+            detect_zip(data)
+        } else if data.starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+            ContentType::OleCompoundFile
+        } else if data.starts_with(&ASF_HEADER_OBJECT) {
+            if contains(asf_header(data), &ASF_VIDEO_MEDIA) {
+                ContentType::Wmv
+            } else {
+                ContentType::Wma
+            }
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE") {
+            ContentType::Wav
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"AVI ") {
+            ContentType::Avi
+        } else if data.get(4..8) == Some(b"ftyp") {
+            match data.get(8..12) {
+                Some(b"qt  ") => ContentType::QuickTime,
+                Some(b"M4A ") | Some(b"M4B ") => ContentType::M4a,
+                _ => ContentType::Mp4,
+            }
+        } else if data.starts_with(b"OggS") {
+            ContentType::Ogg
+        } else if data.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+            ContentType::Matroska
+        } else if is_mp3(data) {
+            ContentType::Mp3
+        } else {
+            return None;
+        };
+
+        Some(content_type)
+    }
+
+    /// The content type's MIME type.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ContentType::Png => "image/png",
+            ContentType::Jpeg => "image/jpeg",
+            ContentType::Gif => "image/gif",
+            ContentType::Bmp => "image/bmp",
+            ContentType::Tiff => "image/tiff",
+            ContentType::Emf => "image/emf",
+            ContentType::Wmf => "image/wmf",
+            ContentType::Pdf => "application/pdf",
+            ContentType::Xps => "application/vnd.ms-xpsdocument",
+            ContentType::Docx => {
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            }
+            ContentType::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            ContentType::Pptx => {
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+            }
+            ContentType::OleCompoundFile => "application/x-ole-storage",
+            ContentType::Zip => "application/zip",
+            ContentType::Wma => "audio/x-ms-wma",
+            ContentType::Wmv => "video/x-ms-wmv",
+            ContentType::Wav => "audio/wav",
+            ContentType::Mp3 => "audio/mpeg",
+            ContentType::M4a => "audio/mp4",
+            ContentType::Mp4 => "video/mp4",
+            ContentType::QuickTime => "video/quicktime",
+            ContentType::Avi => "video/x-msvideo",
+            ContentType::Ogg => "application/ogg",
+            ContentType::Matroska => "video/x-matroska",
+        }
+    }
+
+    /// The preferred file extension for the content type (without a leading dot).
+    pub fn extension(&self) -> &'static str {
+        self.extensions()[0]
+    }
+
+    /// Whether a file name or extension is consistent with the content type.
+    ///
+    /// Accepts both file names (`photo.jpg`) and extensions with or without a
+    /// leading dot (`.jpg`, `jpg`).
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name = name.trim();
+        let extension = Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_else(|| name.trim_start_matches('.'));
+
+        self.extensions()
+            .iter()
+            .any(|ext| ext.eq_ignore_ascii_case(extension))
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            ContentType::Png => &["png"],
+            ContentType::Jpeg => &["jpg", "jpeg", "jpe", "jfif"],
+            ContentType::Gif => &["gif"],
+            ContentType::Bmp => &["bmp", "dib"],
+            ContentType::Tiff => &["tiff", "tif"],
+            ContentType::Emf => &["emf"],
+            ContentType::Wmf => &["wmf"],
+            ContentType::Pdf => &["pdf"],
+            ContentType::Xps => &["xps", "oxps"],
+            ContentType::Docx => &["docx", "docm", "dotx", "dotm"],
+            ContentType::Xlsx => &["xlsx", "xlsm", "xltx", "xltm"],
+            ContentType::Pptx => &["pptx", "pptm", "potx", "potm", "ppsx"],
+            ContentType::OleCompoundFile => &[
+                "bin", "doc", "dot", "xls", "xlt", "ppt", "pot", "pps", "msg", "vsd", "pub",
+            ],
+            ContentType::Zip => &["zip"],
+            ContentType::Wma => &["wma", "asf"],
+            ContentType::Wmv => &["wmv", "asf"],
+            ContentType::Wav => &["wav"],
+            ContentType::Mp3 => &["mp3"],
+            ContentType::M4a => &["m4a", "m4b"],
+            ContentType::Mp4 => &["mp4", "m4v"],
+            ContentType::QuickTime => &["mov", "qt"],
+            ContentType::Avi => &["avi"],
+            ContentType::Ogg => &["ogg", "oga", "ogv", "opus"],
+            ContentType::Matroska => &["mkv", "webm", "mka"],
+        }
+    }
+}
+
+const ASF_HEADER_OBJECT: [u8; 16] = [
+    0x30, 0x26, 0xB2, 0x75, 0x8E, 0x66, 0xCF, 0x11, 0xA6, 0xD9, 0x00, 0xAA, 0x00, 0x62, 0xCE, 0x6C,
+];
+
+const ASF_VIDEO_MEDIA: [u8; 16] = [
+    0xC0, 0xEF, 0x19, 0xBC, 0x4D, 0x5B, 0xCF, 0x11, 0xA8, 0xFD, 0x00, 0x80, 0x5F, 0x5C, 0x44, 0x2B,
+];
+
+/// Distinguish Office Open XML and XPS documents from other ZIP archives by the
+/// names of the contained files.
+fn detect_zip(data: &[u8]) -> ContentType {
+    let mut offset = 0;
+    let mut content_type = ContentType::Zip;
+
+    while let Some(header) = data.get(offset..offset + 30) {
+        if !header.starts_with(b"PK\x03\x04") {
+            break;
+        }
+
+        let compressed_size = u32::from_le_bytes([header[18], header[19], header[20], header[21]]);
+        let name_length = u16::from_le_bytes([header[26], header[27]]) as usize;
+        let extra_length = u16::from_le_bytes([header[28], header[29]]) as usize;
+        let name = match data.get(offset + 30..offset + 30 + name_length) {
+            Some(name) => name,
+            None => break,
+        };
+
+        if name.starts_with(b"word/") {
+            return ContentType::Docx;
+        } else if name.starts_with(b"xl/") {
+            return ContentType::Xlsx;
+        } else if name.starts_with(b"ppt/") {
+            return ContentType::Pptx;
+        } else if name.ends_with(b".fdseq") || name.ends_with(b".fdoc") {
+            content_type = ContentType::Xps;
+        }
+
+        // Sizes are stored in a data descriptor after the data if bit 3 is set
+        let flags = u16::from_le_bytes([header[6], header[7]]);
+        if flags & 0x8 != 0 {
+            break;
+        }
+
+        offset += 30 + name_length + extra_length + compressed_size as usize;
+    }
+
+    content_type
+}
+
+/// Check the BMP file header and the size of the DIB header that follows it.
+fn is_bmp(data: &[u8]) -> bool {
+    let header = match data.get(..18) {
+        Some(header) if header.starts_with(b"BM") => header,
+        _ => return false,
+    };
+
+    let reserved = &header[6..10];
+    let pixel_offset = u32::from_le_bytes([header[10], header[11], header[12], header[13]]);
+    let dib_header_size = u32::from_le_bytes([header[14], header[15], header[16], header[17]]);
+
+    reserved == [0; 4]
+        && matches!(dib_header_size, 12 | 16 | 40 | 52 | 56 | 64 | 108 | 124)
+        && pixel_offset >= 14 + dib_header_size
+}
+
+/// The ASF header object which contains the stream properties.
+fn asf_header(data: &[u8]) -> &[u8] {
+    let size = data
+        .get(16..24)
+        .map(|size| {
+            u64::from_le_bytes([
+                size[0], size[1], size[2], size[3], size[4], size[5], size[6], size[7],
+            ])
+        })
+        .unwrap_or_default();
+    let size = usize::try_from(size).unwrap_or(usize::MAX);
+
+    &data[..size.min(data.len())]
+}
+
+/// Check for an ID3v2 tag or two consecutive MPEG audio frames.
+///
+/// A single frame sync is too likely to appear at the start of arbitrary data.
+fn is_mp3(data: &[u8]) -> bool {
+    if data.starts_with(b"ID3") {
+        return true;
+    }
+
+    data.get(..4)
+        .and_then(Mp3Frame::parse)
+        .and_then(|frame| data.get(frame.length..frame.length + 4))
+        .and_then(Mp3Frame::parse)
+        .is_some()
+}
+
+fn contains(data: &[u8], needle: &[u8]) -> bool {
+    data.windows(needle.len()).any(|window| window == needle)
+}
+
+/// Determine the play duration of an audio/video recording.
+///
+/// OneNote records audio and video as Windows Media files (ASF container). Inserted
+/// recordings may also be WAV or MP3 files. Returns `None` if the format isn't
+/// recognized or the header is malformed.
+pub(crate) fn recording_duration(data: &[u8]) -> Option<Duration> {
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE") {
+        return parse_wav_duration(&mut reader::Reader::new(&data[12..])).ok()?;
+    }
+
+    if let Some(duration) = parse_asf_duration(&mut reader::Reader::new(data)).ok()? {
+        return Some(duration);
+    }
+
+    parse_mp3_duration(data)
+}
+
+/// Read the play duration from the ASF file properties object.
+///
+/// See the Advanced Systems Format (ASF) specification, section 3.2.
+fn parse_asf_duration(reader: Reader) -> Result<Option<Duration>> {
+    if Guid::parse(reader)? != guid!("75B22630-668E-11CF-A6D9-00AA0062CE6C") {
+        return Ok(None);
+    }
+
+    let _header_size = reader.get_u64()?;
+    let header_count = reader.get_u32()?;
+    reader.advance(2)?;
+
+    for _ in 0..header_count {
+        let object_type = Guid::parse(reader)?;
+        let object_size = reader.get_u64()?;
+
+        if object_type == guid!("8CABDCA1-A947-11CF-8EE4-00C00C205365") {
+            let _file_id = Guid::parse(reader)?;
+            let _file_size = reader.get_u64()?;
+            let _creation_date = reader.get_u64()?;
+            let _data_packets = reader.get_u64()?;
+            let play_duration = reader.get_u64()?;
+            let _send_duration = reader.get_u64()?;
+            let preroll = reader.get_u64()?;
+
+            // The play duration is specified in 100-nanosecond units and includes the preroll
+            let duration = Duration::from_nanos(play_duration.saturating_mul(100))
+                .saturating_sub(Duration::from_millis(preroll));
+
+            return Ok(Some(duration));
+        }
+
+        let body_size = object_size.saturating_sub(24);
+        reader.advance(body_size as usize)?;
+    }
+
+    Ok(None)
+}
+
+/// Calculate the duration of a WAV file from its format and data chunk sizes.
+fn parse_wav_duration(reader: Reader) -> Result<Option<Duration>> {
+    let mut byte_rate = None;
+
+    while reader.remaining() >= 8 {
+        let chunk_id = reader.read(4)?;
+        let chunk_id = [chunk_id[0], chunk_id[1], chunk_id[2], chunk_id[3]];
+        let chunk_size = reader.get_u32()? as usize;
+
+        match &chunk_id {
+            b"fmt " => {
+                let format = reader.read(chunk_size)?;
+                byte_rate = format
+                    .get(8..12)
+                    .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]));
+            }
+            b"data" => {
+                return Ok(byte_rate
+                    .filter(|rate| *rate > 0)
+                    .map(|rate| Duration::from_secs_f64(chunk_size as f64 / rate as f64)));
+            }
+            _ => reader.advance(chunk_size)?,
+        }
+
+        // Chunks are padded to an even size
+        if chunk_size % 2 == 1 && reader.remaining() > 0 {
+            reader.advance(1)?;
+        }
+    }
+
+    Ok(None)
+}
+
+/// Calculate the duration of an MP3 file.
+///
+/// Uses the frame count from the Xing/Info header if present and otherwise assumes
+/// a constant bitrate.
+fn parse_mp3_duration(data: &[u8]) -> Option<Duration> {
+    // Skip the ID3v2 tag
+    let mut offset = 0;
+    if data.starts_with(b"ID3") {
+        let header = data.get(..10)?;
+        let size = header[6..10]
+            .iter()
+            .fold(0usize, |size, b| (size << 7) | (*b & 0x7F) as usize);
+        let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+        offset = 10 + size + footer;
+    }
+
+    let frame = Mp3Frame::parse(data.get(offset..offset + 4)?)?;
+
+    let xing = offset + 4 + frame.side_info_size;
+    if matches!(data.get(xing..xing + 4), Some(b"Xing") | Some(b"Info")) {
+        let flags = data.get(xing + 4..xing + 8)?;
+        if flags[3] & 0x1 != 0 {
+            let frames = data.get(xing + 8..xing + 12)?;
+            let frames = u32::from_be_bytes([frames[0], frames[1], frames[2], frames[3]]);
+
+            return Some(Duration::from_secs_f64(
+                frames as f64 * frame.samples_per_frame as f64 / frame.sample_rate as f64,
+            ));
+        }
+    }
+
+    let audio_size = data.len() - offset;
+    Some(Duration::from_secs_f64(
+        audio_size as f64 * 8.0 / frame.bitrate as f64,
+    ))
+}
+
+/// An MPEG audio frame header.
+struct Mp3Frame {
+    sample_rate: u32,
+    bitrate: u32,
+    samples_per_frame: u32,
+    side_info_size: usize,
+    length: usize,
+}
+
+impl Mp3Frame {
+    fn parse(header: &[u8]) -> Option<Mp3Frame> {
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+
+        let version = (header[1] >> 3) & 0x3;
+        let layer = (header[1] >> 1) & 0x3;
+        let bitrate_index = (header[2] >> 4) as usize;
+        let sample_rate_index = ((header[2] >> 2) & 0x3) as usize;
+        let padding = ((header[2] >> 1) & 0x1) as usize;
+        let mono = header[3] >> 6 == 3;
+
+        let sample_rate = match (version, sample_rate_index) {
+            (3, i) if i < 3 => [44100, 48000, 32000][i],
+            (2, i) if i < 3 => [22050, 24000, 16000][i],
+            (0, i) if i < 3 => [11025, 12000, 8000][i],
+            _ => return None,
+        };
+
+        let mpeg1 = version == 3;
+        let bitrates: [u32; 14] = match (mpeg1, layer) {
+            (true, 3) => [
+                32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+            ],
+            (true, 2) => [
+                32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+            ],
+            (true, 1) => [
+                32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+            ],
+            (false, 3) => [
+                32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+            ],
+            (false, 1 | 2) => [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+            _ => return None,
+        };
+        let bitrate = *bitrates.get(bitrate_index.checked_sub(1)?)? * 1000;
+
+        let samples_per_frame = match (layer, mpeg1) {
+            (3, _) => 384,
+            (2, _) | (1, true) => 1152,
+            _ => 576,
+        };
+
+        let side_info_size = match (mpeg1, mono) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        };
+
+        // Layer I frames consist of 4 byte slots, the other layers of single bytes
+        let length = if layer == 3 {
+            (12 * bitrate as usize / sample_rate as usize + padding) * 4
+        } else {
+            samples_per_frame as usize / 8 * bitrate as usize / sample_rate as usize + padding
+        };
+
+        Some(Mp3Frame {
+            sample_rate,
+            bitrate,
+            samples_per_frame,
+            side_info_size,
+            length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContentType, recording_duration};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn guid_bytes(value: &str) -> [u8; 16] {
+        Uuid::parse_str(value).unwrap().to_bytes_le()
+    }
+
+    #[test]
+    fn test_asf_duration() {
+        let mut file_properties = vec![];
+        file_properties.extend(guid_bytes("8CABDCA1-A947-11CF-8EE4-00C00C205365"));
+        file_properties.extend(104u64.to_le_bytes());
+        file_properties.extend([0; 16]); // File ID
+        file_properties.extend(0u64.to_le_bytes()); // File size
+        file_properties.extend(0u64.to_le_bytes()); // Creation date
+        file_properties.extend(0u64.to_le_bytes()); // Data packets
+        file_properties.extend(125_000_000u64.to_le_bytes()); // Play duration
+        file_properties.extend(0u64.to_le_bytes()); // Send duration
+        file_properties.extend(3000u64.to_le_bytes()); // Preroll
+        file_properties.extend([0; 16]);
+
+        let mut unknown = vec![];
+        unknown.extend(guid_bytes("B7DC0791-A9B7-11CF-8EE6-00C00C205365"));
+        unknown.extend(28u64.to_le_bytes());
+        unknown.extend([0; 4]);
+
+        let mut data = vec![];
+        data.extend(guid_bytes("75B22630-668E-11CF-A6D9-00AA0062CE6C"));
+        data.extend(0u64.to_le_bytes());
+        data.extend(2u32.to_le_bytes());
+        data.extend([1, 2]);
+        data.extend(unknown);
+        data.extend(file_properties);
+
+        assert_eq!(
+            recording_duration(&data),
+            Some(Duration::from_millis(9_500))
+        );
+    }
+
+    #[test]
+    fn test_wav_duration() {
+        let mut data = vec![];
+        data.extend(b"RIFF");
+        data.extend(0u32.to_le_bytes());
+        data.extend(b"WAVE");
+        data.extend(b"fmt ");
+        data.extend(16u32.to_le_bytes());
+        data.extend(1u16.to_le_bytes()); // PCM
+        data.extend(1u16.to_le_bytes()); // Channels
+        data.extend(8000u32.to_le_bytes()); // Sample rate
+        data.extend(16000u32.to_le_bytes()); // Byte rate
+        data.extend(2u16.to_le_bytes()); // Block align
+        data.extend(16u16.to_le_bytes()); // Bits per sample
+        data.extend(b"data");
+        data.extend(32000u32.to_le_bytes());
+
+        assert_eq!(recording_duration(&data), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_mp3_duration() {
+        // MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, stereo
+        let frame_header = [0xFF, 0xFB, 0x90, 0x00];
+
+        let mut data = vec![];
+        data.extend(b"ID3");
+        data.extend([4, 0, 0, 0, 0, 0, 2]);
+        data.extend([0; 2]);
+        data.extend(frame_header);
+        data.extend(vec![0; 160_000 - 4]);
+
+        assert_eq!(recording_duration(&data), Some(Duration::from_secs(10)));
+
+        let mut data = vec![];
+        data.extend(frame_header);
+        data.extend([0; 32]);
+        data.extend(b"Xing");
+        data.extend(1u32.to_be_bytes());
+        data.extend(1000u32.to_be_bytes());
+
+        assert_eq!(
+            recording_duration(&data),
+            Some(Duration::from_secs_f64(1000.0 * 1152.0 / 44100.0))
+        );
+    }
+
+    fn zip_with_entry(name: &str) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(b"PK\x03\x04");
+        data.extend([0; 14]);
+        data.extend(0u32.to_le_bytes()); // Compressed size
+        data.extend(0u32.to_le_bytes()); // Uncompressed size
+        data.extend((name.len() as u16).to_le_bytes());
+        data.extend(0u16.to_le_bytes());
+        data.extend(name.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_detect_content_type() {
+        let mut emf = vec![1, 0, 0, 0];
+        emf.extend([0; 36]);
+        emf.extend(b" EMF");
+
+        assert_eq!(
+            ContentType::detect(b"\x89PNG\r\n\x1A\n\0\0"),
+            Some(ContentType::Png)
+        );
+        assert_eq!(
+            ContentType::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ContentType::Jpeg)
+        );
+        assert_eq!(ContentType::detect(b"GIF89a"), Some(ContentType::Gif));
+        assert_eq!(ContentType::detect(&emf), Some(ContentType::Emf));
+        assert_eq!(
+            ContentType::detect(&[0xD7, 0xCD, 0xC6, 0x9A, 0, 0]),
+            Some(ContentType::Wmf)
+        );
+        assert_eq!(ContentType::detect(b"%PDF-1.7"), Some(ContentType::Pdf));
+        assert_eq!(
+            ContentType::detect(&zip_with_entry("word/document.xml")),
+            Some(ContentType::Docx)
+        );
+        assert_eq!(
+            ContentType::detect(&zip_with_entry("FixedDocSeq.fdseq")),
+            Some(ContentType::Xps)
+        );
+        assert_eq!(
+            ContentType::detect(&zip_with_entry("notes.txt")),
+            Some(ContentType::Zip)
+        );
+        assert_eq!(
+            ContentType::detect(b"\0\0\0\x20ftypisom"),
+            Some(ContentType::Mp4)
+        );
+        assert_eq!(ContentType::detect(b"Hello world"), None);
+    }
+
+    #[test]
+    fn test_detect_bmp() {
+        let mut bmp = vec![];
+        bmp.extend(b"BM");
+        bmp.extend(70u32.to_le_bytes()); // File size
+        bmp.extend([0; 4]);
+        bmp.extend(54u32.to_le_bytes()); // Pixel data offset
+        bmp.extend(40u32.to_le_bytes()); // DIB header size
+        bmp.extend([0; 36]);
+
+        assert_eq!(ContentType::detect(&bmp), Some(ContentType::Bmp));
+
+        // Arbitrary data starting with "BM"
+        assert_eq!(ContentType::detect(b"BMP images are bitmaps"), None);
+    }
+
+    #[test]
+    fn test_detect_mp3() {
+        // MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, stereo: 417 bytes per frame
+        let frame_header = [0xFF, 0xFB, 0x90, 0x00];
+        let mut frame = frame_header.to_vec();
+        frame.resize(417, 0);
+
+        assert_eq!(
+            ContentType::detect(&[frame.clone(), frame.clone()].concat()),
+            Some(ContentType::Mp3)
+        );
+        assert_eq!(
+            ContentType::detect(b"ID3\x04\0\0\0\0\0\0"),
+            Some(ContentType::Mp3)
+        );
+
+        // A single frame sync isn't enough
+        assert_eq!(ContentType::detect(&frame), None);
+        assert_eq!(ContentType::detect(&[frame, vec![0; 417]].concat()), None);
+    }
+
+    #[test]
+    fn test_detect_asf() {
+        let asf = |header_size: u64, body: &[u8]| {
+            let mut data = vec![];
+            data.extend(guid_bytes("75B22630-668E-11CF-A6D9-00AA0062CE6C"));
+            data.extend(header_size.to_le_bytes());
+            data.extend([0; 6]);
+            data.extend(body);
+            data
+        };
+        let video = guid_bytes("BC19EFC0-5B4D-11CF-A8FD-00805F5C442B");
+
+        assert_eq!(
+            ContentType::detect(&asf(46, &video)),
+            Some(ContentType::Wmv)
+        );
+        assert_eq!(ContentType::detect(&asf(30, &[])), Some(ContentType::Wma));
+
+        // The video stream type is only checked in the header object
+        assert_eq!(
+            ContentType::detect(&asf(30, &video)),
+            Some(ContentType::Wma)
+        );
+    }
+
+    #[test]
+    fn test_content_type_matches_name() {
+        assert!(ContentType::Jpeg.matches_name(".jpg"));
+        assert!(ContentType::Jpeg.matches_name("JPEG"));
+        assert!(ContentType::Jpeg.matches_name("photo.JPG"));
+        assert!(!ContentType::Png.matches_name("photo.jpg"));
+        assert!(!ContentType::Docx.matches_name("report"));
+        assert_eq!(ContentType::Jpeg.extension(), "jpg");
+        assert_eq!(ContentType::Jpeg.mime_type(), "image/jpeg");
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        assert_eq!(recording_duration(b"not a recording"), None);
+        assert_eq!(recording_duration(&[]), None);
+    }
+}
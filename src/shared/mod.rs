@@ -1,2 +1,4 @@
+pub(crate) mod file_data;
 pub(crate) mod guid;
+pub(crate) mod media;
 pub(crate) mod multi_byte;
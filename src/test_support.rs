@@ -0,0 +1,735 @@
+//! Synthetic OneStore files for unit tests and benchmarks.
+//!
+//! The builders describe a OneStore file in terms of object spaces and objects with property
+//! sets. [`StoreBuilder::build`] turns the description into a FSSHTTPB package with a storage
+//! index, a storage manifest, and a cell manifest, revision manifest and object group per
+//! object space. This allows testing particular property combinations without binary fixtures.
+
+use crate::fsshttpb::data::cell_id::CellId;
+use crate::fsshttpb::data::exguid::ExGuid;
+use crate::fsshttpb::packaging::OneStorePackaging;
+use crate::fsshttpb::writer::{Declaration, Element, GroupData, PropSet, Serial, Value, Writer};
+use crate::one::property::PropertyType;
+use crate::one::property_set::PropertySetId;
+use crate::onestore::object::Object;
+use crate::onestore::object_space::ObjectSpace;
+use crate::onestore::parse_store;
+use crate::reader::Reader;
+use crate::shared::guid::Guid;
+
+/// Create an object ID.
+///
+/// IDs starting at `0xFFFF` are reserved for the data elements and the header object.
+pub(crate) fn id(value: u32) -> ExGuid {
+    ExGuid::from_guid(id_guid(), value)
+}
+
+/// The GUID used for the IDs of data elements and objects.
+fn id_guid() -> Guid {
+    guid!("3E8AB4D2-5C8E-4B1A-9E4B-2D6F0C3A7B91")
+}
+
+/// A OneStore file.
+pub(crate) struct StoreBuilder {
+    data_root: SpaceBuilder,
+    spaces: Vec<SpaceBuilder>,
+    shared_revisions: u32,
+    shared_objects: u32,
+}
+
+impl StoreBuilder {
+    /// Create a section file with the object space as its data root.
+    pub(crate) fn section(data_root: SpaceBuilder) -> StoreBuilder {
+        StoreBuilder {
+            data_root,
+            spaces: vec![],
+            shared_revisions: 0,
+            shared_objects: 0,
+        }
+    }
+
+    /// Add an object space besides the data root (e.g. a page).
+    pub(crate) fn space(mut self, space: SpaceBuilder) -> StoreBuilder {
+        self.spaces.push(space);
+        self
+    }
+
+    /// Base the object spaces besides the data root on a chain of `revisions` revisions.
+    ///
+    /// Each revision of the chain adds an object group with `objects` objects and references
+    /// the object groups of all previous revisions, so the objects are shared by all
+    /// revisions of the chain and by all object spaces that are based on it.
+    pub(crate) fn shared_revisions(mut self, revisions: u32, objects: u32) -> StoreBuilder {
+        self.shared_revisions = revisions;
+        self.shared_objects = objects;
+        self
+    }
+
+    /// Encode the file as a FSSHTTPB package.
+    pub(crate) fn build(&self) -> Vec<u8> {
+        let schema = guid!("1F937CB4-B26F-445F-B9F8-17E20160E461");
+        let mut ids = IdAllocator(0x10000);
+        let mut elements = vec![];
+        let mut cell_mappings = vec![];
+        let mut revision_mappings = vec![];
+
+        let header = SpaceBuilder::new(0xFFFF).serial(1).object(
+            ObjectBuilder::with_jcid(id(0xFFFF), 0)
+                .prop(
+                    PropertyType::FileIdentityGuid,
+                    Value::Vec(id_guid().0.to_bytes_le().to_vec()),
+                )
+                .prop(
+                    PropertyType::FileAncestorIdentityGuid,
+                    Value::Vec(Guid::nil().0.to_bytes_le().to_vec()),
+                )
+                .prop(PropertyType::FileNameCrc, Value::U32(0)),
+        );
+
+        for space in [&header, &self.data_root] {
+            let (cell_mapping, revision_mapping) =
+                space.put_revision(None, &mut ids, &mut elements);
+            cell_mappings.push(cell_mapping);
+            revision_mappings.push(revision_mapping);
+        }
+
+        let base_rev = self.put_shared_revisions(&mut ids, &mut elements, &mut revision_mappings);
+
+        for space in &self.spaces {
+            let (cell_mapping, revision_mapping) =
+                space.put_revision(base_rev, &mut ids, &mut elements);
+            cell_mappings.push(cell_mapping);
+            revision_mappings.push(revision_mapping);
+        }
+
+        let storage_index_id = ids.next();
+        elements.push((
+            storage_index_id,
+            Serial::Null,
+            Element::StorageIndex {
+                manifest_mappings: vec![],
+                cell_mappings,
+                revision_mappings,
+            },
+        ));
+        elements.push((
+            ids.next(),
+            Serial::Null,
+            Element::StorageManifest {
+                schema,
+                roots: vec![
+                    (
+                        exguid!({{"1A5A319C-C26B-41AA-B9C5-9BD8C44E07D4"}, 1}),
+                        header.cell_id(),
+                    ),
+                    (
+                        exguid!({{"84DEFAB9-AAA3-4A0D-A3A8-520C77AC7073"}, 2}),
+                        self.data_root.cell_id(),
+                    ),
+                ],
+            },
+        ));
+
+        let mut writer = Writer::new();
+        writer.put_packaging(Guid::nil(), storage_index_id, schema, &elements);
+        writer.into_bytes()
+    }
+
+    /// Add the data elements of the shared revision chain.
+    ///
+    /// Returns the revision ID of the newest revision of the chain.
+    fn put_shared_revisions(
+        &self,
+        ids: &mut IdAllocator,
+        elements: &mut Vec<(ExGuid, Serial, Element)>,
+        revision_mappings: &mut Vec<(ExGuid, ExGuid, Serial)>,
+    ) -> Option<ExGuid> {
+        let mut base_rev = None;
+        let mut group_references = vec![];
+
+        for revision in 0..self.shared_revisions {
+            let revision_id = ids.next();
+            let revision_manifest_id = ids.next();
+            let group_id = ids.next();
+
+            let space = (0..self.shared_objects).fold(SpaceBuilder::new(0), |space, index| {
+                let value = 0x8000 + revision * self.shared_objects + index;
+
+                space.object(
+                    ObjectBuilder::with_jcid(id(value), 0x0006_0099)
+                        .string(PropertyType::CachedTitleString, "Shared"),
+                )
+            });
+
+            let (group, blobs) = space.object_group(ids);
+            elements.push((group_id, Serial::Null, group));
+            elements.extend(blobs);
+
+            group_references.push(group_id);
+            elements.push((
+                revision_manifest_id,
+                Serial::Null,
+                Element::RevisionManifest {
+                    rev_id: revision_id,
+                    base_rev_id: base_rev.unwrap_or_else(|| ExGuid::from_guid(Guid::nil(), 0)),
+                    roots: vec![],
+                    group_references: group_references.clone(),
+                },
+            ));
+            revision_mappings.push((revision_id, revision_manifest_id, Serial::Null));
+
+            base_rev = Some(revision_id);
+        }
+
+        base_rev
+    }
+}
+
+/// Encode an incremental update with a new revision of each object space.
+///
+/// The update is a FSSHTTPB data element package with its own storage index.
+/// Updates with different `generation`s (starting at 1) don't share data
+/// element IDs with each other or with files built by [`StoreBuilder::build`].
+pub(crate) fn build_update(generation: u32, spaces: &[SpaceBuilder]) -> Vec<u8> {
+    let mut ids = IdAllocator(0x10000 * (generation + 1));
+    let mut elements = vec![];
+    let mut cell_mappings = vec![];
+    let mut revision_mappings = vec![];
+
+    for space in spaces {
+        let (cell_mapping, revision_mapping) = space.put_revision(None, &mut ids, &mut elements);
+        cell_mappings.push(cell_mapping);
+        revision_mappings.push(revision_mapping);
+    }
+
+    elements.push((
+        ids.next(),
+        Serial::Null,
+        Element::StorageIndex {
+            manifest_mappings: vec![],
+            cell_mappings,
+            revision_mappings,
+        },
+    ));
+
+    let mut writer = Writer::new();
+    writer.put_data_element_package(&elements);
+    writer.into_bytes()
+}
+
+/// A OneStore object space.
+pub(crate) struct SpaceBuilder {
+    id: ExGuid,
+    serial: u64,
+    content_root: Option<ExGuid>,
+    metadata_root: Option<ExGuid>,
+    objects: Vec<ObjectBuilder>,
+}
+
+impl SpaceBuilder {
+    pub(crate) fn new(value: u32) -> SpaceBuilder {
+        SpaceBuilder {
+            id: id(value),
+            serial: 0,
+            content_root: None,
+            metadata_root: None,
+            objects: vec![],
+        }
+    }
+
+    /// The cell ID that is used to reference the object space.
+    pub(crate) fn cell_id(&self) -> CellId {
+        CellId(id(0), self.id)
+    }
+
+    /// Set the serial number of the object space's cell.
+    ///
+    /// Cells without a serial number (the default) have a null serial number.
+    pub(crate) fn serial(mut self, serial: u64) -> SpaceBuilder {
+        self.serial = serial;
+        self
+    }
+
+    fn cell_serial(&self) -> Serial {
+        match self.serial {
+            0 => Serial::Null,
+            serial => Serial::Value(id_guid(), serial),
+        }
+    }
+
+    /// Add an object and declare it as the root of the object space's content.
+    pub(crate) fn content_root(mut self, object: ObjectBuilder) -> SpaceBuilder {
+        self.content_root = Some(object.id);
+        self.object(object)
+    }
+
+    /// Add an object and declare it as the root of the object space's metadata.
+    pub(crate) fn metadata_root(mut self, object: ObjectBuilder) -> SpaceBuilder {
+        self.metadata_root = Some(object.id);
+        self.object(object)
+    }
+
+    pub(crate) fn object(mut self, object: ObjectBuilder) -> SpaceBuilder {
+        self.objects.push(object);
+        self
+    }
+
+    /// Add the data elements of a revision that contains all objects.
+    ///
+    /// Returns the storage index's cell mapping and revision mapping.
+    fn put_revision(
+        &self,
+        base_rev: Option<ExGuid>,
+        ids: &mut IdAllocator,
+        elements: &mut Vec<(ExGuid, Serial, Element)>,
+    ) -> ((CellId, ExGuid, Serial), (ExGuid, ExGuid, Serial)) {
+        let cell_manifest_id = ids.next();
+        let revision_id = ids.next();
+        let revision_manifest_id = ids.next();
+        let group_id = ids.next();
+
+        let mut roots = vec![];
+        if let Some(root) = self.content_root {
+            roots.push((revision_role(1), root));
+        }
+        if let Some(root) = self.metadata_root {
+            roots.push((revision_role(2), root));
+        }
+
+        elements.push((
+            cell_manifest_id,
+            Serial::Null,
+            Element::CellManifest {
+                revision: revision_id,
+            },
+        ));
+        elements.push((
+            revision_manifest_id,
+            Serial::Null,
+            Element::RevisionManifest {
+                rev_id: revision_id,
+                base_rev_id: base_rev.unwrap_or_else(|| ExGuid::from_guid(Guid::nil(), 0)),
+                roots,
+                group_references: vec![group_id],
+            },
+        ));
+
+        let (group, blobs) = self.object_group(ids);
+        elements.push((group_id, Serial::Null, group));
+        elements.extend(blobs);
+
+        (
+            (self.cell_id(), cell_manifest_id, self.cell_serial()),
+            (revision_id, revision_manifest_id, Serial::Null),
+        )
+    }
+
+    fn object_group(&self, ids: &mut IdAllocator) -> (Element, Vec<(ExGuid, Serial, Element)>) {
+        let mut declarations = vec![];
+        let mut objects = vec![];
+        let mut blobs = vec![];
+
+        for object in &self.objects {
+            let mut writer = Writer::new();
+            writer.put_object_prop_set(&object.prop_set);
+            let data = writer.into_bytes();
+
+            declarations.push(Declaration::Object {
+                object_id: object.id,
+                partition_id: 4,
+                data_size: 4,
+                object_reference_count: 0,
+                cell_reference_count: 0,
+            });
+            objects.push(GroupData::Object {
+                group: vec![],
+                cells: vec![],
+                data: object.jcid.to_le_bytes().to_vec(),
+            });
+
+            declarations.push(Declaration::Object {
+                object_id: object.id,
+                partition_id: 1,
+                data_size: data.len() as u64,
+                object_reference_count: object.object_refs.len() as u64,
+                cell_reference_count: object.cell_refs.len() as u64,
+            });
+            objects.push(GroupData::Object {
+                group: object.object_refs.clone(),
+                cells: object.cell_refs.clone(),
+                data,
+            });
+
+            if let Some(file_data) = &object.file_data {
+                let blob_id = ids.next();
+
+                declarations.push(Declaration::Blob {
+                    object_id: object.id,
+                    blob_id,
+                    partition_id: 2,
+                    object_reference_count: 0,
+                    cell_reference_count: 0,
+                });
+                objects.push(GroupData::BlobReference {
+                    objects: vec![],
+                    cells: vec![],
+                    blob: blob_id,
+                });
+                blobs.push((
+                    blob_id,
+                    Serial::Null,
+                    Element::Blob {
+                        data: file_data.clone(),
+                    },
+                ));
+            }
+        }
+
+        let group = Element::ObjectGroup {
+            declarations,
+            metadata: None,
+            objects,
+        };
+
+        (group, blobs)
+    }
+}
+
+/// A OneStore object with its property set.
+///
+/// References to other objects and object spaces are resolved using the
+/// object's mapping table, so they must be added in property order.
+pub(crate) struct ObjectBuilder {
+    id: ExGuid,
+    jcid: u32,
+    prop_set: PropSet,
+    object_refs: Vec<ExGuid>,
+    cell_refs: Vec<CellId>,
+    file_data: Option<Vec<u8>>,
+}
+
+impl ObjectBuilder {
+    pub(crate) fn new(id: ExGuid, jcid: PropertySetId) -> ObjectBuilder {
+        Self::with_jcid(id, jcid.as_jcid().0)
+    }
+
+    pub(crate) fn with_jcid(id: ExGuid, jcid: u32) -> ObjectBuilder {
+        ObjectBuilder {
+            id,
+            jcid,
+            prop_set: PropSet::default(),
+            object_refs: vec![],
+            cell_refs: vec![],
+            file_data: None,
+        }
+    }
+
+    pub(crate) fn prop(mut self, prop_type: PropertyType, value: Value) -> ObjectBuilder {
+        self.prop_set.properties.push((prop_type as u32, value));
+        self
+    }
+
+    /// Add a UTF-16 string property.
+    pub(crate) fn string(self, prop_type: PropertyType, value: &str) -> ObjectBuilder {
+        let data = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+
+        self.prop(prop_type, Value::Vec(data))
+    }
+
+    /// Add a reference to another object.
+    pub(crate) fn object_ref(mut self, prop_type: PropertyType, id: ExGuid) -> ObjectBuilder {
+        self.add_object_refs(&[id]);
+        self.prop(prop_type, Value::ObjectId)
+    }
+
+    /// Add an array of references to other objects.
+    pub(crate) fn object_refs(mut self, prop_type: PropertyType, ids: &[ExGuid]) -> ObjectBuilder {
+        self.add_object_refs(ids);
+        self.prop(prop_type, Value::ObjectIds(ids.len() as u32))
+    }
+
+    /// Add an array of references to object spaces.
+    pub(crate) fn space_refs(mut self, prop_type: PropertyType, ids: &[CellId]) -> ObjectBuilder {
+        for id in ids {
+            self.cell_refs.push(*id);
+            self.prop_set
+                .object_space_ids
+                .push(compact_id(self.prop_set.object_space_ids.len()));
+        }
+
+        self.prop(prop_type, Value::ObjectSpaceIds(ids.len() as u32))
+    }
+
+    /// Attach file data (e.g. an image) to the object.
+    pub(crate) fn file_data(mut self, data: &[u8]) -> ObjectBuilder {
+        self.file_data = Some(data.to_vec());
+        self
+    }
+
+    fn add_object_refs(&mut self, ids: &[ExGuid]) {
+        for id in ids {
+            self.object_refs.push(*id);
+            self.prop_set
+                .object_ids
+                .push(compact_id(self.prop_set.object_ids.len()));
+        }
+    }
+}
+
+/// Parse a store with the object space as its data root and pass the space to `f`.
+pub(crate) fn with_space<T>(space: SpaceBuilder, f: impl FnOnce(&ObjectSpace) -> T) -> T {
+    let data = StoreBuilder::section(space).build();
+
+    let packaging = OneStorePackaging::parse(&mut Reader::new(&data)).unwrap();
+    let store = parse_store(&packaging).unwrap();
+
+    f(store.data_root())
+}
+
+/// Parse a store that only contains the object and pass it to `f`.
+pub(crate) fn with_object<T>(object: ObjectBuilder, f: impl FnOnce(&Object) -> T) -> T {
+    let object_id = object.id;
+
+    with_space(SpaceBuilder::new(1).content_root(object), |space| {
+        f(space.get_object(object_id).unwrap())
+    })
+}
+
+/// A unique compact ID for the reference at `index`.
+fn compact_id(index: usize) -> u32 {
+    (index as u32 + 1) << 8
+}
+
+fn revision_role(value: u32) -> ExGuid {
+    ExGuid::from_guid(guid!("4A3717F8-1C14-49E7-9526-81D942DE1741"), value)
+}
+
+/// Create a page object space with a page of the given height.
+///
+/// The page contains one content object with the unsupported JCID `0x00060099`.
+pub(crate) fn page_space(value: u32, serial: u64, height: f32) -> SpaceBuilder {
+    subpage_space(value, serial, height, 0)
+}
+
+/// Create a page object space like [`page_space`] with the given page level.
+pub(crate) fn subpage_space(value: u32, serial: u64, height: f32, level: u32) -> SpaceBuilder {
+    SpaceBuilder::new(value)
+        .serial(serial)
+        .metadata_root(
+            ObjectBuilder::new(id(1), PropertySetId::PageMetadata)
+                .prop(
+                    PropertyType::NotebookManagementEntityGuid,
+                    Value::Vec(vec![0; 16]),
+                )
+                .string(PropertyType::CachedTitleString, "Page")
+                .prop(PropertyType::TopologyCreationTimeStamp, Value::U64(0))
+                .prop(PropertyType::PageLevel, Value::U32(level)),
+        )
+        .content_root(
+            ObjectBuilder::new(id(2), PropertySetId::PageManifestNode)
+                .object_refs(PropertyType::ContentChildNodes, &[id(3)]),
+        )
+        .object(
+            ObjectBuilder::new(id(3), PropertySetId::PageNode)
+                .prop(PropertyType::PageHeight, Value::U32(height.to_bits()))
+                .object_refs(PropertyType::ElementChildNodes, &[id(4)]),
+        )
+        .object(ObjectBuilder::with_jcid(id(4), 0x0006_0099))
+}
+
+/// Create the data root of a section with a single page series.
+pub(crate) fn section_root(serial: u64, pages: &[CellId]) -> SpaceBuilder {
+    section_root_with_series(serial, &[pages])
+}
+
+/// Create the data root of a section with a page series for each list of pages.
+pub(crate) fn section_root_with_series(serial: u64, series: &[&[CellId]]) -> SpaceBuilder {
+    let series_ids: Vec<_> = (0..series.len() as u32)
+        .map(|index| id(4 + index))
+        .collect();
+
+    let root = SpaceBuilder::new(1)
+        .serial(serial)
+        .metadata_root(
+            ObjectBuilder::new(id(2), PropertySetId::SectionMetadata)
+                .prop(PropertyType::SchemaRevisionInOrderToRead, Value::U32(0))
+                .prop(PropertyType::SchemaRevisionInOrderToWrite, Value::U32(0))
+                .string(PropertyType::SectionDisplayName, "Synthetic"),
+        )
+        .content_root(
+            ObjectBuilder::new(id(3), PropertySetId::SectionNode)
+                .prop(
+                    PropertyType::NotebookManagementEntityGuid,
+                    Value::Vec(vec![0; 16]),
+                )
+                .prop(PropertyType::TopologyCreationTimeStamp, Value::U64(0))
+                .object_refs(PropertyType::ElementChildNodes, &series_ids),
+        );
+
+    series_ids
+        .iter()
+        .zip(series)
+        .fold(root, |root, (id, pages)| {
+            root.object(
+                ObjectBuilder::new(*id, PropertySetId::PageSeriesNode)
+                    .prop(
+                        PropertyType::NotebookManagementEntityGuid,
+                        Value::Vec(vec![0; 16]),
+                    )
+                    .space_refs(PropertyType::ChildGraphSpaceElementNodes, pages),
+            )
+        })
+}
+
+/// Encode a section file with the given page object spaces.
+pub(crate) fn section_store(pages: Vec<SpaceBuilder>) -> Vec<u8> {
+    let page_ids: Vec<_> = pages.iter().map(SpaceBuilder::cell_id).collect();
+
+    pages
+        .into_iter()
+        .fold(
+            StoreBuilder::section(section_root(1, &page_ids)),
+            StoreBuilder::space,
+        )
+        .build()
+}
+
+struct IdAllocator(u32);
+
+impl IdAllocator {
+    fn next(&mut self) -> ExGuid {
+        self.0 += 1;
+
+        id(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ObjectBuilder, SpaceBuilder, StoreBuilder, id, page_space, section_root, with_object,
+    };
+    use crate::Parser;
+    use crate::fsshttpb::packaging::OneStorePackaging;
+    use crate::fsshttpb::writer::Value;
+    use crate::one::property::PropertyType;
+    use crate::one::property::object_reference::ObjectReference;
+    use crate::one::property::object_space_reference::ObjectSpaceReference;
+    use crate::one::property_set::PropertySetId;
+    use crate::onestore::parse_store;
+    use crate::reader::Reader;
+    use std::path::Path;
+
+    fn section_metadata() -> ObjectBuilder {
+        ObjectBuilder::new(id(2), PropertySetId::SectionMetadata)
+            .prop(PropertyType::SchemaRevisionInOrderToRead, Value::U32(0))
+            .prop(PropertyType::SchemaRevisionInOrderToWrite, Value::U32(0))
+            .string(PropertyType::SectionDisplayName, "Synthetic")
+    }
+
+    fn section_node() -> ObjectBuilder {
+        ObjectBuilder::new(id(3), PropertySetId::SectionNode)
+            .prop(
+                PropertyType::NotebookManagementEntityGuid,
+                Value::Vec(vec![0; 16]),
+            )
+            .prop(PropertyType::TopologyCreationTimeStamp, Value::U64(0))
+    }
+
+    #[test]
+    fn test_store_references() {
+        let page = SpaceBuilder::new(20);
+        let data = StoreBuilder::section(
+            SpaceBuilder::new(1)
+                .content_root(
+                    ObjectBuilder::new(id(10), PropertySetId::PageSeriesNode)
+                        .object_refs(PropertyType::ElementChildNodes, &[id(11), id(12)])
+                        .space_refs(PropertyType::ChildGraphSpaceElementNodes, &[page.cell_id()]),
+                )
+                .object(ObjectBuilder::new(id(11), PropertySetId::PageNode))
+                .object(ObjectBuilder::new(id(12), PropertySetId::PageNode).file_data(&[1, 2])),
+        )
+        .space(page)
+        .build();
+
+        let packaging = OneStorePackaging::parse(&mut Reader::new(&data)).unwrap();
+        let store = parse_store(&packaging).unwrap();
+
+        let space = store.data_root();
+        let object = space.get_object(space.content_root().unwrap()).unwrap();
+
+        assert_eq!(
+            ObjectReference::parse_vec(PropertyType::ElementChildNodes, object).unwrap(),
+            Some(vec![id(11), id(12)])
+        );
+
+        let pages =
+            ObjectSpaceReference::parse_vec(PropertyType::ChildGraphSpaceElementNodes, object)
+                .unwrap();
+        assert_eq!(pages, Some(vec![SpaceBuilder::new(20).cell_id()]));
+        assert!(
+            store
+                .object_space(SpaceBuilder::new(20).cell_id())
+                .is_some()
+        );
+
+        assert_eq!(
+            space.get_object(id(12)).unwrap().file_data(),
+            Some(&[1, 2][..])
+        );
+    }
+
+    #[test]
+    fn test_shared_revisions() {
+        let page_ids = [
+            page_space(20, 2, 10.0).cell_id(),
+            page_space(21, 3, 10.0).cell_id(),
+        ];
+        let data = StoreBuilder::section(section_root(1, &page_ids))
+            .space(page_space(20, 2, 10.0))
+            .space(page_space(21, 3, 10.0))
+            .shared_revisions(3, 2)
+            .build();
+
+        let packaging = OneStorePackaging::parse(&mut Reader::new(&data)).unwrap();
+        let store = parse_store(&packaging).unwrap();
+
+        for page_id in page_ids {
+            let space = store.object_space(page_id).unwrap();
+
+            assert_eq!(space.objects().count(), 4 + 3 * 2);
+            assert!(space.get_object(id(0x8000 + 5)).is_some());
+            assert!(space.content_root().is_some());
+        }
+        assert!(store.data_root().get_object(id(0x8000)).is_none());
+
+        let section = Parser::new()
+            .parse_section_buffer(&data, Path::new("Synthetic.one"))
+            .unwrap();
+        assert_eq!(section.page_series()[0].pages().len(), 2);
+    }
+
+    #[test]
+    fn test_with_object() {
+        let jcid = with_object(section_node(), |object| object.id());
+
+        assert_eq!(jcid, PropertySetId::SectionNode.as_jcid());
+    }
+
+    #[test]
+    fn test_parse_empty_section() {
+        let data = StoreBuilder::section(
+            SpaceBuilder::new(1)
+                .content_root(section_node())
+                .metadata_root(section_metadata()),
+        )
+        .build();
+
+        let section = Parser::new()
+            .parse_section_buffer(&data, Path::new("Test.one"))
+            .unwrap();
+
+        assert_eq!(section.display_name(), "Synthetic");
+        assert!(section.page_series().is_empty());
+    }
+}
@@ -0,0 +1,857 @@
+//! Health checks for notebooks and sections.
+//!
+//! The checks report problems that don't prevent a notebook from being parsed
+//! but that users should know about, e.g. table of contents entries whose
+//! section files are missing or links to pages that no longer exist.
+//!
+//! [`validate_notebook`] and [`validate_section`] check parsed notebooks and
+//! sections. Deleted pages are found by `validate_store` (with the `raw`
+//! feature), which checks all object spaces of a section file including the
+//! pages that parsed sections don't contain.
+//!
+//! ```no_run
+//! use onenote_parser::Parser;
+//! use onenote_parser::validation::{Severity, validate_notebook};
+//! use std::path::Path;
+//!
+//! let notebook = Parser::new()
+//!     .parse_notebook(Path::new("Notebook/Open Notebook.onetoc2"))
+//!     .unwrap();
+//!
+//! let report = validate_notebook(&notebook);
+//! for issue in report.issues_with(Severity::Warning) {
+//!     println!("{issue}");
+//! }
+//! ```
+
+use crate::onenote::content::Content;
+use crate::onenote::embedded_file::EmbeddedFile;
+use crate::onenote::image::Image;
+use crate::onenote::notebook::Notebook;
+use crate::onenote::outline::{OutlineElement, OutlineItem};
+use crate::onenote::page::Page;
+use crate::onenote::page_content::PageContent;
+use crate::onenote::section::{Section, SectionEntry};
+use crate::shared::guid::Guid;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+
+/// The newest schema revision of the section files that the parser supports.
+///
+/// Sections that require a newer revision to be read (see
+/// [`Section::schema_revision_in_order_to_read`]) may contain data the parser
+/// doesn't understand.
+pub const SUPPORTED_SCHEMA_REVISION: u32 = 40;
+
+/// The severity of an [`Issue`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The issue doesn't affect the notebook's content.
+    Info,
+    /// Content may be missing or inaccessible to users.
+    Warning,
+    /// Content is missing or can't be read reliably.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The kind of an [`Issue`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IssueKind {
+    /// A section file that the table of contents doesn't list.
+    OrphanedSection { path: PathBuf },
+    /// A table of contents entry whose section file or section group
+    /// directory doesn't exist.
+    MissingTocTarget { file_name: String },
+    /// A page that OneNote created for conflicting changes to another page.
+    ConflictPage { title: String },
+    /// A page that has been deleted but is still stored in the section file.
+    DeletedPage { title: String },
+    /// A link to a section or page of the notebook that doesn't exist.
+    BrokenLink { url: String },
+    /// An image without image data.
+    ImageWithoutData,
+    /// An embedded file without any data.
+    EmptyEmbeddedFile { file_name: String },
+    /// A section or page that requires a newer schema revision than
+    /// [`SUPPORTED_SCHEMA_REVISION`] to be read.
+    UnsupportedSchemaRevision { revision: u32 },
+}
+
+impl IssueKind {
+    /// The severity of issues of this kind.
+    pub fn severity(&self) -> Severity {
+        match self {
+            IssueKind::DeletedPage { .. } => Severity::Info,
+            IssueKind::OrphanedSection { .. }
+            | IssueKind::ConflictPage { .. }
+            | IssueKind::BrokenLink { .. }
+            | IssueKind::ImageWithoutData
+            | IssueKind::EmptyEmbeddedFile { .. } => Severity::Warning,
+            IssueKind::MissingTocTarget { .. } | IssueKind::UnsupportedSchemaRevision { .. } => {
+                Severity::Error
+            }
+        }
+    }
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueKind::OrphanedSection { path } => write!(
+                f,
+                "section file {} is not listed in the table of contents",
+                path.display()
+            ),
+            IssueKind::MissingTocTarget { file_name } => {
+                write!(f, "table of contents entry {file_name:?} doesn't exist")
+            }
+            IssueKind::ConflictPage { title } => write!(f, "page {title:?} is a conflict page"),
+            IssueKind::DeletedPage { title } => {
+                write!(f, "deleted page {title:?} is still stored in the file")
+            }
+            IssueKind::BrokenLink { url } => write!(f, "link target doesn't exist: {url}"),
+            IssueKind::ImageWithoutData => write!(f, "image has no data"),
+            IssueKind::EmptyEmbeddedFile { file_name } => {
+                write!(f, "embedded file {file_name:?} is empty")
+            }
+            IssueKind::UnsupportedSchemaRevision { revision } => write!(
+                f,
+                "schema revision {revision} is newer than the supported revision \
+                 {SUPPORTED_SCHEMA_REVISION}"
+            ),
+        }
+    }
+}
+
+/// A problem found by a health check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    kind: IssueKind,
+    location: Vec<String>,
+}
+
+impl Issue {
+    /// The kind of the issue.
+    pub fn kind(&self) -> &IssueKind {
+        &self.kind
+    }
+
+    /// The severity of the issue.
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+
+    /// The location of the affected object.
+    ///
+    /// The location consists of path segments starting at the outermost object,
+    /// e.g. `["section \"Notes\"", "page series 0", "page 2"]`. It's empty for
+    /// issues that affect the notebook or section that has been checked.
+    pub fn location(&self) -> &[String] {
+        &self.location
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.location.is_empty() {
+            write!(f, "{}: {}", self.severity(), self.kind)
+        } else {
+            write!(
+                f,
+                "{}: {}: {}",
+                self.severity(),
+                self.location.join(" > "),
+                self.kind
+            )
+        }
+    }
+}
+
+/// The issues found by a health check.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    issues: Vec<Issue>,
+}
+
+impl Report {
+    /// All issues in the order they were found.
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// The issues with at least the given severity.
+    pub fn issues_with(&self, severity: Severity) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.severity() >= severity)
+    }
+
+    /// The highest severity of the issues or `None` if there are no issues.
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.issues.iter().map(Issue::severity).max()
+    }
+
+    /// Whether no issues have been found.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check a parsed notebook.
+///
+/// This reports orphaned sections, missing table of contents entries, conflict
+/// pages, broken links, images without data, empty embedded files and
+/// unsupported schema revisions in all sections and section groups of the
+/// notebook.
+///
+/// Links are broken if they refer to a page that doesn't exist in the linked
+/// section of the notebook, or if they refer to a section of the notebook that
+/// doesn't exist. Links to other notebooks are not checked.
+pub fn validate_notebook(notebook: &Notebook) -> Report {
+    let mut sections = HashMap::new();
+    collect_link_targets(notebook.entries(), &mut sections);
+
+    let mut validator = Validator::new(sections, LinkScope::Notebook);
+    validator.check_entries(notebook.entries(), notebook.orphaned_sections());
+
+    validator.into_report()
+}
+
+/// Check a parsed section.
+///
+/// This works like [`validate_notebook`] for a single section. Only links
+/// within the section are checked.
+pub fn validate_section(section: &Section) -> Report {
+    let mut validator = Validator::new(
+        HashMap::from([(section.id, page_ids(section))]),
+        LinkScope::Section,
+    );
+    validator.check_section(section);
+
+    validator.into_report()
+}
+
+/// Check the object spaces of a section file.
+///
+/// This reports deleted pages that are still stored in the file and
+/// unsupported schema revisions of the section and its pages. It also works
+/// for sections that the parser fails to parse.
+///
+/// ```no_run
+/// use onenote_parser::raw::RawFile;
+/// use onenote_parser::validation::validate_store;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("Section.one")?;
+/// let file = RawFile::parse(&data)?;
+///
+/// for issue in validate_store(&file.store()?).issues() {
+///     println!("{issue}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "raw")]
+pub fn validate_store(store: &crate::raw::Store) -> Report {
+    use crate::one::property_set::{PropertySetId, page_metadata, section_metadata_node};
+
+    let mut validator = Validator::new(HashMap::new(), LinkScope::Section);
+
+    let section_metadata = store
+        .data_root()
+        .metadata_root()
+        .and_then(|id| store.data_root().object(id))
+        .filter(|object| object.jcid() == PropertySetId::SectionMetadata.as_jcid().0)
+        .and_then(|object| section_metadata_node::parse(object.data()).ok());
+    if let Some(metadata) = section_metadata {
+        validator.check_schema_revision(metadata.schema_revision_in_order_to_read);
+    }
+
+    for (_, space) in store.object_spaces() {
+        let metadata = space
+            .metadata_root()
+            .and_then(|id| space.object(id))
+            .filter(|object| object.jcid() == PropertySetId::PageMetadata.as_jcid().0)
+            .and_then(|object| page_metadata::parse(object.data()).ok());
+        let Some(metadata) = metadata else {
+            continue;
+        };
+
+        let title = metadata.cached_title;
+        validator.nested(format!("page {title:?}"), |validator| {
+            if let Some(revision) = metadata.schema_revision_in_order_to_read {
+                validator.check_schema_revision(revision);
+            }
+            if metadata.is_deleted {
+                validator.report(IssueKind::DeletedPage {
+                    title: title.clone(),
+                });
+            }
+        });
+    }
+
+    validator.into_report()
+}
+
+/// Which links can be resolved by a validator.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum LinkScope {
+    /// Links to other sections of the same notebook can be resolved.
+    Notebook,
+    /// Only links within the same section can be resolved.
+    Section,
+}
+
+struct Validator {
+    /// The IDs of the pages of each section, by section ID.
+    sections: HashMap<Guid, HashSet<Guid>>,
+    scope: LinkScope,
+    location: Vec<String>,
+    issues: Vec<Issue>,
+}
+
+impl Validator {
+    fn new(sections: HashMap<Guid, HashSet<Guid>>, scope: LinkScope) -> Validator {
+        Validator {
+            sections,
+            scope,
+            location: vec![],
+            issues: vec![],
+        }
+    }
+
+    fn into_report(self) -> Report {
+        Report {
+            issues: self.issues,
+        }
+    }
+
+    fn report(&mut self, kind: IssueKind) {
+        self.issues.push(Issue {
+            kind,
+            location: self.location.clone(),
+        });
+    }
+
+    fn nested(&mut self, segment: String, f: impl FnOnce(&mut Validator)) {
+        self.location.push(segment);
+        f(self);
+        self.location.pop();
+    }
+
+    fn check_entries(&mut self, entries: &[SectionEntry], orphaned_sections: &[PathBuf]) {
+        for path in orphaned_sections {
+            self.report(IssueKind::OrphanedSection { path: path.clone() });
+        }
+
+        for entry in entries {
+            match entry {
+                SectionEntry::Section(section) => self.nested(
+                    format!("section {:?}", section.display_name()),
+                    |validator| validator.check_section(section),
+                ),
+                SectionEntry::SectionGroup(group) => self.nested(
+                    format!("section group {:?}", group.display_name()),
+                    |validator| validator.check_entries(group.entries(), group.orphaned_sections()),
+                ),
+                SectionEntry::Missing(entry) => self.report(IssueKind::MissingTocTarget {
+                    file_name: entry.file_name().to_string(),
+                }),
+            }
+        }
+    }
+
+    fn check_section(&mut self, section: &Section) {
+        self.check_schema_revision(section.schema_revision_in_order_to_read());
+
+        for (index, series) in section.page_series().iter().enumerate() {
+            self.nested(format!("page series {index}"), |validator| {
+                for (index, page) in series.pages().iter().enumerate() {
+                    validator.nested(format!("page {index}"), |validator| {
+                        validator.check_page(page)
+                    });
+                }
+            });
+        }
+    }
+
+    fn check_schema_revision(&mut self, revision: u32) {
+        if revision > SUPPORTED_SCHEMA_REVISION {
+            self.report(IssueKind::UnsupportedSchemaRevision { revision });
+        }
+    }
+
+    fn check_page(&mut self, page: &Page) {
+        if page.is_conflict_page() {
+            self.report(IssueKind::ConflictPage {
+                title: page.title_text().unwrap_or_default().to_string(),
+            });
+        }
+
+        if let Some(title) = page.title() {
+            for outline in title.contents() {
+                self.check_outline_items(outline.items());
+            }
+        }
+
+        for content in page.contents() {
+            match content {
+                PageContent::Outline(outline) => self.check_outline_items(outline.items()),
+                PageContent::Image(image) => self.check_image(image),
+                PageContent::EmbeddedFile(file) => self.check_embedded_file(file),
+                PageContent::Ink(_) | PageContent::Unknown(_) => {}
+            }
+        }
+    }
+
+    fn check_outline_items(&mut self, items: &[OutlineItem]) {
+        for item in items {
+            match item {
+                OutlineItem::Group(group) => self.check_outline_items(group.outlines()),
+                OutlineItem::Element(element) => self.check_outline_element(element),
+            }
+        }
+    }
+
+    fn check_outline_element(&mut self, element: &OutlineElement) {
+        for content in element.contents() {
+            match content {
+                Content::RichText(text) => {
+                    for url in hyperlinks(text.text()) {
+                        self.check_link(url);
+                    }
+                }
+                Content::Table(table) => {
+                    for cell in table.contents().iter().flat_map(|row| row.contents()) {
+                        for element in cell.contents() {
+                            self.check_outline_element(element);
+                        }
+                    }
+                }
+                Content::Image(image) => self.check_image(image),
+                Content::EmbeddedFile(file) => self.check_embedded_file(file),
+                Content::Ink(_) | Content::Unknown(_) => {}
+            }
+        }
+
+        self.check_outline_items(element.children());
+    }
+
+    fn check_image(&mut self, image: &Image) {
+        if image.data().is_none() {
+            self.report(IssueKind::ImageWithoutData);
+        }
+
+        if let Some(url) = image.hyperlink_url() {
+            self.check_link(url);
+        }
+    }
+
+    fn check_embedded_file(&mut self, file: &EmbeddedFile) {
+        if file.data().is_empty() {
+            self.report(IssueKind::EmptyEmbeddedFile {
+                file_name: file.filename().to_string(),
+            });
+        }
+    }
+
+    fn check_link(&mut self, url: &str) {
+        let Some(link) = InternalLink::parse(url) else {
+            return;
+        };
+        let Some(section_id) = link.section_id else {
+            return;
+        };
+
+        let exists = match self.sections.get(&section_id) {
+            Some(pages) => link.page_id.is_none_or(|page_id| pages.contains(&page_id)),
+            None => !link.is_within(self.scope),
+        };
+
+        if !exists {
+            self.report(IssueKind::BrokenLink {
+                url: url.to_string(),
+            });
+        }
+    }
+}
+
+/// Collect the IDs of the sections and their pages.
+fn collect_link_targets(entries: &[SectionEntry], sections: &mut HashMap<Guid, HashSet<Guid>>) {
+    for entry in entries {
+        match entry {
+            SectionEntry::Section(section) => {
+                sections.insert(section.id, page_ids(section));
+            }
+            SectionEntry::SectionGroup(group) => collect_link_targets(group.entries(), sections),
+            SectionEntry::Missing(_) => {}
+        }
+    }
+}
+
+fn page_ids(section: &Section) -> HashSet<Guid> {
+    section
+        .page_series()
+        .iter()
+        .flat_map(|series| series.pages())
+        .map(|page| page.id)
+        .collect()
+}
+
+/// Find the URLs of the hyperlinks in a paragraph's text.
+///
+/// Hyperlinks are stored as `HYPERLINK "<url>"` fields in the text.
+fn hyperlinks(text: &str) -> impl Iterator<Item = &str> {
+    const FIELD: &str = "\u{fddf}HYPERLINK \"";
+
+    text.match_indices(FIELD).filter_map(|(start, _)| {
+        let url = &text[start + FIELD.len()..];
+
+        url.find('"').map(|end| &url[..end])
+    })
+}
+
+/// A `onenote:` link to a section or page.
+#[derive(Debug, PartialEq)]
+struct InternalLink<'a> {
+    /// The path of the linked section file, empty for links within a section.
+    path: &'a str,
+    section_id: Option<Guid>,
+    page_id: Option<Guid>,
+}
+
+impl<'a> InternalLink<'a> {
+    fn parse(url: &'a str) -> Option<InternalLink<'a>> {
+        let scheme = url.get(..8)?;
+        if !scheme.eq_ignore_ascii_case("onenote:") {
+            return None;
+        }
+
+        let (path, params) = url[8..].split_once('#').unwrap_or((&url[8..], ""));
+
+        let mut link = InternalLink {
+            path,
+            section_id: None,
+            page_id: None,
+        };
+        for param in params.split('&') {
+            match param.split_once('=') {
+                Some(("section-id", id)) => link.section_id = parse_guid(id),
+                Some(("page-id", id)) => link.page_id = parse_guid(id),
+                _ => {}
+            }
+        }
+
+        Some(link)
+    }
+
+    /// Whether the link target is within the scope of the validated notebook
+    /// or section.
+    fn is_within(&self, scope: LinkScope) -> bool {
+        let is_relative = !self.path.contains(':') && !self.path.starts_with(['/', '\\']);
+
+        match scope {
+            LinkScope::Notebook => is_relative,
+            LinkScope::Section => self.path.is_empty(),
+        }
+    }
+}
+
+fn parse_guid(value: &str) -> Option<Guid> {
+    let value = value.replace("%7B", "{").replace("%7D", "}");
+
+    Guid::from_str(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        InternalLink, IssueKind, LinkScope, Severity, Validator, hyperlinks, validate_notebook,
+        validate_section,
+    };
+    use crate::Parser;
+    use crate::onenote::content::Content;
+    use crate::onenote::outline::OutlineItem;
+    use crate::onenote::page_content::PageContent;
+    use crate::shared::guid::Guid;
+    use std::collections::{HashMap, HashSet};
+    use std::path::{Path, PathBuf};
+
+    fn guid(value: u8) -> Guid {
+        Guid::from_str(&format!("00000000-0000-0000-0000-0000000000{value:02X}")).unwrap()
+    }
+
+    #[test]
+    fn test_validate_notebook() {
+        let notebook = Parser::new()
+            .parse_notebook(Path::new("tests/samples/non-legacy/Open Notebook.onetoc2"))
+            .unwrap();
+
+        let report = validate_notebook(&notebook);
+        assert_eq!(report.issues().len(), 1, "{report:?}");
+        assert_eq!(
+            report.issues()[0].kind(),
+            &IssueKind::MissingTocTarget {
+                file_name: "New Section Group".to_string()
+            }
+        );
+        assert_eq!(report.max_severity(), Some(Severity::Error));
+        assert_eq!(
+            report.issues()[0].to_string(),
+            "error: table of contents entry \"New Section Group\" doesn't exist"
+        );
+
+        let notebook = Parser::new()
+            .parse_notebook(Path::new("tests/samples/Open Notebook.onetoc2"))
+            .unwrap();
+
+        let report = validate_notebook(&notebook);
+        let kinds: Vec<_> = report.issues().iter().map(|issue| issue.kind()).collect();
+        assert_eq!(
+            kinds,
+            [&IssueKind::OrphanedSection {
+                path: PathBuf::from("tests/samples/Schnelle Notizen.one")
+            }]
+        );
+        assert_eq!(report.issues_with(Severity::Error).count(), 0);
+    }
+
+    #[test]
+    fn test_check_links() {
+        let sections = HashMap::from([
+            (guid(1), HashSet::from([guid(10)])),
+            (guid(2), HashSet::new()),
+        ]);
+        let link = |section: u8, page: u8| {
+            format!(
+                "onenote:Notes.one#Page&section-id={}&page-id={}&end",
+                guid(section),
+                guid(page)
+            )
+        };
+        let external = format!(
+            "onenote:https://example.com/Notes.one#Page&section-id={}&end",
+            guid(3)
+        );
+
+        let mut validator = Validator::new(sections.clone(), LinkScope::Notebook);
+        for url in [&link(1, 10), &link(2, 10), &link(3, 10), &external] {
+            validator.check_link(url);
+        }
+        validator.check_link("https://example.com");
+        validator.check_link("onenote:Notes.one#Page&end");
+
+        let report = validator.into_report();
+        let urls: Vec<_> = report
+            .issues()
+            .iter()
+            .map(|issue| match issue.kind() {
+                IssueKind::BrokenLink { url } => url.as_str(),
+                kind => panic!("unexpected issue {kind:?}"),
+            })
+            .collect();
+        assert_eq!(urls, [link(2, 10), link(3, 10)]);
+
+        // Links to other sections can't be resolved when checking a section.
+        let mut validator = Validator::new(sections, LinkScope::Section);
+        validator.check_link(&link(3, 10));
+        assert!(validator.into_report().is_empty());
+    }
+
+    #[test]
+    fn test_check_schema_revision() {
+        let mut validator = Validator::new(HashMap::new(), LinkScope::Section);
+        validator.check_schema_revision(40);
+        validator.nested("page 0".to_string(), |validator| {
+            validator.check_schema_revision(41)
+        });
+
+        let report = validator.into_report();
+        assert_eq!(report.issues().len(), 1);
+        assert_eq!(
+            report.issues()[0].kind(),
+            &IssueKind::UnsupportedSchemaRevision { revision: 41 }
+        );
+        assert_eq!(report.issues()[0].location(), ["page 0"]);
+    }
+
+    #[test]
+    #[cfg(feature = "raw")]
+    fn test_validate_store() {
+        use super::validate_store;
+        use crate::raw::RawFile;
+
+        let data = std::fs::read("tests/samples/Schnelle Notizen.one").unwrap();
+        let file = RawFile::parse(&data).unwrap();
+
+        let report = validate_store(&file.store().unwrap());
+        assert_eq!(report.issues().len(), 1, "{report:?}");
+        assert_eq!(
+            report.issues()[0].kind(),
+            &IssueKind::DeletedPage {
+                title: "Without_image".to_string()
+            }
+        );
+        assert_eq!(report.issues()[0].location(), ["page \"Without_image\""]);
+        assert_eq!(report.max_severity(), Some(Severity::Info));
+    }
+
+    #[test]
+    fn test_validate_section_conflict_page() {
+        use crate::fsshttpb::writer::Value;
+        use crate::one::property::PropertyType;
+        use crate::one::property_set::PropertySetId;
+        use crate::test_support::{ObjectBuilder, SpaceBuilder, id, section_store};
+
+        // A page whose page node or another object is flagged as a conflict.
+        let page = |value: u32, page_node_flag: bool, other_flag: bool| {
+            SpaceBuilder::new(value)
+                .serial(1)
+                .metadata_root(
+                    ObjectBuilder::new(id(1), PropertySetId::PageMetadata)
+                        .prop(
+                            PropertyType::NotebookManagementEntityGuid,
+                            Value::Vec(vec![0; 16]),
+                        )
+                        .string(PropertyType::CachedTitleString, &format!("Page {value}"))
+                        .prop(PropertyType::TopologyCreationTimeStamp, Value::U64(0)),
+                )
+                .content_root(
+                    ObjectBuilder::new(id(2), PropertySetId::PageManifestNode)
+                        .object_refs(PropertyType::ContentChildNodes, &[id(3)]),
+                )
+                .object(
+                    ObjectBuilder::new(id(3), PropertySetId::PageNode)
+                        .prop(PropertyType::IsConflictPage, Value::Bool(page_node_flag))
+                        .object_refs(PropertyType::ElementChildNodes, &[id(4)]),
+                )
+                .object(
+                    ObjectBuilder::with_jcid(id(4), 0x0006_0099)
+                        .prop(PropertyType::IsConflictPage, Value::Bool(other_flag)),
+                )
+        };
+
+        let data = section_store(vec![page(10, true, false), page(11, false, true)]);
+        let section = Parser::new()
+            .parse_section_buffer(&data, Path::new("Synthetic.one"))
+            .unwrap();
+
+        let pages = section.page_series()[0].pages();
+        assert!(pages[0].is_conflict_page());
+        assert!(!pages[1].is_conflict_page());
+
+        let report = validate_section(&section);
+        assert_eq!(report.issues().len(), 1, "{report:?}");
+        assert_eq!(
+            report.issues()[0].kind(),
+            &IssueKind::ConflictPage {
+                title: String::new()
+            }
+        );
+        assert_eq!(report.issues()[0].location(), ["page series 0", "page 0"]);
+        assert_eq!(report.max_severity(), Some(Severity::Warning));
+    }
+
+    #[test]
+    fn test_hyperlinks() {
+        let text = "See \u{fddf}HYPERLINK \"https://example.com\"here and \
+                    \u{fddf}HYPERLINK \"onenote:#Page\"there.";
+
+        assert_eq!(
+            hyperlinks(text).collect::<Vec<_>>(),
+            ["https://example.com", "onenote:#Page"]
+        );
+        assert_eq!(hyperlinks("\u{fddf}HYPERLINK \"unterminated").count(), 0);
+    }
+
+    #[test]
+    fn test_hyperlinks_in_parsed_text() {
+        fn collect_texts(items: &[OutlineItem], texts: &mut Vec<String>) {
+            for item in items {
+                match item {
+                    OutlineItem::Group(group) => collect_texts(group.outlines(), texts),
+                    OutlineItem::Element(element) => {
+                        for content in element.contents() {
+                            if let Content::RichText(text) = content {
+                                texts.push(text.text().to_string());
+                            }
+                        }
+                        collect_texts(element.children(), texts);
+                    }
+                }
+            }
+        }
+
+        let section = Parser::new()
+            .parse_section(Path::new("tests/samples/New Section 1.one"))
+            .unwrap();
+
+        let mut texts = vec![];
+        for page in section
+            .page_series()
+            .iter()
+            .flat_map(|series| series.pages())
+        {
+            for content in page.contents() {
+                if let PageContent::Outline(outline) = content {
+                    collect_texts(outline.items(), &mut texts);
+                }
+            }
+        }
+
+        let text = texts
+            .iter()
+            .find(|text| text.contains("\u{fddf}HYPERLINK \""))
+            .expect("sample has no hyperlink");
+        assert_eq!(
+            hyperlinks(text).collect::<Vec<_>>(),
+            ["https://example.com"]
+        );
+        assert!(validate_section(&section).is_empty());
+    }
+
+    #[test]
+    fn test_parse_internal_link() {
+        let section = Guid::from_str("{6A1C9B7E-1D33-4E2A-9E0F-2B1D4A0C5E11}").unwrap();
+        let page = Guid::from_str("{0F3E5D2C-8B7A-4C6D-9E1F-A2B3C4D5E6F7}").unwrap();
+
+        let link = InternalLink::parse(
+            "onenote:Notes.one#Page&section-id={6A1C9B7E-1D33-4E2A-9E0F-2B1D4A0C5E11}\
+             &page-id=%7B0F3E5D2C-8B7A-4C6D-9E1F-A2B3C4D5E6F7%7D&end",
+        )
+        .unwrap();
+        assert_eq!(
+            link,
+            InternalLink {
+                path: "Notes.one",
+                section_id: Some(section),
+                page_id: Some(page),
+            }
+        );
+        assert!(link.is_within(LinkScope::Notebook));
+        assert!(!link.is_within(LinkScope::Section));
+
+        let link = InternalLink::parse("ONENOTE:#Page&section-id=invalid&end").unwrap();
+        assert_eq!(link.section_id, None);
+        assert!(link.is_within(LinkScope::Section));
+
+        let link = InternalLink::parse("onenote:https://example.com/Notes.one#Page").unwrap();
+        assert!(!link.is_within(LinkScope::Notebook));
+
+        assert_eq!(InternalLink::parse("https://example.com"), None);
+        assert_eq!(InternalLink::parse("onenote"), None);
+    }
+}
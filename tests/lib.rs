@@ -34,6 +34,19 @@ fn test_parse_section_with_image_missing_last_modified() {
     assert_debug_snapshot!(parser.parse_section(&path).unwrap());
 }
 
+#[test]
+#[cfg(feature = "mmap")]
+fn test_parse_section_mmap() {
+    let path = PathBuf::from("tests/samples/Schnelle Notizen.one");
+
+    let parser = Parser::new();
+    let source = unsafe { onenote_parser::source::MmapSource::new() };
+    let mapped = parser.parse_section_from(&source, &path).unwrap();
+    let read = parser.parse_section(&path).unwrap();
+
+    assert_eq!(format!("{mapped:?}"), format!("{read:?}"));
+}
+
 #[test]
 fn test_readme_example_parse_notebook() {
     let parser = Parser::new();